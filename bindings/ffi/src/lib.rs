@@ -0,0 +1,2 @@
+// exported symbols of the ffi module are linked into the library
+pub use geocode::ffi::*;
@@ -0,0 +1,4 @@
+fn main() {
+    // node addons resolve napi symbols from the host process at load time
+    napi_build::setup();
+}
@@ -0,0 +1,2 @@
+// exported symbols of the node module are linked into the addon
+pub use geocode::node::*;
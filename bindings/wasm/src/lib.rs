@@ -0,0 +1,2 @@
+// exported symbols of the wasm module are linked into the library
+pub use geocode::wasm::*;
@@ -0,0 +1,86 @@
+#ifndef GEOCODE_H
+#define GEOCODE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* geocode types */
+#define GEOCODE_GEOHASH 0
+#define GEOCODE_GEOHASH16 1
+#define GEOCODE_GEOHASH36 2
+#define GEOCODE_MAIDENHEAD 3
+#define GEOCODE_PLUSCODE 4
+#define GEOCODE_QUADTILE 5
+
+/* status codes, failures record a message for geocode_last_error */
+#define GEOCODE_OK 0
+#define GEOCODE_ERROR -1
+#define GEOCODE_ERROR_NULL_POINTER -2
+#define GEOCODE_ERROR_INVALID_TYPE -3
+#define GEOCODE_ERROR_INVALID_UTF8 -4
+#define GEOCODE_ERROR_BUFFER_TOO_SMALL -5
+#define GEOCODE_ERROR_PANIC -6
+
+/* null pointers are reported with GEOCODE_ERROR_NULL_POINTER, while any
+ * other pointer must be valid for the access described by its function.
+ * the library never retains pointers passed to it. */
+
+typedef struct {
+    double min_x;
+    double max_x;
+    double min_y;
+    double max_y;
+} geocode_bbox_t;
+
+/* strings are allocated by the library and released with
+ * geocode_strings_free */
+typedef struct {
+    char **data;
+    size_t len;
+} geocode_strings_t;
+
+/* cover a bounding box with cells, where min_x > max_x crosses the
+ * antimeridian. bbox must point to a readable geocode_bbox_t and out to a
+ * writable geocode_strings_t, whose previous contents are overwritten
+ * without being released. the caller owns the strings written to out and
+ * releases them with geocode_strings_free. */
+int32_t geocode_cover_bbox(uint32_t geocode, const geocode_bbox_t *bbox,
+    size_t precision, geocode_strings_t *out);
+
+/* decode a code into the bounds of its cell. code must point to a nul
+ * terminated string, which is rejected unless it is valid utf-8, and out
+ * to a writable geocode_bbox_t. */
+int32_t geocode_decode(uint32_t geocode, const char *code,
+    geocode_bbox_t *out);
+
+/* encode a coordinate into buf as a nul terminated code. buf must point to
+ * at least buf_len writable bytes, which are owned by the caller and must
+ * leave room for the terminator. */
+int32_t geocode_encode(uint32_t geocode, double x, double y,
+    size_t precision, char *buf, size_t buf_len);
+
+/* message describing the last failure on the calling thread, valid until
+ * the next failure on that thread. the message is owned by the library
+ * and must not be freed. */
+const char *geocode_last_error(void);
+
+/* compute the neighbors of a code. code must point to a nul terminated
+ * utf-8 string and out to a writable geocode_strings_t, which is owned by
+ * the caller as with geocode_cover_bbox. */
+int32_t geocode_neighbors(uint32_t geocode, const char *code,
+    geocode_strings_t *out);
+
+/* release strings returned by the library, resetting them to empty.
+ * strings must be null, empty or filled by the library, and their data
+ * must not be modified or released by any other means. */
+void geocode_strings_free(geocode_strings_t *strings);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
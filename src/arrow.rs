@@ -0,0 +1,167 @@
+use crate::{cellid, Geocode, GeocodeError};
+
+use arrow_array::{Array, Float64Array, StringArray, UInt64Array};
+use arrow_array::builder::{Float64Builder, StringBuilder, UInt64Builder};
+
+impl Geocode {
+    pub fn decode_arrow(&self, values: &StringArray)
+            -> Result<(Float64Array, Float64Array), GeocodeError> {
+        // decode cell centers, where null codes produce null coordinates
+        let mut xs = Float64Builder::with_capacity(values.len());
+        let mut ys = Float64Builder::with_capacity(values.len());
+        for value in values.iter() {
+            match value {
+                Some(value) => {
+                    let (x, y, _, _) = self.decode_center(value)?;
+                    xs.append_value(x);
+                    ys.append_value(y);
+                },
+                None => {
+                    xs.append_null();
+                    ys.append_null();
+                },
+            }
+        }
+
+        Ok((xs.finish(), ys.finish()))
+    }
+
+    pub fn decode_arrow_u64(&self, ids: &UInt64Array)
+            -> Result<(Float64Array, Float64Array), GeocodeError> {
+        let mut xs = Float64Builder::with_capacity(ids.len());
+        let mut ys = Float64Builder::with_capacity(ids.len());
+        for id in ids.iter() {
+            match id {
+                Some(id) => {
+                    let code = self.u64_to_code(id)?;
+                    let (x, y, _, _) = self.decode_center(&code)?;
+                    xs.append_value(x);
+                    ys.append_value(y);
+                },
+                None => {
+                    xs.append_null();
+                    ys.append_null();
+                },
+            }
+        }
+
+        Ok((xs.finish(), ys.finish()))
+    }
+
+    pub fn encode_arrow(&self, xs: &Float64Array, ys: &Float64Array,
+            precision: usize) -> Result<StringArray, GeocodeError> {
+        check_lengths(xs, ys)?;
+
+        // codes are written directly into the value buffer, where rows
+        // with a null coordinate produce a null code
+        let mut codes = StringBuilder::with_capacity(xs.len(),
+            xs.len() * precision);
+        for (x, y) in xs.iter().zip(ys.iter()) {
+            match (x, y) {
+                (Some(x), Some(y)) => {
+                    self.encode_into(x, y, precision, &mut codes)?;
+                    codes.append_value("");
+                },
+                _ => codes.append_null(),
+            }
+        }
+
+        Ok(codes.finish())
+    }
+
+    pub fn encode_arrow_u64(&self, xs: &Float64Array, ys: &Float64Array,
+            precision: usize) -> Result<UInt64Array, GeocodeError> {
+        check_lengths(xs, ys)?;
+
+        // check if precision fits within cell ids
+        let (char_bits, chars) = self.get_id_parameters()?;
+        if precision == 0 || precision > self.get_max_id_precision()? {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // a single code buffer is reused across rows
+        let mut ids = UInt64Builder::with_capacity(xs.len());
+        let mut code = String::with_capacity(precision);
+        for (x, y) in xs.iter().zip(ys.iter()) {
+            match (x, y) {
+                (Some(x), Some(y)) => {
+                    code.clear();
+                    self.encode_into(x, y, precision, &mut code)?;
+                    ids.append_value(cellid::pack_id(&code, char_bits,
+                        chars));
+                },
+                _ => ids.append_null(),
+            }
+        }
+
+        Ok(ids.finish())
+    }
+}
+
+fn check_lengths(xs: &Float64Array, ys: &Float64Array)
+        -> Result<(), GeocodeError> {
+    match xs.len() == ys.len() {
+        true => Ok(()),
+        false => Err(GeocodeError::InvalidFormat { reason: format!(
+            "coordinate arrays of length {} and {} differ",
+            xs.len(), ys.len()) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    use arrow_array::{Array, Float64Array, StringArray, UInt64Array};
+
+    #[test]
+    fn decode_arrow() {
+        let codes = StringArray::from(vec![Some("dp"), None, Some("9q")]);
+        let (xs, ys) = Geocode::Geohash.decode_arrow(&codes).unwrap();
+        assert_eq!(xs, Float64Array::from(vec![Some(-84.375), None,
+            Some(-118.125)]));
+        assert_eq!(ys.value(0), 42.1875);
+        assert!(ys.is_null(1));
+
+        let ids = UInt64Array::from(vec![None,
+            Some(Geocode::Geohash.code_to_u64("dp").unwrap())]);
+        let (xs, _) = Geocode::Geohash.decode_arrow_u64(&ids).unwrap();
+        assert_eq!(xs, Float64Array::from(vec![None, Some(-84.375)]));
+
+        assert!(Geocode::Geohash.decode_arrow(&StringArray::from(
+            vec!["dpa"])).is_err());
+        assert!(Geocode::Geohash.decode_arrow_u64(&UInt64Array::from(
+            vec![0])).is_err());
+    }
+
+    #[test]
+    fn encode_arrow() {
+        let xs = Float64Array::from(vec![Some(-88.4), None, Some(-122.4)]);
+        let ys = Float64Array::from(vec![Some(44.266667), Some(0.0),
+            Some(37.8)]);
+        let codes = Geocode::Geohash.encode_arrow(&xs, &ys, 6).unwrap();
+        assert_eq!(codes, StringArray::from(vec![Some("dpc5u6"), None,
+            Some("9q8zn9")]));
+
+        // pluscodes are formatted without bisection
+        let codes = Geocode::PlusCode.encode_arrow(&xs, &ys, 8).unwrap();
+        assert_eq!(codes.value(0),
+            Geocode::PlusCode.encode(-88.4, 44.266667, 8).unwrap());
+
+        let ids = Geocode::QuadTile.encode_arrow_u64(
+            &Float64Array::from(vec![-9840642.99]),
+            &Float64Array::from(vec![5506802.68]), 12).unwrap();
+        assert_eq!(ids.value(0), Geocode::QuadTile.code_to_u64(
+            &Geocode::QuadTile.encode(-9840642.99, 5506802.68, 12).unwrap())
+            .unwrap());
+
+        assert!(Geocode::Geohash.encode_arrow(&xs,
+            &Float64Array::from(vec![0.0]), 6).is_err());
+        assert!(Geocode::Geohash.encode_arrow(&Float64Array::from(vec![
+            -188.4]), &Float64Array::from(vec![0.0]), 6).is_err());
+        assert_eq!(Geocode::PlusCode.encode_arrow_u64(&xs, &ys, 8),
+            Err(GeocodeError::UnsupportedGeocode {
+                geocode: Geocode::PlusCode }));
+        assert!(Geocode::Geohash.encode_arrow_u64(&xs, &ys, 13).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+impl Geocode {
+    pub fn decode_batch<T: AsRef<str>>(&self, values: &[T])
+            -> Result<Vec<BoundingBox>, GeocodeError> {
+        let mut bboxes = Vec::with_capacity(values.len());
+        for value in values.iter() {
+            bboxes.push(self.decode(value.as_ref())?);
+        }
+
+        Ok(bboxes)
+    }
+
+    pub fn encode_batch(&self, points: &[(f64, f64)], precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        let mut codes = Vec::with_capacity(points.len());
+        self.encode_batch_into(points, precision, &mut codes)?;
+        Ok(codes)
+    }
+
+    pub fn encode_batch_into(&self, points: &[(f64, f64)],
+            precision: usize, codes: &mut Vec<String>)
+            -> Result<(), GeocodeError> {
+        // check if precision is valid and retrieve parameters once
+        let parameters = self.get_parameters();
+        if parameters.is_some() && precision == 0 {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // existing strings are reused, on error the contents of codes
+        // are unspecified
+        codes.truncate(points.len());
+        while codes.len() < points.len() {
+            codes.push(String::with_capacity(precision));
+        }
+
+        #[allow(unused_mut)]
+        let mut start = 0;
+        #[cfg(feature = "simd")]
+        if let Some((char_bits, chars)) = parameters {
+            start = crate::simd::encode_batch(self, points, precision,
+                char_bits, chars, codes)?;
+        }
+
+        for ((x, y), out) in points.iter().zip(codes.iter_mut()).skip(start) {
+            out.clear();
+            self.encode_into(*x, *y, precision, out)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn decode_batch() {
+        let geocode = Geocode::Geohash;
+        let bboxes = geocode.decode_batch(&["dpc5u6", "9xjq8zs6"]).unwrap();
+        assert_eq!(bboxes, vec![geocode.decode("dpc5u6").unwrap(),
+            geocode.decode("9xjq8zs6").unwrap()]);
+
+        let codes = vec!["dpc5u6".to_string(), "dpca".to_string()];
+        assert_eq!(geocode.decode_batch(&codes),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 3 }));
+        assert!(geocode.decode_batch::<&str>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn encode_batch() {
+        let points = [(-88.4, 44.266667), (-105.078056, 40.559167)];
+        for geocode in [Geocode::Geohash, Geocode::Geohash16,
+                Geocode::Geohash36, Geocode::Maidenhead,
+                Geocode::PlusCode].iter() {
+            let codes = geocode.encode_batch(&points, 8).unwrap();
+            for ((x, y), code) in points.iter().zip(codes.iter()) {
+                assert_eq!(&geocode.encode(*x, *y, 8).unwrap(), code);
+            }
+        }
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_batch(&points, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+        assert!(geocode.encode_batch(&[(0.0, 0.0), (0.0, 91.0)], 6)
+            .is_err());
+        assert!(geocode.encode_batch(&[], 6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn encode_batch_into() {
+        let geocode = Geocode::Geohash;
+        let mut codes = vec!["stale".to_string(); 3];
+        geocode.encode_batch_into(&[(-88.4, 44.266667)], 6, &mut codes)
+            .unwrap();
+        assert_eq!(codes, vec!["dpc5u6"]);
+
+        geocode.encode_batch_into(&[(-88.4, 44.266667),
+            (-105.078056, 40.559167)], 8, &mut codes).unwrap();
+        assert_eq!(codes, vec!["dpc5u6t0", "9xjq8zs6"]);
+    }
+}
@@ -0,0 +1,70 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[repr(C)]
+pub struct BoundingBox {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    pub const fn new(min_x: f64, max_x: f64,
+            min_y: f64, max_y: f64) -> BoundingBox {
+        BoundingBox { min_x, max_x, min_y, max_y }
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        ((self.min_x + self.max_x) / 2f64, (self.min_y + self.max_y) / 2f64)
+    }
+
+    pub const fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x
+            && y >= self.min_y && y <= self.max_y
+    }
+
+    pub const fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min_x <= other.max_x && self.max_x >= other.min_x
+            && self.min_y <= other.max_y && self.max_y >= other.min_y
+    }
+
+    pub const fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundingBox;
+
+    #[test]
+    fn dimensions() {
+        let bbox = BoundingBox::new(-10.0, 30.0, 5.0, 15.0);
+        assert_eq!(bbox.center(), (10.0, 10.0));
+        assert_eq!(bbox.width(), 40.0);
+        assert_eq!(bbox.height(), 10.0);
+    }
+
+    #[test]
+    fn contains() {
+        let bbox = BoundingBox::new(-10.0, 30.0, 5.0, 15.0);
+        assert!(bbox.contains(0.0, 10.0));
+        assert!(bbox.contains(-10.0, 15.0));
+        assert!(!bbox.contains(-10.5, 10.0));
+        assert!(!bbox.contains(0.0, 15.5));
+    }
+
+    #[test]
+    fn intersects() {
+        let bbox = BoundingBox::new(-10.0, 30.0, 5.0, 15.0);
+        assert!(bbox.intersects(&BoundingBox::new(0.0, 5.0, 0.0, 10.0)));
+        assert!(bbox.intersects(&BoundingBox::new(-20.0, 40.0, 0.0, 20.0)));
+        assert!(bbox.intersects(&BoundingBox::new(30.0, 40.0, 15.0, 20.0)));
+        assert!(!bbox.intersects(&BoundingBox::new(31.0, 40.0, 0.0, 10.0)));
+        assert!(!bbox.intersects(&BoundingBox::new(0.0, 5.0, -5.0, 4.0)));
+    }
+}
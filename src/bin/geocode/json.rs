@@ -0,0 +1,197 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+// minimal json values, sufficient for reading geojson geometries
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Array(Vec<Json>),
+    Bool(bool),
+    Null,
+    Number(f64),
+    Object(Vec<(String, Json)>),
+    String(String),
+}
+
+impl Json {
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter()
+                .find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(value: &str) -> Result<Json, String> {
+    let mut chars = value.chars().peekable();
+    let json = parse_value(&mut chars)?;
+
+    skip_whitespace(&mut chars);
+    match chars.next() {
+        Some(c) => Err(format!("unexpected character '{}'", c)),
+        None => Ok(json),
+    }
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: &str)
+        -> Result<(), String> {
+    for c in expected.chars() {
+        if chars.next() != Some(c) {
+            return Err(format!("expected '{}'", expected));
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<Json, String> {
+    let mut value = String::new();
+    while let Some(c) = chars.peek() {
+        match c {
+            '0'..='9' | '-' | '+' | '.' | 'e' | 'E' => value.push(*c),
+            _ => break,
+        }
+
+        chars.next();
+    }
+
+    value.parse().map(Json::Number)
+        .map_err(|_| format!("invalid number '{}'", value))
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect(chars, "\"")?;
+
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('b') => value.push('\u{8}'),
+                Some('f') => value.push('\u{c}'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    let c = u32::from_str_radix(&code, 16).ok()
+                        .and_then(char::from_u32)
+                        .ok_or(format!("invalid escape '\\u{}'", code))?;
+                    value.push(c);
+                },
+                Some(c) => value.push(c),
+                None => return Err("unterminated string".to_string()),
+            },
+            Some(c) => value.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Json, String> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('[') => {
+            chars.next();
+            let mut values = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Ok(Json::Array(values));
+            }
+
+            loop {
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => {},
+                    Some(']') => return Ok(Json::Array(values)),
+                    _ => return Err("expected ',' or ']'".to_string()),
+                }
+            }
+        },
+        Some('{') => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Ok(Json::Object(fields));
+            }
+
+            loop {
+                skip_whitespace(chars);
+                let name = parse_string(chars)?;
+                skip_whitespace(chars);
+                expect(chars, ":")?;
+                fields.push((name, parse_value(chars)?));
+
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => {},
+                    Some('}') => return Ok(Json::Object(fields)),
+                    _ => return Err("expected ',' or '}'".to_string()),
+                }
+            }
+        },
+        Some('"') => parse_string(chars).map(Json::String),
+        Some('t') => expect(chars, "true").map(|_| Json::Bool(true)),
+        Some('f') => expect(chars, "false").map(|_| Json::Bool(false)),
+        Some('n') => expect(chars, "null").map(|_| Json::Null),
+        Some(_) => parse_number(chars),
+        None => Err("unexpected end of input".to_string()),
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().map(|x| x.is_whitespace()).unwrap_or(false) {
+        chars.next();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+
+    #[test]
+    fn parse() {
+        let json = super::parse(" { \"type\": \"Point\", \"coordinates\": \
+            [-88.4, 44.266667e0], \"id\": null, \"valid\": true, \
+            \"name\": \"a\\\"b\\u0063\", \"empty\": {} } ").unwrap();
+        assert_eq!(json.get("type").and_then(|x| x.as_str()), Some("Point"));
+        assert_eq!(json.get("coordinates").unwrap(), &Json::Array(vec![
+            Json::Number(-88.4), Json::Number(44.266667)]));
+        assert_eq!(json.get("id"), Some(&Json::Null));
+        assert_eq!(json.get("valid"), Some(&Json::Bool(true)));
+        assert_eq!(json.get("name").and_then(|x| x.as_str()), Some("a\"bc"));
+        assert_eq!(json.get("empty"), Some(&Json::Object(Vec::new())));
+        assert_eq!(json.get("missing"), None);
+
+        assert!(super::parse("[1, 2").is_err());
+        assert!(super::parse("{\"a\" 1}").is_err());
+        assert!(super::parse("[1] 2").is_err());
+        assert!(super::parse("\"abc").is_err());
+        assert!(super::parse("").is_err());
+    }
+}
@@ -0,0 +1,805 @@
+mod json;
+
+use geocode::{BoundingBox, CoverMode, Direction, Geocode, GeocodeError,
+    Polygon, SortOptions};
+use geocode::project;
+use json::Json;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::process;
+use std::str::FromStr;
+
+const USAGE: &str = "usage: geocode <command> [options] [arguments]
+
+commands:
+    convert --from <geocode> --to <geocode> --precision <n> <code>...
+                                    re-encode cell centers in another geocode
+    cover --precision <n> [file]    cover geojson polygons read from a file
+                                    or stdin with cells
+    encode <lon> <lat> <precision>  encode a coordinate to a code
+    decode <code>...                decode codes to bounds or centers
+    neighbors <code>                print the neighbors of a cell
+    pipe --precision <precision>    append codes to delimited rows on stdin
+    ring <code>                     print cells within k rings of a cell
+    sort [file]                     sort delimited rows read from a file
+                                    or stdin by cell
+
+options:
+    --type <geocode>    geohash (default), geohash16, geohash36,
+                        maidenhead, pluscode, or quadtile
+    --format <format>   plain (default) or json
+    --center            print cell centers rather than bounds
+
+cover options:
+    --mode <mode>       intersecting (default), contained, or centroid
+    --compact           merge complete sibling groups into their parents
+
+ring options:
+    --k <k>             number of rings surrounding the cell (default 1)
+
+pipe options:
+    --delimiter <char>  field delimiter, ',' (default) or 'tab'
+    --lon-column <n>    zero-based longitude column (default 0)
+    --lat-column <n>    zero-based latitude column (default 1)
+    --header            copy the first row, appending a 'geocode' column
+    --skip-invalid      append an empty code to invalid rows rather than
+                        failing. fields are split without quoting
+
+sort options:
+    --column <n>        zero-based cell column (default 0)
+    --delimiter <char>  field delimiter, ',' (default) or 'tab'
+    --chunk-size <n>    rows sorted in memory before spilling to disk
+    --merge             merge the sorted file arguments instead
+    --unique            keep only the first row of each cell";
+
+// options which do not take a value
+const FLAGS: &[&str] = &["center", "compact", "header", "merge",
+    "skip-invalid", "unique"];
+
+#[derive(Debug)]
+enum CliError {
+    Geocode(GeocodeError),
+    Input { line: usize, reason: String },
+    Io(io::Error),
+    Usage(String),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CliError::Geocode(e) => write!(f, "{}", e),
+            CliError::Input { line, reason } =>
+                write!(f, "line {}: {}", line, reason),
+            CliError::Io(e) => write!(f, "{}", e),
+            CliError::Usage(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<GeocodeError> for CliError {
+    fn from(e: GeocodeError) -> CliError {
+        CliError::Geocode(e)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> CliError {
+        CliError::Io(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Json,
+    Plain,
+}
+
+struct Args {
+    options: HashMap<String, String>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    fn parse(values: &[String]) -> Result<Args, CliError> {
+        let (mut options, mut positional) = (HashMap::new(), Vec::new());
+        let mut iter = values.iter();
+        while let Some(value) = iter.next() {
+            // negative coordinates are positional, options use two dashes
+            let name = match value.strip_prefix("--") {
+                Some(name) => name,
+                None => {
+                    positional.push(value.clone());
+                    continue;
+                },
+            };
+
+            let (name, option) = match name.split_once('=') {
+                Some((name, option)) => (name, option.to_string()),
+                None if FLAGS.contains(&name) => (name, String::new()),
+                None => match iter.next() {
+                    Some(option) => (name, option.clone()),
+                    None => return Err(CliError::Usage(
+                        format!("missing value for option '--{}'", name))),
+                },
+            };
+
+            options.insert(name.to_string(), option);
+        }
+
+        Ok(Args { options, positional })
+    }
+
+    fn get_delimiter(&self) -> Result<char, CliError> {
+        match self.options.get("delimiter").map(|x| x.as_str()) {
+            None => Ok(','),
+            Some("tab") | Some("\\t") => Ok('\t'),
+            Some(value) if value.chars().count() == 1 =>
+                Ok(value.chars().next().unwrap()),
+            Some(value) => Err(CliError::Usage(
+                format!("invalid delimiter '{}'", value))),
+        }
+    }
+
+    fn get_format(&self) -> Result<Format, CliError> {
+        match self.options.get("format").map(|x| x.as_str()) {
+            None | Some("plain") => Ok(Format::Plain),
+            Some("json") => Ok(Format::Json),
+            Some(format) => Err(CliError::Usage(
+                format!("unknown format '{}'", format))),
+        }
+    }
+
+    fn get_geocode(&self) -> Result<Geocode, CliError> {
+        self.get_geocode_option("type", Some(Geocode::Geohash))
+    }
+
+    fn get_geocode_option(&self, name: &str, default: Option<Geocode>)
+            -> Result<Geocode, CliError> {
+        match self.options.get(name).map(|x| x.as_str()) {
+            None => default.ok_or_else(|| CliError::Usage(
+                format!("missing option '--{}'", name))),
+            Some(geocode) => geocode.parse().map_err(|_| CliError::Usage(
+                format!("unknown geocode type '{}'", geocode))),
+        }
+    }
+
+    fn get_option<T: FromStr>(&self, name: &str, default: Option<T>)
+            -> Result<T, CliError> {
+        match (self.options.get(name), default) {
+            (Some(value), _) => value.parse().map_err(|_| CliError::Usage(
+                format!("invalid value '{}' for option '--{}'", value, name))),
+            (None, Some(default)) => Ok(default),
+            (None, None) => Err(CliError::Usage(
+                format!("missing option '--{}'", name))),
+        }
+    }
+
+    fn get_positional<T: FromStr>(&self, index: usize, name: &str)
+            -> Result<T, CliError> {
+        let value = self.positional.get(index).ok_or_else(||
+            CliError::Usage(format!("missing argument <{}>", name)))?;
+        value.parse().map_err(|_| CliError::Usage(
+            format!("invalid {} '{}'", name, value)))
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+}
+
+fn convert<W: Write>(args: &Args, out: &mut W) -> Result<(), CliError> {
+    let from = args.get_geocode_option("from", None)?;
+    let to = args.get_geocode_option("to", None)?;
+    let format = args.get_format()?;
+    let precision: usize = args.get_option("precision", None)?;
+    if args.positional.is_empty() {
+        return Err(CliError::Usage("missing argument <code>".to_string()));
+    }
+
+    for code in args.positional.iter() {
+        let (lon, lat) = get_center(from, code)?;
+        let converted = to.encode_lonlat(lon, lat, precision)?;
+        match format {
+            Format::Json => writeln!(out, "{{\"code\":\"{}\",\
+                \"converted\":\"{}\"}}", code, converted)?,
+            Format::Plain => writeln!(out, "{}", converted)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn cover<R: BufRead, W: Write>(args: &Args, mut input: R, out: &mut W)
+        -> Result<(), CliError> {
+    let (geocode, format) = (args.get_geocode()?, args.get_format()?);
+    let precision: usize = args.get_option("precision", None)?;
+    let mode = match args.options.get("mode").map(|x| x.as_str()) {
+        None | Some("intersecting") => CoverMode::Intersecting,
+        Some("centroid") => CoverMode::Centroid,
+        Some("contained") => CoverMode::Contained,
+        Some(mode) => return Err(CliError::Usage(
+            format!("unknown cover mode '{}'", mode))),
+    };
+
+    // read geometry from the file argument or stdin
+    let text = match args.positional.first().map(|x| x.as_str()) {
+        None | Some("-") => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            text
+        },
+        Some(path) => fs::read_to_string(path)?,
+    };
+
+    let json = json::parse(&text).map_err(|e|
+        GeocodeError::InvalidGeometry { reason: e })?;
+    let mut polygons = Vec::new();
+    get_polygons(geocode, &json, &mut polygons)?;
+
+    // remove cells shared between polygons
+    let mut cells = Vec::new();
+    let mut visited = HashSet::new();
+    for polygon in polygons.iter() {
+        for cell in geocode.cover_polygon(polygon, precision, mode)? {
+            if visited.insert(cell.clone()) {
+                cells.push(cell);
+            }
+        }
+    }
+
+    if args.has_flag("compact") {
+        cells = geocode.compact(cells)?;
+    }
+
+    match format {
+        Format::Json => {
+            let cells: Vec<String> = cells.iter()
+                .map(|x| format!("\"{}\"", x)).collect();
+            writeln!(out, "[{}]", cells.join(","))?;
+        },
+        Format::Plain => {
+            for cell in cells.iter() {
+                writeln!(out, "{}", cell)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn decode<W: Write>(args: &Args, out: &mut W) -> Result<(), CliError> {
+    let (geocode, format) = (args.get_geocode()?, args.get_format()?);
+    if args.positional.is_empty() {
+        return Err(CliError::Usage("missing argument <code>".to_string()));
+    }
+
+    for code in args.positional.iter() {
+        let bbox = geocode.decode_lonlat(code)?;
+        let (x, y) = get_center(geocode, code)?;
+        match (format, args.has_flag("center")) {
+            (Format::Json, _) => writeln!(out, "{{\"code\":\"{}\",\
+                \"min_x\":{},\"max_x\":{},\"min_y\":{},\"max_y\":{},\
+                \"x\":{},\"y\":{}}}", code, bbox.min_x, bbox.max_x,
+                bbox.min_y, bbox.max_y, x, y)?,
+            (Format::Plain, true) => writeln!(out, "{} {}", x, y)?,
+            (Format::Plain, false) => writeln!(out, "{} {} {} {}",
+                bbox.min_x, bbox.max_x, bbox.min_y, bbox.max_y)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn encode<W: Write>(args: &Args, out: &mut W) -> Result<(), CliError> {
+    let (geocode, format) = (args.get_geocode()?, args.get_format()?);
+    let lon: f64 = args.get_positional(0, "lon")?;
+    let lat: f64 = args.get_positional(1, "lat")?;
+    let precision: usize = args.get_positional(2, "precision")?;
+
+    let code = geocode.encode_lonlat(lon, lat, precision)?;
+    match format {
+        Format::Json => writeln!(out, "{{\"code\":\"{}\"}}", code)?,
+        Format::Plain => writeln!(out, "{}", code)?,
+    }
+
+    Ok(())
+}
+
+fn encode_row(geocode: Geocode, lon: f64, lat: f64, precision: usize,
+        code: &mut String) -> Result<(), CliError> {
+    // wgs84 geocodes write directly into the reused buffer
+    match geocode.get_epsg_code() {
+        4326 => geocode.encode_into(lon, lat, precision, code)?,
+        _ => code.push_str(&geocode.encode_lonlat(lon, lat, precision)?),
+    }
+
+    Ok(())
+}
+
+fn get_center(geocode: Geocode, code: &str)
+        -> Result<(f64, f64), CliError> {
+    // project the native cell center so it matches the encoded cell
+    let BoundingBox { min_x, max_x, min_y, max_y } = geocode.decode(code)?;
+    let (x, y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    match geocode.get_epsg_code() {
+        3857 => Ok(project::mercator_to_wgs84(x, y)),
+        _ => Ok((x, y)),
+    }
+}
+
+fn get_direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::North => "north",
+        Direction::NorthEast => "north_east",
+        Direction::East => "east",
+        Direction::SouthEast => "south_east",
+        Direction::South => "south",
+        Direction::SouthWest => "south_west",
+        Direction::West => "west",
+        Direction::NorthWest => "north_west",
+    }
+}
+
+fn get_polygons(geocode: Geocode, json: &Json,
+        polygons: &mut Vec<Polygon>) -> Result<(), GeocodeError> {
+    let invalid = |reason: &str| GeocodeError::InvalidGeometry {
+        reason: reason.to_string() };
+
+    // collect polygons from geometries, features, and collections
+    let coordinates = json.get("coordinates");
+    match json.get("type").and_then(|x| x.as_str()) {
+        Some("Feature") => get_polygons(geocode, json.get("geometry")
+            .ok_or_else(|| invalid("feature has no geometry"))?, polygons)?,
+        Some("FeatureCollection") => {
+            for feature in json.get("features").and_then(|x| x.as_array())
+                    .ok_or_else(|| invalid("collection has no features"))? {
+                get_polygons(geocode, feature, polygons)?;
+            }
+        },
+        Some("GeometryCollection") => {
+            for geometry in json.get("geometries").and_then(|x| x.as_array())
+                    .ok_or_else(|| invalid("collection has no geometries"))? {
+                get_polygons(geocode, geometry, polygons)?;
+            }
+        },
+        Some("MultiPolygon") => {
+            for polygon in coordinates.and_then(|x| x.as_array())
+                    .ok_or_else(|| invalid("invalid coordinates"))? {
+                polygons.push(get_polygon(geocode, polygon)?);
+            }
+        },
+        Some("Polygon") => polygons.push(get_polygon(geocode,
+            coordinates.ok_or_else(|| invalid("invalid coordinates"))?)?),
+        Some(geometry) => return Err(GeocodeError::InvalidGeometry {
+            reason: format!("unsupported geometry type '{}'", geometry) }),
+        None => return Err(invalid("missing geometry type")),
+    }
+
+    Ok(())
+}
+
+fn get_polygon(geocode: Geocode, json: &Json)
+        -> Result<Polygon, GeocodeError> {
+    let invalid = || GeocodeError::InvalidGeometry {
+        reason: "invalid polygon coordinates".to_string() };
+
+    // project each position from wgs84 to geocode coordinates
+    let mut rings = Vec::new();
+    for ring in json.as_array().ok_or_else(invalid)? {
+        let mut points = Vec::new();
+        for position in ring.as_array().ok_or_else(invalid)? {
+            let position = position.as_array().ok_or_else(invalid)?;
+            match (position.first().and_then(|x| x.as_f64()),
+                    position.get(1).and_then(|x| x.as_f64())) {
+                (Some(lon), Some(lat)) => points.push(match geocode
+                        .get_epsg_code() {
+                    3857 => project::wgs84_to_mercator(lon, lat),
+                    _ => (lon, lat),
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        rings.push(points);
+    }
+
+    if rings.is_empty() {
+        return Err(invalid());
+    }
+
+    let exterior = rings.remove(0);
+    Ok(Polygon::new(exterior, rings))
+}
+
+fn neighbors<W: Write>(args: &Args, out: &mut W) -> Result<(), CliError> {
+    let (geocode, format) = (args.get_geocode()?, args.get_format()?);
+    let code: String = args.get_positional(0, "code")?;
+
+    // label each neighbor with its direction, skipping those beyond a pole
+    let mut neighbors = Vec::new();
+    for direction in Direction::ALL.iter() {
+        match geocode.neighbor(&code, *direction) {
+            Ok(neighbor) => neighbors.push((*direction, neighbor)),
+            Err(GeocodeError::PoleCrossing { .. }) => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    match format {
+        Format::Json => {
+            let neighbors: Vec<String> = neighbors.iter()
+                .map(|(direction, neighbor)| format!("\"{}\":\"{}\"",
+                    get_direction_name(*direction), neighbor)).collect();
+            writeln!(out, "{{\"code\":\"{}\",\"neighbors\":{{{}}}}}",
+                code, neighbors.join(","))?;
+        },
+        Format::Plain => {
+            for (direction, neighbor) in neighbors.iter() {
+                writeln!(out, "{} {}", get_direction_name(*direction),
+                    neighbor)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn pipe<R: BufRead, W: Write>(args: &Args, mut input: R, out: &mut W)
+        -> Result<(), CliError> {
+    let geocode = args.get_geocode()?;
+    let precision: usize = args.get_option("precision", None)?;
+    let lon_column: usize = args.get_option("lon-column", Some(0))?;
+    let lat_column: usize = args.get_option("lat-column", Some(1))?;
+    let delimiter = args.get_delimiter()?;
+
+    // reuse line and code buffers across rows
+    let (mut line, mut code) = (String::new(), String::new());
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        line_number += 1;
+        let row = line.trim_end_matches(&['\r', '\n'][..]);
+        if line_number == 1 && args.has_flag("header") {
+            writeln!(out, "{}{}geocode", row, delimiter)?;
+            continue;
+        }
+
+        code.clear();
+        let lon = row.split(delimiter).nth(lon_column);
+        let lat = row.split(delimiter).nth(lat_column);
+        let result = match (lon.map(|x| x.trim().parse::<f64>()),
+                lat.map(|x| x.trim().parse::<f64>())) {
+            (Some(Ok(lon)), Some(Ok(lat))) => encode_row(geocode,
+                lon, lat, precision, &mut code),
+            _ => Err(CliError::Usage("invalid coordinates".to_string())),
+        };
+
+        match result {
+            Ok(()) => {},
+            Err(_) if args.has_flag("skip-invalid") => code.clear(),
+            Err(e) => return Err(CliError::Input {
+                line: line_number, reason: e.to_string() }),
+        }
+
+        writeln!(out, "{}{}{}", row, delimiter, code)?;
+    }
+
+    Ok(())
+}
+
+fn ring<W: Write>(args: &Args, out: &mut W) -> Result<(), CliError> {
+    let (geocode, format) = (args.get_geocode()?, args.get_format()?);
+    let code: String = args.get_positional(0, "code")?;
+    let k: usize = args.get_option("k", Some(1))?;
+
+    let cells: Vec<(usize, String)> = geocode.spiral(&code)?
+        .take_while(|(ring, _)| *ring <= k).collect();
+    match format {
+        Format::Json => {
+            let cells: Vec<String> = cells.iter()
+                .map(|(ring, cell)| format!("{{\"ring\":{},\"code\":\"{}\"}}",
+                    ring, cell)).collect();
+            writeln!(out, "[{}]", cells.join(","))?;
+        },
+        Format::Plain => {
+            for (ring, cell) in cells.iter() {
+                writeln!(out, "{} {}", ring, cell)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn sort<R: BufRead, W: Write>(args: &Args, input: R, out: &mut W)
+        -> Result<(), CliError> {
+    let geocode = args.get_geocode()?;
+    let defaults = SortOptions::default();
+    let options = SortOptions {
+        delimiter: args.get_delimiter()?,
+        column: args.get_option("column", Some(defaults.column))?,
+        chunk_size: args.get_option("chunk-size",
+            Some(defaults.chunk_size))?,
+        unique: args.has_flag("unique"),
+        ..defaults
+    };
+
+    // merge presorted files, otherwise sort a file or stdin
+    if args.has_flag("merge") {
+        let mut inputs = Vec::new();
+        for path in args.positional.iter() {
+            inputs.push(BufReader::new(fs::File::open(path)?));
+        }
+
+        geocode.merge_records(inputs, out, &options)?;
+        return Ok(());
+    }
+
+    match args.positional.first().map(|x| x.as_str()) {
+        None | Some("-") => geocode.sort_records(input, out, &options)?,
+        Some(path) => geocode.sort_records(
+            BufReader::new(fs::File::open(path)?), out, &options)?,
+    };
+
+    Ok(())
+}
+
+fn run<R: BufRead, W: Write>(values: &[String], input: R, out: &mut W)
+        -> Result<(), CliError> {
+    let command = values.first().ok_or_else(||
+        CliError::Usage("missing command".to_string()))?;
+    let args = Args::parse(&values[1..])?;
+
+    match command.as_str() {
+        "convert" => convert(&args, out),
+        "cover" => cover(&args, input, out),
+        "decode" => decode(&args, out),
+        "encode" => encode(&args, out),
+        "neighbors" => neighbors(&args, out),
+        "pipe" => pipe(&args, input, out),
+        "ring" => ring(&args, out),
+        "sort" => sort(&args, input, out),
+        _ => Err(CliError::Usage(format!("unknown command '{}'", command))),
+    }
+}
+
+fn main() {
+    let values: Vec<String> = std::env::args().skip(1).collect();
+    let (stdin, stdout) = (io::stdin(), io::stdout());
+    let mut out = BufWriter::new(stdout.lock());
+    match run(&values, stdin.lock(), &mut out).and_then(|_| Ok(out.flush()?)) {
+        Ok(()) => {},
+        // downstream commands such as head may close the pipe early
+        Err(CliError::Io(e)) if e.kind() == ErrorKind::BrokenPipe => {},
+        Err(CliError::Usage(reason)) => {
+            eprintln!("error: {}\n\n{}", reason, USAGE);
+            process::exit(2);
+        },
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geocode::{CoverMode, Geocode, Polygon};
+    use super::CliError;
+
+    fn run(values: &[&str]) -> Result<String, CliError> {
+        run_with_input(values, "")
+    }
+
+    fn run_with_input(values: &[&str], input: &str)
+            -> Result<String, CliError> {
+        let values: Vec<String> = values.iter().map(|x| x.to_string())
+            .collect();
+        let mut out = Vec::new();
+        super::run(&values, input.as_bytes(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(run(&["encode", "-88.4", "44.266667", "6"]).unwrap(),
+            "dpc5u6\n");
+        assert_eq!(run(&["encode", "--type", "quadtile", "-88.4",
+            "44.266667", "4"]).unwrap(), format!("{}\n",
+            Geocode::QuadTile.encode_lonlat(-88.4, 44.266667, 4).unwrap()));
+        assert_eq!(run(&["encode", "--format=json", "-88.4", "44.266667",
+            "6"]).unwrap(), "{\"code\":\"dpc5u6\"}\n");
+
+        assert!(matches!(run(&["encode", "-88.4", "44.266667"]),
+            Err(CliError::Usage(_))));
+        assert!(matches!(run(&["encode", "--type", "h4", "-88.4",
+            "44.266667", "6"]), Err(CliError::Usage(_))));
+        assert!(matches!(run(&["encode", "-88.4", "144.0", "6"]),
+            Err(CliError::Geocode(_))));
+    }
+
+    #[test]
+    fn decode() {
+        let bbox = Geocode::Geohash.decode("dpc5u6").unwrap();
+        let (x, y) = bbox.center();
+        assert_eq!(run(&["decode", "dpc5u6", "dpc5u6"]).unwrap(),
+            format!("{0} {1} {2} {3}\n{0} {1} {2} {3}\n", bbox.min_x,
+                bbox.max_x, bbox.min_y, bbox.max_y));
+        assert_eq!(run(&["decode", "--center", "dpc5u6"]).unwrap(),
+            format!("{} {}\n", x, y));
+        assert_eq!(run(&["decode", "--format", "json", "dpc5u6"]).unwrap(),
+            format!("{{\"code\":\"dpc5u6\",\"min_x\":{},\"max_x\":{},\
+                \"min_y\":{},\"max_y\":{},\"x\":{},\"y\":{}}}\n",
+                bbox.min_x, bbox.max_x, bbox.min_y, bbox.max_y, x, y));
+
+        // quadtile centers lie within the projected cell bounds
+        let output = run(&["decode", "--type", "quadtile", "--center",
+            "0302"]).unwrap();
+        let center: Vec<f64> = output.split_whitespace()
+            .map(|x| x.parse().unwrap()).collect();
+        let bbox = Geocode::QuadTile.decode_lonlat("0302").unwrap();
+        assert!(bbox.contains(center[0], center[1]));
+
+        assert!(matches!(run(&["decode"]), Err(CliError::Usage(_))));
+        assert!(matches!(run(&["decode", "dpca"]),
+            Err(CliError::Geocode(_))));
+        assert!(matches!(run(&["lookup"]), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn pipe() {
+        let input = "lon,lat\n-88.4,44.266667\n-105.078056,40.559167\n";
+        assert_eq!(run_with_input(&["pipe", "--precision", "6", "--header"],
+            input).unwrap(), "lon,lat,geocode\n-88.4,44.266667,dpc5u6\n\
+            -105.078056,40.559167,9xjq8z\n");
+
+        // columns may appear in any order with other fields
+        let input = "a\t44.266667\tb\t-88.4\r\n";
+        assert_eq!(run_with_input(&["pipe", "--precision", "6",
+            "--delimiter", "tab", "--lon-column", "3", "--lat-column", "1"],
+            input).unwrap(), "a\t44.266667\tb\t-88.4\tdpc5u6\n");
+        assert_eq!(run_with_input(&["pipe", "--precision=4",
+            "--type=quadtile"], "-88.4,44.266667\n").unwrap(), format!(
+            "-88.4,44.266667,{}\n",
+            Geocode::QuadTile.encode_lonlat(-88.4, 44.266667, 4).unwrap()));
+
+        // invalid rows fail unless skipped
+        let input = "-88.4,44.266667\n-88.4\n200.0,0.0\n";
+        assert!(matches!(run_with_input(&["pipe", "--precision", "6"],
+            input), Err(CliError::Input { line: 2, .. })));
+        assert_eq!(run_with_input(&["pipe", "--precision", "6",
+            "--skip-invalid"], input).unwrap(),
+            "-88.4,44.266667,dpc5u6\n-88.4,\n200.0,0.0,\n");
+
+        assert!(matches!(run_with_input(&["pipe"], input),
+            Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn cover() {
+        let rings = "[[[-89.0, 44.0], [-88.0, 44.0], [-88.0, 45.0], \
+            [-89.0, 44.0]]]";
+        let polygon = &format!("{{\"type\": \"Polygon\", \
+            \"coordinates\": {}}}", rings);
+        let cells = Geocode::Geohash.cover_polygon(&Polygon::new(vec![
+            (-89.0, 44.0), (-88.0, 44.0), (-88.0, 45.0), (-89.0, 44.0)],
+            Vec::new()), 4, CoverMode::Intersecting).unwrap();
+        assert_eq!(run_with_input(&["cover", "--precision", "4"], polygon)
+            .unwrap(), cells.iter().map(|x| format!("{}\n", x))
+                .collect::<String>());
+
+        // features wrapping multipolygons cover each polygon once
+        let feature = format!("{{\"type\": \"Feature\", \"properties\": \
+            {{}}, \"geometry\": {{\"type\": \"MultiPolygon\", \
+            \"coordinates\": [{0}, {0}]}}}}", rings);
+        assert_eq!(run_with_input(&["cover", "--precision", "4",
+            "--format", "json"], &feature).unwrap(), format!("[{}]\n",
+            cells.iter().map(|x| format!("\"{}\"", x)).collect::<Vec<_>>()
+                .join(",")));
+
+        let compacted = run_with_input(&["cover", "--precision", "5",
+            "--compact", "--mode", "contained"], polygon).unwrap();
+        assert!(compacted.lines().any(|x| x.len() == 4));
+        assert!(compacted.lines().count()
+            < run_with_input(&["cover", "--precision", "5", "--mode",
+                "contained"], polygon).unwrap().lines().count());
+
+        assert!(matches!(run_with_input(&["cover", "--precision", "4"],
+            "{\"type\": \"Point\", \"coordinates\": [0.0, 0.0]}"),
+            Err(CliError::Geocode(_))));
+        assert!(matches!(run_with_input(&["cover", "--precision", "4"],
+            "{\"type\": "), Err(CliError::Geocode(_))));
+        assert!(matches!(run_with_input(&["cover", "--precision", "4",
+            "--mode", "all"], polygon), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn neighbors() {
+        assert_eq!(run(&["neighbors", "dpc5u6"]).unwrap(), "north dpc5u7\n\
+            north_east dpc5ue\neast dpc5ud\nsouth_east dpc5u9\n\
+            south dpc5u3\nsouth_west dpc5u1\nwest dpc5u4\n\
+            north_west dpc5u5\n");
+        assert_eq!(run(&["neighbors", "--type", "quadtile", "--format",
+            "json", "0"]).unwrap(), "{\"code\":\"0\",\"neighbors\":\
+            {\"east\":\"1\",\"south_east\":\"3\",\"south\":\"2\",\
+            \"south_west\":\"3\",\"west\":\"1\"}}\n");
+
+        assert!(matches!(run(&["neighbors"]), Err(CliError::Usage(_))));
+        assert!(matches!(run(&["neighbors", "dpca"]),
+            Err(CliError::Geocode(_))));
+    }
+
+    #[test]
+    fn ring() {
+        let cells = Geocode::Geohash.k_ring("dpc5u6", 2).unwrap();
+        let output = run(&["ring", "dpc5u6", "--k", "2"]).unwrap();
+        assert_eq!(output.lines().map(|x| x.split(' ').nth(1).unwrap())
+            .collect::<Vec<&str>>(), cells);
+        assert!(output.starts_with("0 dpc5u6\n1 dpc5u5\n"));
+        assert!(output.ends_with(&format!("2 {}\n", cells[24])));
+
+        assert_eq!(run(&["ring", "dpc5u6", "--k=0", "--format=json"])
+            .unwrap(), "[{\"ring\":0,\"code\":\"dpc5u6\"}]\n");
+        assert!(matches!(run(&["ring", "dpc5u6", "--k", "-1"]),
+            Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn sort() {
+        let input = "a,dpc5u6\nb,9xjq\nc,dpc5\nd,9xjq\n";
+        assert_eq!(run_with_input(&["sort", "--column", "1"], input).unwrap(),
+            "b,9xjq\nd,9xjq\nc,dpc5\na,dpc5u6\n");
+        assert_eq!(run_with_input(&["sort", "--column=1", "--unique",
+            "--chunk-size", "1"], input).unwrap(), "b,9xjq\nc,dpc5\na,dpc5u6\n");
+
+        // merged files must already be sorted
+        let dir = std::env::temp_dir();
+        let (a, b) = (dir.join(format!("geocode-cli-{}-a.csv",
+            std::process::id())), dir.join(format!("geocode-cli-{}-b.csv",
+            std::process::id())));
+        std::fs::write(&a, "9xjq\ndpc5\n").unwrap();
+        std::fs::write(&b, "dp\n").unwrap();
+        let paths = [a.to_str().unwrap(), b.to_str().unwrap()];
+        let output = run(&["sort", "--merge", paths[0], paths[1]]);
+        let unsorted = run(&["sort", "--merge", paths[1], paths[0], paths[1]]);
+        std::fs::write(&b, "zz\ndp\n").unwrap();
+        let invalid = run(&["sort", "--merge", paths[0], paths[1]]);
+        std::fs::remove_file(&a).unwrap();
+        std::fs::remove_file(&b).unwrap();
+
+        assert_eq!(output.unwrap(), "9xjq\ndp\ndpc5\n");
+        assert_eq!(unsorted.unwrap(), "9xjq\ndp\ndp\ndpc5\n");
+        assert!(matches!(invalid, Err(CliError::Geocode(_))));
+        assert!(matches!(run_with_input(&["sort", "--column", "2"], input),
+            Err(CliError::Geocode(_))));
+    }
+
+    #[test]
+    fn convert() {
+        // converted cells contain the center of the original cell
+        let (x, y) = Geocode::Geohash.decode("dpc5u6").unwrap().center();
+        let code = Geocode::QuadTile.encode_lonlat(x, y, 18).unwrap();
+        assert_eq!(run(&["convert", "--from", "geohash", "--to", "quadtile",
+            "--precision", "18", "dpc5u6"]).unwrap(), format!("{}\n", code));
+
+        // converting a finer cell back recovers the original cell
+        assert_eq!(run(&["convert", "--from=quadtile", "--to=geohash",
+            "--precision=6", "--format=json", &code]).unwrap(), format!(
+            "{{\"code\":\"{}\",\"converted\":\"dpc5u6\"}}\n", code));
+
+        assert!(matches!(run(&["convert", "--to", "quadtile", "--precision",
+            "12", "dpc5u6"]), Err(CliError::Usage(_))));
+        assert!(matches!(run(&["convert", "--from", "geohash", "--to",
+            "quadtile", "dpc5u6"]), Err(CliError::Usage(_))));
+        assert!(matches!(run(&["convert", "--from", "geohash", "--to",
+            "quadtile", "--precision", "12", "dpca"]),
+            Err(CliError::Geocode(_))));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{Geocode, GeocodeError};
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aggregate {
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Aggregate {
+    pub fn new(value: f64) -> Aggregate {
+        Aggregate { count: 1, sum: value, min: value, max: value }
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    pub fn merge(&mut self, other: &Aggregate) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Binner {
+    geocode: Geocode,
+    precision: usize,
+    // aggregates are stored by key so iteration follows the hierarchy
+    bins: BTreeMap<String, Aggregate>,
+}
+
+impl Binner {
+    pub fn new(geocode: Geocode, precision: usize)
+            -> Result<Binner, GeocodeError> {
+        // check if precision is valid by encoding the geocode center
+        let (x, y) = geocode.get_bounds().center();
+        geocode.encode(x, y, precision)?;
+
+        Ok(Binner { geocode, precision, bins: BTreeMap::new() })
+    }
+
+    pub fn get(&self, value: &str) -> Option<&Aggregate> {
+        let key = self.geocode.parse_key(value).ok()?;
+        self.bins.get(&key)
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn insert(&mut self, x: f64, y: f64, value: f64)
+            -> Result<(), GeocodeError> {
+        if !value.is_finite() {
+            return Err(GeocodeError::InvalidFormat {
+                reason: format!("value {} is not finite", value) });
+        }
+
+        let code = self.geocode.encode(x, y, self.precision)?;
+        let key = self.geocode.parse_key(&code)?;
+        match self.bins.get_mut(&key) {
+            Some(aggregate) => aggregate.insert(value),
+            None => {
+                self.bins.insert(key, Aggregate::new(value));
+            },
+        }
+
+        Ok(())
+    }
+
+    pub fn insert_aggregate(&mut self, value: &str, aggregate: &Aggregate)
+            -> Result<(), GeocodeError> {
+        // precomputed aggregates must match the binner precision
+        let key = self.geocode.parse_key(value)?;
+        let precision = self.geocode.get_precision(value)?;
+        if precision != self.precision {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        match self.bins.get_mut(&key) {
+            Some(existing) => existing.merge(aggregate),
+            None => {
+                self.bins.insert(key, *aggregate);
+            },
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (String, &Aggregate)> + '_ {
+        self.bins.iter().map(move |(key, aggregate)|
+            (self.geocode.format_key(key), aggregate))
+    }
+
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn merge(&mut self, other: &Binner) -> Result<(), GeocodeError> {
+        // binners over separate partitions combine into a single rollup
+        if self.geocode != other.geocode {
+            return Err(GeocodeError::GeocodeMismatch {
+                expected: self.geocode, found: other.geocode });
+        } else if self.precision != other.precision {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: other.precision });
+        }
+
+        for (key, aggregate) in other.bins.iter() {
+            match self.bins.get_mut(key) {
+                Some(existing) => existing.merge(aggregate),
+                None => {
+                    self.bins.insert(key.clone(), *aggregate);
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::{Aggregate, Binner};
+
+    #[test]
+    fn aggregate() {
+        let mut aggregate = Aggregate::new(2.0);
+        aggregate.insert(-1.0);
+        aggregate.insert(5.0);
+        assert_eq!(aggregate, Aggregate { count: 3, sum: 6.0,
+            min: -1.0, max: 5.0 });
+        assert_eq!(aggregate.mean(), 2.0);
+
+        aggregate.merge(&Aggregate::new(10.0));
+        assert_eq!(aggregate, Aggregate { count: 4, sum: 16.0,
+            min: -1.0, max: 10.0 });
+    }
+
+    #[test]
+    fn binner() {
+        let geocode = Geocode::Geohash;
+        let mut binner = Binner::new(geocode, 4).unwrap();
+        assert!(binner.is_empty());
+
+        for (x, y, value) in [(-88.4, 44.26, 1.0), (-88.39, 44.27, 3.0),
+                (-122.4, 37.8, 7.0)].iter() {
+            binner.insert(*x, *y, *value).unwrap();
+        }
+
+        assert_eq!(binner.len(), 2);
+        assert_eq!(binner.get("dpc5"), Some(&Aggregate { count: 2,
+            sum: 4.0, min: 1.0, max: 3.0 }));
+        assert_eq!(binner.get("9q8z").unwrap().mean(), 7.0);
+        assert_eq!(binner.get("dpc6"), None);
+
+        let cells: Vec<String> = binner.iter().map(|x| x.0).collect();
+        assert_eq!(cells, vec!["9q8z".to_string(), "dpc5".to_string()]);
+
+        // partial binners merge into the combined aggregates
+        let mut other = Binner::new(geocode, 4).unwrap();
+        other.insert(-88.4, 44.26, -2.0).unwrap();
+        other.insert(0.0, 0.0, 0.0).unwrap();
+        binner.merge(&other).unwrap();
+        assert_eq!(binner.len(), 3);
+        assert_eq!(binner.get("dpc5"), Some(&Aggregate { count: 3,
+            sum: 2.0, min: -2.0, max: 3.0 }));
+
+        assert_eq!(binner.merge(&Binner::new(geocode, 5).unwrap()),
+            Err(GeocodeError::InvalidPrecision { precision: 5 }));
+        assert!(binner.merge(&Binner::new(Geocode::Geohash16, 4).unwrap())
+            .is_err());
+        assert!(binner.insert(-88.4, 44.26, f64::NAN).is_err());
+        assert!(binner.insert(-188.4, 44.26, 1.0).is_err());
+        assert!(Binner::new(geocode, 0).is_err());
+    }
+
+    #[test]
+    fn insert_aggregate() {
+        let mut binner = Binner::new(Geocode::QuadTile, 2).unwrap();
+        binner.insert_aggregate("03", &Aggregate::new(1.0)).unwrap();
+        binner.insert_aggregate("03", &Aggregate { count: 2, sum: 5.0,
+            min: 2.0, max: 3.0 }).unwrap();
+        assert_eq!(binner.get("03"), Some(&Aggregate { count: 3, sum: 6.0,
+            min: 1.0, max: 3.0 }));
+
+        assert_eq!(binner.insert_aggregate("031", &Aggregate::new(1.0)),
+            Err(GeocodeError::InvalidPrecision { precision: 3 }));
+        assert!(binner.insert_aggregate("04", &Aggregate::new(1.0))
+            .is_err());
+    }
+}
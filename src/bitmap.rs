@@ -0,0 +1,251 @@
+use crate::{CellSet, Geocode, GeocodeError};
+
+use roaring::RoaringTreemap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitmapCellSet {
+    geocode: Geocode,
+    precision: usize,
+    // cells are stored as dense interleaved indices at a fixed precision,
+    // so coarser cells become contiguous runs of their descendants
+    bitmap: RoaringTreemap,
+}
+
+impl BitmapCellSet {
+    pub fn new(geocode: Geocode, precision: usize)
+            -> Result<BitmapCellSet, GeocodeError> {
+        if precision == 0 || precision > geocode.get_max_id_precision()? {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        Ok(BitmapCellSet { geocode, precision, bitmap: RoaringTreemap::new() })
+    }
+
+    pub fn from_bytes(geocode: Geocode, precision: usize, bytes: &[u8])
+            -> Result<BitmapCellSet, GeocodeError> {
+        let mut cell_set = BitmapCellSet::new(geocode, precision)?;
+        cell_set.bitmap = RoaringTreemap::deserialize_from(bytes)
+            .map_err(|x| GeocodeError::InvalidFormat {
+                reason: x.to_string() })?;
+
+        // check all indices lie within the precision
+        if cell_set.bitmap.max().is_some_and(|x| x >= cell_set.get_size()) {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "cell index exceeds precision".to_string() });
+        }
+
+        Ok(cell_set)
+    }
+
+    pub fn from_cells(geocode: Geocode, precision: usize, cells: &[String])
+            -> Result<BitmapCellSet, GeocodeError> {
+        let mut cell_set = BitmapCellSet::new(geocode, precision)?;
+        for cell in cells.iter() {
+            cell_set.insert(cell)?;
+        }
+
+        Ok(cell_set)
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        // cells coarser than the set are contained when every descendant is
+        match self.get_range(value) {
+            Ok((start, end)) => self.bitmap.rank(end - 1)
+                - start.checked_sub(1).map_or(0, |x| self.bitmap.rank(x))
+                == end - start,
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64)
+            -> Result<bool, GeocodeError> {
+        let code = self.geocode.encode(x, y, self.precision)?;
+        Ok(self.contains(&code))
+    }
+
+    pub fn difference(&self, other: &BitmapCellSet)
+            -> Result<BitmapCellSet, GeocodeError> {
+        self.check_layout(other)?;
+        Ok(self.with_bitmap(&self.bitmap - &other.bitmap))
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn insert(&mut self, value: &str) -> Result<(), GeocodeError> {
+        let (start, end) = self.get_range(value)?;
+        self.bitmap.insert_range(start..end);
+        Ok(())
+    }
+
+    pub fn intersect(&self, other: &BitmapCellSet)
+            -> Result<BitmapCellSet, GeocodeError> {
+        self.check_layout(other)?;
+        Ok(self.with_bitmap(&self.bitmap & &other.bitmap))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        let (char_bits, _) = self.geocode.get_id_parameters().unwrap();
+        let shift = 64 - self.precision * char_bits;
+        self.bitmap.iter().map(move |x| self.geocode
+            .u64_to_code((x << shift) | self.precision as u64).unwrap())
+    }
+
+    pub fn len(&self) -> u64 {
+        self.bitmap.len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.bitmap.serialized_size());
+        self.bitmap.serialize_into(&mut bytes).unwrap();
+        bytes
+    }
+
+    pub fn union(&self, other: &BitmapCellSet)
+            -> Result<BitmapCellSet, GeocodeError> {
+        self.check_layout(other)?;
+        Ok(self.with_bitmap(&self.bitmap | &other.bitmap))
+    }
+
+    fn check_layout(&self, other: &BitmapCellSet)
+            -> Result<(), GeocodeError> {
+        if self.geocode != other.geocode {
+            return Err(GeocodeError::GeocodeMismatch {
+                expected: self.geocode, found: other.geocode });
+        } else if self.precision != other.precision {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: other.precision });
+        }
+
+        Ok(())
+    }
+
+    fn get_range(&self, value: &str) -> Result<(u64, u64), GeocodeError> {
+        let precision = self.geocode.get_precision(value)?;
+        if precision > self.precision {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // strip the precision field and alignment padding from the id
+        let (char_bits, _) = self.geocode.get_id_parameters()?;
+        let id = self.geocode.code_to_u64(value)?;
+        let index = id >> (64 - precision * char_bits);
+
+        let shift = (self.precision - precision) * char_bits;
+        Ok((index << shift, (index + 1) << shift))
+    }
+
+    fn get_size(&self) -> u64 {
+        let (char_bits, _) = self.geocode.get_id_parameters().unwrap();
+        1 << (self.precision * char_bits)
+    }
+
+    fn with_bitmap(&self, bitmap: RoaringTreemap) -> BitmapCellSet {
+        BitmapCellSet { geocode: self.geocode, precision: self.precision,
+            bitmap }
+    }
+}
+
+impl CellSet {
+    pub fn to_bitmap(&self, precision: usize)
+            -> Result<BitmapCellSet, GeocodeError> {
+        let cells: Vec<String> = self.iter().collect();
+        BitmapCellSet::from_cells(self.get_geocode(), precision, &cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+    use super::BitmapCellSet;
+
+    fn cells(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn insert() {
+        let geocode = Geocode::Geohash;
+        let mut cell_set = BitmapCellSet::new(geocode, 6).unwrap();
+        assert!(cell_set.is_empty());
+        cell_set.insert("dpc5u6").unwrap();
+        cell_set.insert("dpc5v").unwrap();
+        assert_eq!(cell_set.len(), 33);
+        assert_eq!(cell_set.insert("dpc5u6d"),
+            Err(GeocodeError::InvalidPrecision { precision: 7 }));
+
+        // coarse cells are contained only when fully expanded
+        assert!(cell_set.contains("dpc5u6"));
+        assert!(cell_set.contains("dpc5v"));
+        assert!(cell_set.contains("dpc5vz"));
+        assert!(!cell_set.contains("dpc5u"));
+        assert!(!cell_set.contains("dpc5u7"));
+        assert_eq!(cell_set.contains_point(-88.4, 44.266667), Ok(true));
+        assert_eq!(cell_set.contains_point(-88.0, 44.266667), Ok(false));
+
+        let codes: Vec<String> = cell_set.iter().collect();
+        assert_eq!(codes.len(), 33);
+        assert_eq!(codes[0], "dpc5u6");
+        assert!(codes[1..].iter().all(|x| x.starts_with("dpc5v")));
+
+        // precisions are limited to the integer id layout
+        assert!(BitmapCellSet::new(geocode, 12).is_ok());
+        assert_eq!(BitmapCellSet::new(geocode, 13),
+            Err(GeocodeError::InvalidPrecision { precision: 13 }));
+        assert_eq!(BitmapCellSet::new(Geocode::PlusCode, 4),
+            Err(GeocodeError::UnsupportedGeocode {
+                geocode: Geocode::PlusCode }));
+    }
+
+    #[test]
+    fn operations() {
+        let geocode = Geocode::QuadTile;
+        let a = BitmapCellSet::from_cells(geocode, 3,
+            &cells(&["0", "12"])).unwrap();
+        let b = BitmapCellSet::from_cells(geocode, 3,
+            &cells(&["03", "120", "2"])).unwrap();
+        assert_eq!(a.len(), 20);
+
+        let union = a.union(&b).unwrap();
+        assert_eq!(union.len(), 36);
+        assert!(union.contains("0") && union.contains("2"));
+
+        let intersection = a.intersect(&b).unwrap();
+        assert_eq!(intersection, BitmapCellSet::from_cells(geocode, 3,
+            &cells(&["03", "120"])).unwrap());
+
+        let difference = a.difference(&b).unwrap();
+        assert_eq!(difference.len(), 15);
+        assert!(!difference.contains("030") && difference.contains("02"));
+
+        let c = BitmapCellSet::new(geocode, 4).unwrap();
+        assert_eq!(a.union(&c),
+            Err(GeocodeError::InvalidPrecision { precision: 4 }));
+        let d = BitmapCellSet::new(Geocode::Geohash16, 3).unwrap();
+        assert_eq!(a.union(&d), Err(GeocodeError::GeocodeMismatch {
+            expected: geocode, found: Geocode::Geohash16 }));
+    }
+
+    #[test]
+    fn serialize() {
+        let geocode = Geocode::Geohash;
+        let cell_set = CellSet::from_cells(geocode,
+            cells(&["9xjq", "dpc5"])).unwrap().to_bitmap(8).unwrap();
+        assert_eq!(cell_set.len(), 2 << 20);
+
+        let bytes = cell_set.to_bytes();
+        assert_eq!(BitmapCellSet::from_bytes(geocode, 8, &bytes).unwrap(),
+            cell_set);
+        assert!(BitmapCellSet::from_bytes(geocode, 4, &bytes).is_err());
+        assert!(BitmapCellSet::from_bytes(geocode, 8, &bytes[..4]).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{CellSet, Geocode, GeocodeError};
+
+use std::convert::TryInto;
+
+const MAGIC: &[u8; 2] = b"GB";
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 5;
+const MAX_HASHES: usize = 32;
+
+// a bloom filter over cell keys, where codes are tested together with
+// their ancestors so compacted sets match descendants of stored cells
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellBloom {
+    geocode: Geocode,
+    hashes: usize,
+    bits: Vec<u64>,
+}
+
+impl CellBloom {
+    pub fn new(geocode: Geocode, capacity: usize, false_positive_rate: f64)
+            -> Result<CellBloom, GeocodeError> {
+        // check if the false positive rate is valid
+        if !(false_positive_rate > 0f64 && false_positive_rate < 1f64) {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "false positive rate {} is not within (0, 1)",
+                false_positive_rate) });
+        }
+
+        // size the filter optimally for the expected number of cells
+        let ln2 = std::f64::consts::LN_2;
+        let capacity = capacity.max(1) as f64;
+        let bits = (-capacity * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil().max(64f64);
+        let hashes = ((bits / capacity) * ln2).round()
+            .clamp(1f64, MAX_HASHES as f64) as usize;
+
+        let words = (bits / 64f64).ceil() as usize;
+        Ok(CellBloom { geocode, hashes, bits: vec![0; words] })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<CellBloom, GeocodeError> {
+        if bytes.len() < HEADER_LENGTH || &bytes[..2] != MAGIC
+                || bytes[2] != VERSION {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "unrecognized cell bloom header".to_string() });
+        }
+
+        let geocode = *Geocode::ALL.get(bytes[3] as usize)
+            .ok_or_else(|| GeocodeError::InvalidFormat {
+                reason: format!("unknown geocode index {}", bytes[3]) })?;
+        let hashes = bytes[4] as usize;
+        let words = &bytes[HEADER_LENGTH..];
+        if hashes == 0 || hashes > MAX_HASHES || words.is_empty()
+                || !words.len().is_multiple_of(8) {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "invalid cell bloom layout".to_string() });
+        }
+
+        let bits = words.chunks(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap())).collect();
+        Ok(CellBloom { geocode, hashes, bits })
+    }
+
+    pub fn from_cell_set(cell_set: &CellSet, false_positive_rate: f64)
+            -> Result<CellBloom, GeocodeError> {
+        let mut bloom = CellBloom::new(cell_set.get_geocode(),
+            cell_set.len(), false_positive_rate)?;
+        for cell in cell_set.iter() {
+            bloom.insert(&cell)?;
+        }
+
+        Ok(bloom)
+    }
+
+    pub fn get_bit_count(&self) -> usize {
+        self.bits.len() * 64
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_hash_count(&self) -> usize {
+        self.hashes
+    }
+
+    pub fn insert(&mut self, value: &str) -> Result<(), GeocodeError> {
+        let key = self.geocode.parse_key(value)?;
+        let bit_count = self.get_bit_count() as u64;
+        for index in get_indices(&key, self.hashes, bit_count) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+
+        Ok(())
+    }
+
+    pub fn might_contain(&self, value: &str) -> bool {
+        // each ancestor tested adds to the false positive rate, so deep
+        // codes over coarse sets match slightly more often
+        let key = match self.geocode.parse_key(value) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+
+        let mut precision = Some(key.chars().count());
+        while let Some(length) = precision {
+            let prefix: String = key.chars().take(length).collect();
+            if self.contains_key(&prefix) {
+                return true;
+            }
+
+            precision = self.geocode.get_parent_precision(length);
+        }
+
+        false
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let index = Geocode::ALL.iter()
+            .position(|x| *x == self.geocode).unwrap();
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH + self.bits.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[VERSION, index as u8, self.hashes as u8]);
+        for word in self.bits.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        let bit_count = self.get_bit_count() as u64;
+        get_indices(key, self.hashes, bit_count)
+            .all(|x| self.bits[(x / 64) as usize] & (1 << (x % 64)) != 0)
+    }
+}
+
+pub(crate) fn hash_key(key: &str) -> u64 {
+    // fnv-1a followed by a splitmix64 finalizer so every bit avalanches
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in key.bytes() {
+        hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+fn get_indices(key: &str, hashes: usize, bit_count: u64)
+        -> impl Iterator<Item = u64> {
+    // derive each index from two halves of a single hash
+    let hash = hash_key(key);
+    let (a, b) = (hash & 0xffffffff, (hash >> 32) | 1);
+    (0..hashes as u64).map(move |i| a.wrapping_add(i.wrapping_mul(b))
+        % bit_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CellSet, Geocode};
+    use super::CellBloom;
+
+    #[test]
+    fn from_cell_set() {
+        let cells = Geocode::Geohash.cover_bbox(&BoundingBox::new(-88.5,
+            -88.0, 44.0, 44.5), 6).unwrap();
+        let cell_set = CellSet::from_cells(Geocode::Geohash, cells.clone())
+            .unwrap();
+        let bloom = CellBloom::from_cell_set(&cell_set, 0.01).unwrap();
+
+        // stored cells and their descendants always match
+        for cell in cell_set.iter() {
+            assert!(bloom.might_contain(&cell));
+            assert!(bloom.might_contain(&format!("{}0", cell)));
+        }
+
+        for cell in cells.iter() {
+            assert!(bloom.might_contain(cell));
+        }
+
+        // outside cells rarely match
+        let outside = Geocode::Geohash.cover_bbox(&BoundingBox::new(10.0,
+            12.0, 10.0, 12.0), 6).unwrap();
+        let matched = outside.iter().filter(|x| bloom.might_contain(x))
+            .count();
+        assert!(matched * 20 < outside.len(), "{} of {}", matched,
+            outside.len());
+        assert!(!bloom.might_contain("dpa"));
+    }
+
+    #[test]
+    fn new() {
+        let bloom = CellBloom::new(Geocode::QuadTile, 1000, 0.01).unwrap();
+        assert_eq!(bloom.get_hash_count(), 7);
+        assert_eq!(bloom.get_bit_count(), 9600);
+        assert!(!bloom.might_contain("0123"));
+
+        assert!(CellBloom::new(Geocode::QuadTile, 1000, 0.0).is_err());
+        assert!(CellBloom::new(Geocode::QuadTile, 1000, 1.0).is_err());
+        assert!(CellBloom::new(Geocode::QuadTile, 1000, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn to_bytes() {
+        let mut bloom = CellBloom::new(Geocode::PlusCode, 100, 0.001)
+            .unwrap();
+        bloom.insert("86HWG800+").unwrap();
+        assert!(bloom.insert("86HW").is_err());
+
+        let bytes = bloom.to_bytes();
+        assert_eq!(bytes.len(), 5 + bloom.get_bit_count() / 8);
+        let decoded = CellBloom::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, bloom);
+        assert!(decoded.might_contain("86HWG8GG+GG"));
+
+        assert!(CellBloom::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(CellBloom::from_bytes(&bytes[..5]).is_err());
+        assert!(CellBloom::from_bytes(b"XX\x01\x00\x01").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use alloc::string::{String, ToString};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Case {
+    Lower,
+    Upper,
+}
+
+impl Geocode {
+    pub fn decode_ignore_case(&self, value: &str)
+            -> Result<BoundingBox, GeocodeError> {
+        // geohash36 characters differ only by case
+        if let Geocode::Geohash36 = self {
+            return Err(GeocodeError::UnsupportedGeocode { geocode: *self });
+        }
+
+        self.decode(&self.normalize_case(value))
+    }
+
+    pub fn encode_with_case(&self, x: f64, y: f64, precision: usize,
+            case: Case) -> Result<String, GeocodeError> {
+        self.to_case(&self.encode(x, y, precision)?, case)
+    }
+
+    pub(crate) fn normalize_case(&self, value: &str) -> String {
+        match self {
+            Geocode::Geohash | Geocode::Geohash16 =>
+                value.to_ascii_lowercase(),
+            Geocode::PlusCode => value.to_ascii_uppercase(),
+            _ => value.to_string(),
+        }
+    }
+
+    pub fn to_case(&self, value: &str, case: Case)
+            -> Result<String, GeocodeError> {
+        // check if code is valid
+        if let Geocode::Geohash36 = self {
+            return Err(GeocodeError::UnsupportedGeocode { geocode: *self });
+        }
+
+        let code = self.format_key(&self.parse_key(
+            &self.normalize_case(value))?);
+        Ok(match case {
+            Case::Lower => code.to_ascii_lowercase(),
+            Case::Upper => code.to_ascii_uppercase(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::Case;
+
+    #[test]
+    fn decode_ignore_case() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.decode_ignore_case("DPC5U6").unwrap(),
+            geocode.decode("dpc5u6").unwrap());
+        assert_eq!(geocode.decode_ignore_case("DPC5A6"),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 4 }));
+
+        let geocode = Geocode::Geohash16;
+        assert_eq!(geocode.decode_ignore_case("65565D").unwrap(),
+            geocode.decode("65565d").unwrap());
+
+        assert_eq!(Geocode::Geohash36.decode_ignore_case("bdrd"),
+            Err(GeocodeError::UnsupportedGeocode {
+                geocode: Geocode::Geohash36 }));
+    }
+
+    #[test]
+    fn encode_with_case() {
+        let (x, y) = (-88.4, 44.266667);
+        assert_eq!(Geocode::Geohash.encode_with_case(x, y, 6, Case::Upper)
+            .unwrap(), "DPC5U6");
+        assert_eq!(Geocode::Geohash.encode_with_case(x, y, 6, Case::Lower)
+            .unwrap(), "dpc5u6");
+        assert_eq!(Geocode::Maidenhead
+            .encode_with_case(-72.72726, 41.714775, 6, Case::Upper)
+            .unwrap(), "FN31PR");
+        assert!(Geocode::Geohash36
+            .encode_with_case(x, y, 6, Case::Upper).is_err());
+    }
+
+    #[test]
+    fn to_case() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.to_case("DPC5U6", Case::Lower).unwrap(), "dpc5u6");
+        assert_eq!(geocode.to_case("dpc5u6", Case::Upper).unwrap(), "DPC5U6");
+        assert!(geocode.to_case("dpc5a6", Case::Upper).is_err());
+
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.to_case("86HJ7JR9+CV", Case::Lower).unwrap(),
+            "86hj7jr9+cv");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{Geocode, GeocodeError};
+
+use alloc::string::String;
+
+impl Geocode {
+    pub fn code_to_u64(&self, value: &str) -> Result<u64, GeocodeError> {
+        // check if code is valid
+        self.decode(value)?;
+
+        let (char_bits, codes) = self.get_id_parameters()?;
+        let (_, max_precision) = get_id_layout(char_bits);
+        let precision = value.chars().count();
+        if precision > max_precision {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        Ok(pack_id(value, char_bits, codes))
+    }
+
+    pub(crate) fn get_id_parameters(&self)
+            -> Result<(usize, &'static [char]), GeocodeError> {
+        // ids are only defined for geocodes using bit interleaving
+        self.get_parameters()
+            .ok_or(GeocodeError::UnsupportedGeocode { geocode: *self })
+    }
+
+    pub fn get_max_id_precision(&self) -> Result<usize, GeocodeError> {
+        let (char_bits, _) = self.get_id_parameters()?;
+        Ok(get_id_layout(char_bits).1)
+    }
+
+    pub fn u64_to_code(&self, id: u64) -> Result<String, GeocodeError> {
+        let (char_bits, codes) = self.get_id_parameters()?;
+        let (precision_bits, max_precision) = get_id_layout(char_bits);
+
+        // check if id is valid
+        let precision = (id & ((1 << precision_bits) - 1)) as usize;
+        if precision == 0 || precision > max_precision {
+            return Err(GeocodeError::InvalidCellId { id });
+        }
+
+        let unused_bits = 64 - precision * char_bits - precision_bits;
+        if (id >> precision_bits) & ((1 << unused_bits) - 1) != 0 {
+            return Err(GeocodeError::InvalidCellId { id });
+        }
+
+        // read characters from the most significant bits
+        let mask = (1u64 << char_bits) - 1;
+        Ok((0..precision).map(|i| {
+            let shift = 64 - (i + 1) * char_bits;
+            codes[((id >> shift) & mask) as usize]
+        }).collect())
+    }
+}
+
+fn get_id_layout(char_bits: usize) -> (usize, usize) {
+    // compute the smallest precision field which can store the maximum
+    // number of characters fitting in the remaining bits
+    let mut precision_bits = 1;
+    while (64 - precision_bits) / char_bits >= 1 << precision_bits {
+        precision_bits += 1;
+    }
+
+    (precision_bits, (64 - precision_bits) / char_bits)
+}
+
+pub(crate) fn pack_id(value: &str, char_bits: usize, codes: &[char]) -> u64 {
+    // left-align interleaved bits and append the precision of a code
+    // known to be valid
+    let mut id = 0u64;
+    let mut precision = 0;
+    for c in value.chars() {
+        let hash_value = codes.iter().position(|x| *x == c).unwrap();
+        id = (id << char_bits) | hash_value as u64;
+        precision += 1;
+    }
+
+    (id << (64 - precision * char_bits)) | precision as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn code_to_u64() {
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.code_to_u64("0").unwrap(), (1 << 62) | 1);
+        assert_eq!(geocode.code_to_u64("31").unwrap(),
+            (0b1011 << 60) | 2);
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.get_max_id_precision(), Ok(12));
+        assert!(geocode.code_to_u64("dpc5u6dpc5u6").is_ok());
+        assert_eq!(geocode.code_to_u64("dpc5u6dpc5u6d"),
+            Err(GeocodeError::InvalidPrecision { precision: 13 }));
+        assert_eq!(geocode.code_to_u64(""), Err(GeocodeError::EmptyCode));
+    }
+
+    #[test]
+    fn ordering() {
+        // check ids preserve prefix ordering
+        let geocode = Geocode::Geohash;
+        let parent = geocode.code_to_u64("dpc5").unwrap();
+        let next = geocode.code_to_u64("dpc6").unwrap();
+        for code in ["dpc5", "dpc50", "dpc5u6", "dpc5zzzz"].iter() {
+            let id = geocode.code_to_u64(code).unwrap();
+            assert!(id >= parent && id < next);
+        }
+
+        let mut codes = vec!["9xjq", "dpc5u6", "dpc5", "0", "zz", "dpc"];
+        let mut ids: Vec<u64> = codes.iter()
+            .map(|x| geocode.code_to_u64(x).unwrap()).collect();
+        codes.sort();
+        ids.sort();
+        let sorted: Vec<String> = ids.iter()
+            .map(|x| geocode.u64_to_code(*x).unwrap()).collect();
+        assert_eq!(sorted, codes);
+    }
+
+    #[test]
+    fn u64_to_code() {
+        for geocode in [Geocode::Geohash,
+                Geocode::Geohash16, Geocode::QuadTile].iter() {
+            let (x, y) = geocode.decode_center("0").map(|x| (x.0, x.1))
+                .unwrap();
+            for precision in 1..=geocode.get_max_id_precision().unwrap() {
+                let code = geocode.encode(x, y, precision).unwrap();
+                let id = geocode.code_to_u64(&code).unwrap();
+                assert_eq!(geocode.u64_to_code(id).unwrap(), code);
+            }
+        }
+
+        let geocode = Geocode::Geohash16;
+        assert_eq!(geocode.u64_to_code(0),
+            Err(GeocodeError::InvalidCellId { id: 0 }));
+        assert_eq!(geocode.u64_to_code(1 << 10 | 1),
+            Err(GeocodeError::InvalidCellId { id: 1 << 10 | 1 }));
+
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.code_to_u64("7FG49QCJ+2V"),
+            Err(GeocodeError::UnsupportedGeocode { geocode }));
+    }
+}
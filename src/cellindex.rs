@@ -0,0 +1,267 @@
+use crate::{Geocode, GeocodeError};
+use crate::distance::{self, EARTH_MEAN_RADIUS};
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    x: f64,
+    y: f64,
+    value: T,
+}
+
+#[derive(Clone, Debug)]
+pub struct CellIndex<T> {
+    geocode: Geocode,
+    precision: usize,
+    cells: HashMap<String, Vec<Entry<T>>>,
+    len: usize,
+}
+
+impl<T> CellIndex<T> {
+    pub fn new(geocode: Geocode, precision: usize)
+            -> Result<CellIndex<T>, GeocodeError> {
+        // check if precision is valid by encoding the geocode center
+        let (x, y) = geocode.get_bounds().center();
+        geocode.encode(x, y, precision)?;
+
+        Ok(CellIndex { geocode, precision, cells: HashMap::new(), len: 0 })
+    }
+
+    pub fn get(&self, value: &str) -> impl Iterator<Item = &T> + '_ {
+        self.cells.get(value).into_iter()
+            .flat_map(|x| x.iter().map(|x| &x.value))
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn insert(&mut self, x: f64, y: f64, value: T)
+            -> Result<(), GeocodeError> {
+        let code = self.geocode.encode(x, y, self.precision)?;
+        self.cells.entry(code).or_default().push(Entry { x, y, value });
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn k_nearest(&self, x: f64, y: f64, k: usize)
+            -> Result<Vec<(f64, &T)>, GeocodeError> {
+        self.search(x, y, f64::INFINITY, k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn within_radius(&self, x: f64, y: f64, radius: f64)
+            -> Result<Vec<(f64, &T)>, GeocodeError> {
+        if radius.is_nan() || radius < 0.0 || radius.is_infinite() {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: format!("invalid radius {}", radius) });
+        }
+
+        self.search(x, y, radius, usize::MAX)
+    }
+
+    fn get_bound(&self, lon: f64, lat: f64, center: (f64, f64),
+            extent: (f64, f64)) -> f64 {
+        // lower bound on the distance to any point outside the block of
+        // cells, which must cross either a bounding meridian or parallel
+        let (geocode, bounds) = (self.geocode, self.geocode.get_bounds());
+        let mut bound = f64::INFINITY;
+        if 2f64 * extent.0 < bounds.width() {
+            for x in [center.0 - extent.0, center.0 + extent.0].iter() {
+                let (edge_lon, _) = geocode.native_to_wgs84(*x, center.1);
+                let lon_delta = (lon - edge_lon).to_radians();
+                bound = bound.min(EARTH_MEAN_RADIUS
+                    * (lat.to_radians().cos() * lon_delta.sin().abs())
+                        .min(1f64).asin());
+            }
+        }
+
+        for y in [center.1 - extent.1, center.1 + extent.1].iter() {
+            if *y > bounds.min_y && *y < bounds.max_y {
+                let (_, edge_lat) = geocode.native_to_wgs84(center.0, *y);
+                bound = bound.min(EARTH_MEAN_RADIUS
+                    * (lat - edge_lat).to_radians().abs());
+            }
+        }
+
+        bound
+    }
+
+    fn search(&self, x: f64, y: f64, radius: f64, k: usize)
+            -> Result<Vec<(f64, &T)>, GeocodeError> {
+        let code = self.geocode.encode(x, y, self.precision)?;
+        if self.is_empty() || k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let bounds = self.geocode.get_bounds();
+        let center = self.geocode.decode(&code)?.center();
+        let (x_interval, y_interval) = self.geocode
+            .get_intervals(self.precision);
+        let (lon, lat) = self.geocode.native_to_wgs84(x, y);
+
+        // visit rings of cells around the center, wrapping across the
+        // antimeridian, until no unvisited point may be any nearer or
+        // every point is found
+        let mut visited = HashSet::new();
+        let mut results: Vec<(f64, &T)> = Vec::new();
+        let (mut ring, mut found) = (0, 0);
+        loop {
+            let r = ring as i64;
+            for j in -r..=r {
+                let cell_y = center.1 + j as f64 * y_interval;
+                if cell_y < bounds.min_y || cell_y > bounds.max_y {
+                    continue;
+                }
+
+                let step = if j.abs() == r { 1 } else { (2 * r).max(1) };
+                for i in (-r..=r).step_by(step as usize) {
+                    let mut cell_x = center.0 + i as f64 * x_interval;
+                    while cell_x > bounds.max_x {
+                        cell_x -= bounds.width();
+                    }
+                    while cell_x < bounds.min_x {
+                        cell_x += bounds.width();
+                    }
+
+                    let code = self.geocode.encode(cell_x, cell_y,
+                        self.precision)?;
+                    let entries = match self.cells.get(&code) {
+                        Some(entries) if visited.insert(code) => entries,
+                        _ => continue,
+                    };
+
+                    found += entries.len();
+                    for entry in entries.iter() {
+                        let (entry_lon, entry_lat) = self.geocode
+                            .native_to_wgs84(entry.x, entry.y);
+                        let distance = distance::haversine(lon, lat,
+                            entry_lon, entry_lat);
+                        if distance <= radius {
+                            results.push((distance, &entry.value));
+                        }
+                    }
+                }
+            }
+
+            // the search radius shrinks once k points are found
+            results.sort_by(|a, b| a.0.total_cmp(&b.0));
+            results.truncate(k);
+            let limit = match results.len() == k {
+                true => results[k - 1].0,
+                false => radius,
+            };
+
+            let extent = ((ring as f64 + 0.5) * x_interval,
+                (ring as f64 + 0.5) * y_interval);
+            let covered = 2f64 * extent.0 >= bounds.width()
+                && center.1 - extent.1 <= bounds.min_y
+                && center.1 + extent.1 >= bounds.max_y;
+            if covered || found == self.len
+                    || self.get_bound(lon, lat, center, extent) > limit {
+                return Ok(results);
+            }
+
+            ring += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{distance, Geocode, GeocodeError};
+    use super::CellIndex;
+
+    fn index() -> CellIndex<usize> {
+        let mut index = CellIndex::new(Geocode::Geohash, 4).unwrap();
+        for (i, (x, y)) in [(-88.4, 44.266667), (-88.5, 44.3),
+                (-87.9, 44.0), (-105.078056, 40.559167), (179.99, 10.0),
+                (-179.99, 10.0)].iter().enumerate() {
+            index.insert(*x, *y, i).unwrap();
+        }
+
+        index
+    }
+
+    #[test]
+    fn insert() {
+        let index = index();
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.get("dpc5").collect::<Vec<&usize>>(), vec![&0]);
+        assert_eq!(index.get("9q8z").count(), 0);
+
+        let mut index = index;
+        assert!(index.insert(0.0, 91.0, 6).is_err());
+        assert!(CellIndex::<usize>::new(Geocode::Geohash, 0).is_err());
+    }
+
+    #[test]
+    fn k_nearest() {
+        let index = index();
+        let nearest = index.k_nearest(-88.4, 44.266667, 3).unwrap();
+        assert_eq!(nearest.iter().map(|x| *x.1).collect::<Vec<usize>>(),
+            vec![0, 1, 2]);
+        assert_eq!(nearest[0].0, 0.0);
+
+        // results match a brute force search
+        let nearest = index.k_nearest(-100.0, 40.0, 1).unwrap();
+        assert_eq!(*nearest[0].1, 3);
+        assert_eq!(nearest[0].0,
+            distance::haversine(-100.0, 40.0, -105.078056, 40.559167));
+
+        let points = [(-9840642.99, 5506802.68), (-11681000.0, 4948000.0),
+            (20037000.0, 0.0), (-20037000.0, 100000.0)];
+        let mut tiles = CellIndex::new(Geocode::QuadTile, 6).unwrap();
+        for (i, (x, y)) in points.iter().enumerate() {
+            tiles.insert(*x, *y, i).unwrap();
+        }
+
+        for (x, y) in [(-10000000.0, 5000000.0), (19000000.0, 50000.0),
+                (0.0, -15000000.0)].iter() {
+            let (lon, lat) = Geocode::QuadTile.native_to_wgs84(*x, *y);
+            let expected = points.iter().map(|(x, y)| {
+                let (point_lon, point_lat) = Geocode::QuadTile
+                    .native_to_wgs84(*x, *y);
+                distance::haversine(lon, lat, point_lon, point_lat)
+            }).fold(f64::INFINITY, f64::min);
+            assert_eq!(tiles.k_nearest(*x, *y, 1).unwrap()[0].0, expected);
+        }
+
+        // searches wrap across the antimeridian
+        let nearest = index.k_nearest(179.95, 10.0, 2).unwrap();
+        assert_eq!(nearest.iter().map(|x| *x.1).collect::<Vec<usize>>(),
+            vec![4, 5]);
+
+        assert_eq!(index.k_nearest(0.0, 0.0, 10).unwrap().len(), 6);
+        assert!(index.k_nearest(0.0, 0.0, 0).unwrap().is_empty());
+        assert!(index.k_nearest(0.0, 91.0, 1).is_err());
+    }
+
+    #[test]
+    fn within_radius() {
+        let index = index();
+        let within = index.within_radius(-88.4, 44.266667, 60000.0)
+            .unwrap();
+        assert_eq!(within.iter().map(|x| *x.1).collect::<Vec<usize>>(),
+            vec![0, 1, 2]);
+        assert!(within.iter().all(|x| x.0 <= 60000.0));
+
+        assert_eq!(index.within_radius(-179.95, 10.0, 10000.0).unwrap()
+            .len(), 2);
+        assert!(index.within_radius(0.0, 0.0, 1000.0).unwrap().is_empty());
+        assert!(matches!(index.within_radius(0.0, 0.0, -1.0),
+            Err(GeocodeError::InvalidGeometry { .. })));
+    }
+}
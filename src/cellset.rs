@@ -0,0 +1,300 @@
+use crate::{Geocode, GeocodeError};
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellSet {
+    geocode: Geocode,
+    // cells are stored as keys so ancestors are always prefixes
+    cells: BTreeSet<String>,
+}
+
+impl CellSet {
+    pub fn new(geocode: Geocode) -> CellSet {
+        CellSet { geocode, cells: BTreeSet::new() }
+    }
+
+    pub fn from_cells(geocode: Geocode, cells: Vec<String>)
+            -> Result<CellSet, GeocodeError> {
+        let mut keys = Vec::new();
+        for cell in cells.iter() {
+            keys.push(geocode.parse_key(cell)?);
+        }
+
+        let cells = geocode.compact_keys(keys).into_iter().collect();
+        Ok(CellSet { geocode, cells })
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        match self.geocode.parse_key(value) {
+            Ok(key) => self.contains_key(&key),
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64)
+            -> Result<bool, GeocodeError> {
+        let precision = match self.get_max_precision() {
+            Some(precision) => precision,
+            None => return Ok(false),
+        };
+
+        let code = self.geocode.encode(x, y, precision)?;
+        Ok(self.contains(&code))
+    }
+
+    pub fn difference(&self, other: &CellSet)
+            -> Result<CellSet, GeocodeError> {
+        self.check_geocode(other)?;
+
+        let mut cells = Vec::new();
+        for cell in self.cells.iter() {
+            other.subtract_from(cell, &mut cells);
+        }
+
+        Ok(self.with_keys(cells))
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_max_precision(&self) -> Option<usize> {
+        self.cells.iter().map(|x| x.chars().count()).max()
+    }
+
+    pub fn extend<I, S>(&mut self, values: I) -> Result<(), GeocodeError>
+            where I: IntoIterator<Item = S>, S: AsRef<str> {
+        // batches are compacted once, rather than after every cell
+        let mut keys: Vec<String> = self.cells.iter().cloned().collect();
+        for value in values {
+            keys.push(self.geocode.parse_key(value.as_ref())?);
+        }
+
+        self.cells = self.geocode.compact_keys(keys).into_iter().collect();
+        Ok(())
+    }
+
+    pub fn insert(&mut self, value: &str) -> Result<(), GeocodeError> {
+        // cells already covered by an ancestor leave the set unchanged
+        let mut key = self.geocode.parse_key(value)?;
+        if self.contains_key(&key) {
+            return Ok(());
+        }
+
+        // the cell replaces its descendants, then merges into its
+        // ancestors while their sibling groups are complete
+        let descendants: Vec<String> = self.descendants(&key).cloned()
+            .collect();
+        for descendant in descendants.iter() {
+            self.cells.remove(descendant);
+        }
+
+        while let Some(parent_precision) =
+                self.geocode.get_parent_precision(key.chars().count()) {
+            let parent: String = key.chars().take(parent_precision).collect();
+            let siblings = self.geocode.child_keys(&parent)
+                .unwrap_or_default();
+            if !siblings.iter().all(|x| *x == key || self.cells.contains(x)) {
+                break;
+            }
+
+            for sibling in siblings.iter() {
+                self.cells.remove(sibling);
+            }
+            key = parent;
+        }
+
+        self.cells.insert(key);
+        Ok(())
+    }
+
+    pub fn intersect(&self, other: &CellSet)
+            -> Result<CellSet, GeocodeError> {
+        self.check_geocode(other)?;
+
+        let mut cells = Vec::new();
+        for cell in self.cells.iter() {
+            if other.contains_key(cell) {
+                cells.push(cell.clone());
+            } else {
+                cells.extend(other.descendants(cell).cloned());
+            }
+        }
+
+        Ok(self.with_keys(cells))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells.iter().map(move |x| self.geocode.format_key(x))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn union(&self, other: &CellSet) -> Result<CellSet, GeocodeError> {
+        self.check_geocode(other)?;
+
+        let cells = self.cells.iter().chain(other.cells.iter())
+            .cloned().collect();
+        Ok(self.with_keys(cells))
+    }
+
+    fn check_geocode(&self, other: &CellSet) -> Result<(), GeocodeError> {
+        match self.geocode == other.geocode {
+            true => Ok(()),
+            false => Err(GeocodeError::GeocodeMismatch {
+                expected: self.geocode, found: other.geocode }),
+        }
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        // check if the cell or any ancestor is in the set
+        key.char_indices()
+            .map(|(i, c)| &key[..i + c.len_utf8()])
+            .any(|prefix| self.cells.contains(prefix))
+    }
+
+    fn descendants<'a>(&'a self, value: &'a str)
+            -> impl Iterator<Item = &'a String> {
+        let range = (Bound::Included(value), Bound::Unbounded);
+        self.cells.range::<str, _>(range)
+            .skip_while(move |x| x.as_str() == value)
+            .take_while(move |x| x.starts_with(value))
+    }
+
+    fn subtract_from(&self, key: &str, cells: &mut Vec<String>) {
+        if self.contains_key(key) {
+            // cell is entirely removed
+        } else if self.descendants(key).next().is_none() {
+            // cell is entirely retained
+            cells.push(key.to_string());
+        } else {
+            // cell is partially removed
+            for child in self.geocode.child_keys(key).unwrap_or_default() {
+                self.subtract_from(&child, cells);
+            }
+        }
+    }
+
+    fn with_keys(&self, keys: Vec<String>) -> CellSet {
+        CellSet {
+            geocode: self.geocode,
+            cells: self.geocode.compact_keys(keys).into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::CellSet;
+
+    fn cell_set(geocode: Geocode, cells: &[&str]) -> CellSet {
+        CellSet::from_cells(geocode,
+            cells.iter().map(|x| x.to_string()).collect()).unwrap()
+    }
+
+    fn to_vec(cell_set: &CellSet) -> Vec<String> {
+        cell_set.iter().collect()
+    }
+
+    #[test]
+    fn contains() {
+        let cell_set = cell_set(Geocode::Geohash, &["dpc5", "9xjq8z"]);
+        assert!(cell_set.contains("dpc5"));
+        assert!(cell_set.contains("dpc5u6"));
+        assert!(cell_set.contains("9xjq8zs6"));
+        assert!(!cell_set.contains("dpc"));
+        assert!(!cell_set.contains("9xjq8y"));
+
+        assert!(cell_set.contains_point(-88.4, 44.266667).unwrap());
+        assert!(cell_set.contains_point(-105.078056, 40.559167).unwrap());
+        assert!(!cell_set.contains_point(0.0, 0.0).unwrap());
+        assert!(cell_set.contains_point(0.0, 91.0).is_err());
+
+        assert!(!CellSet::new(Geocode::Geohash).contains_point(0.0, 0.0)
+            .unwrap());
+    }
+
+    #[test]
+    fn difference() {
+        let a = cell_set(Geocode::QuadTile, &["0", "12"]);
+        let b = cell_set(Geocode::QuadTile, &["03", "012", "1"]);
+        assert_eq!(to_vec(&a.difference(&b).unwrap()),
+            vec!["00", "010", "011", "013", "02"]);
+        assert_eq!(to_vec(&b.difference(&a).unwrap()),
+            vec!["10", "11", "13"]);
+        assert!(a.difference(&a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn insert() {
+        let mut cell_set = cell_set(Geocode::QuadTile, &["00", "01", "02"]);
+        cell_set.insert("030").unwrap();
+        assert_eq!(to_vec(&cell_set), vec!["00", "01", "02", "030"]);
+
+        // complete sibling groups are merged into their parent
+        for cell in ["031", "032", "033"].iter() {
+            cell_set.insert(cell).unwrap();
+        }
+        assert_eq!(to_vec(&cell_set), vec!["0"]);
+
+        assert!(cell_set.insert("04").is_err());
+
+        // inserted cells replace their descendants, and cells within
+        // existing ancestors are ignored
+        let mut cells = CellSet::new(Geocode::Geohash);
+        cells.insert("dpc5u6").unwrap();
+        cells.insert("dpc5u").unwrap();
+        cells.insert("dpc5u6").unwrap();
+        assert_eq!(to_vec(&cells), vec!["dpc5u"]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut cell_set = cell_set(Geocode::QuadTile, &["00", "030"]);
+        cell_set.extend(["01", "02", "031", "032", "033"].iter()).unwrap();
+        assert_eq!(to_vec(&cell_set), vec!["0"]);
+
+        // batches match inserting each cell in turn
+        let cells = ["dpc5u6", "dpc5u", "9xjq8", "9xjq8z", "dpc5"];
+        let mut a = CellSet::new(Geocode::Geohash);
+        a.extend(cells.iter()).unwrap();
+        let mut b = CellSet::new(Geocode::Geohash);
+        for cell in cells.iter() {
+            b.insert(cell).unwrap();
+        }
+        assert_eq!(a, b);
+
+        // invalid cells leave the set unchanged
+        assert!(cell_set.extend(["1", "04"].iter()).is_err());
+        assert_eq!(to_vec(&cell_set), vec!["0"]);
+    }
+
+    #[test]
+    fn intersect() {
+        let a = cell_set(Geocode::QuadTile, &["0", "12"]);
+        let b = cell_set(Geocode::QuadTile, &["03", "012", "1", "2"]);
+        assert_eq!(to_vec(&a.intersect(&b).unwrap()),
+            vec!["012", "03", "12"]);
+        assert_eq!(a.intersect(&b).unwrap(), b.intersect(&a).unwrap());
+    }
+
+    #[test]
+    fn union() {
+        let a = cell_set(Geocode::QuadTile, &["00", "01", "123"]);
+        let b = cell_set(Geocode::QuadTile, &["02", "03", "12"]);
+        assert_eq!(to_vec(&a.union(&b).unwrap()), vec!["0", "12"]);
+
+        let c = cell_set(Geocode::Geohash, &["0"]);
+        assert_eq!(a.union(&c), Err(GeocodeError::GeocodeMismatch {
+            expected: Geocode::QuadTile, found: Geocode::Geohash }));
+    }
+}
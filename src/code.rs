@@ -0,0 +1,276 @@
+use crate::{morton, Geocode, GeocodeError};
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::{self, Debug, Display, Formatter, Write};
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+// codes are stored inline, so short codes avoid heap allocation
+#[derive(Clone, Copy)]
+pub struct Code<const N: usize = 24> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Code<N> {
+    pub const fn new() -> Code<N> {
+        Code { bytes: [0; N], len: 0 }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // bytes are only written from complete str values
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> AsRef<str> for Code<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for Code<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Debug for Code<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Default for Code<N> {
+    fn default() -> Code<N> {
+        Code::new()
+    }
+}
+
+impl<const N: usize> Deref for Code<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Display for Code<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> Eq for Code<N> {}
+
+impl<const N: usize> Hash for Code<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<const N: usize> Ord for Code<N> {
+    fn cmp(&self, other: &Code<N>) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> PartialEq for Code<N> {
+    fn eq(&self, other: &Code<N>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for Code<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for Code<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialOrd for Code<N> {
+    fn partial_cmp(&self, other: &Code<N>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for Code<N> {
+    type Error = GeocodeError;
+
+    fn try_from(value: &str) -> Result<Code<N>, GeocodeError> {
+        let mut code = Code::new();
+        code.write_str(value)?;
+        Ok(code)
+    }
+}
+
+impl<const N: usize> Write for Code<N> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        // check if value fits within the remaining capacity
+        let len = self.len + value.len();
+        if len > N {
+            return Err(fmt::Error);
+        }
+
+        self.bytes[self.len..len].copy_from_slice(value.as_bytes());
+        self.len = len;
+        Ok(())
+    }
+}
+
+impl Geocode {
+    pub const fn encode_const<const N: usize>(&self, x: f64, y: f64,
+            precision: usize) -> Option<Code<N>> {
+        // only bisection codes of at most 64 bits are computed directly
+        // from cell indices, which is possible in const contexts
+        let (char_bits, codes) = match self.get_parameters() {
+            Some(parameters) => parameters,
+            None => return None,
+        };
+
+        // precision is checked against the capacity before computing the
+        // number of bits, which keeps large precisions from overflowing
+        let bbox = self.get_bounds();
+        if precision == 0 || precision > N || precision * char_bits > 64
+                || !bbox.contains(x, y) {
+            return None;
+        }
+
+        let bits = precision * char_bits;
+
+        // the first bit splits on x, so x holds the extra bit when the
+        // total number of bits is odd
+        let (x_bits, y_bits) = (bits.div_ceil(2), bits / 2);
+        let x_index = morton::get_index(x, bbox.min_x, bbox.width(), x_bits);
+        let y_index = morton::get_index(y, bbox.min_y, bbox.height(), y_bits);
+        let interleaved = match bits % 2 {
+            0 => morton::interleave(x_index, y_index),
+            _ => morton::spread(x_index) | (morton::spread(y_index) << 1),
+        };
+
+        let mut code = Code::new();
+        let mask = (1u64 << char_bits) - 1;
+        while code.len < precision {
+            let shift = bits - (code.len + 1) * char_bits;
+            code.bytes[code.len] =
+                codes[((interleaved >> shift) & mask) as usize] as u8;
+            code.len += 1;
+        }
+
+        Some(code)
+    }
+
+    pub fn encode_inline<const N: usize>(&self, x: f64, y: f64,
+            precision: usize) -> Result<Code<N>, GeocodeError> {
+        // check if precision fits within the code capacity
+        if precision > N {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        let mut code = Code::new();
+        self.encode_into(x, y, precision, &mut code)?;
+        Ok(code)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::Code;
+
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    const APPLETON: Code<6> =
+        match Geocode::Geohash.encode_const(-88.4, 44.266667, 6) {
+            Some(code) => code,
+            None => panic!("invalid const code"),
+        };
+
+    #[test]
+    fn code() {
+        let code: Code = Code::try_from("dpc5u6").unwrap();
+        assert_eq!(code, "dpc5u6");
+        assert_eq!(code.len(), 6);
+        assert_eq!(code.capacity(), 24);
+        assert_eq!(format!("{} {:?}", code, code), "dpc5u6 \"dpc5u6\"");
+        assert!(code.starts_with("dpc"));
+
+        // codes are ordered and hashed as strings
+        let other: Code = Code::try_from("dpc5u7").unwrap();
+        assert!(code < other);
+        let set: HashSet<Code> = vec![code, other, code].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("dpc5u7"));
+
+        assert!(Code::<4>::try_from("dpc5u6").is_err());
+        assert_eq!(Code::<4>::new().as_str(), "");
+    }
+
+    #[test]
+    fn encode_inline() {
+        let geocode = Geocode::Geohash;
+        let code: Code = geocode.encode_inline(-88.4, 44.266667, 6).unwrap();
+        assert_eq!(code, "dpc5u6");
+
+        let code: Code<11> = Geocode::PlusCode
+            .encode_inline(-88.4, 44.266667, 10).unwrap();
+        assert_eq!(code.as_str(),
+            Geocode::PlusCode.encode(-88.4, 44.266667, 10).unwrap());
+
+        assert_eq!(geocode.encode_inline::<4>(-88.4, 44.266667, 6),
+            Err(GeocodeError::InvalidPrecision { precision: 6 }));
+        assert!(Geocode::PlusCode
+            .encode_inline::<10>(-88.4, 44.266667, 10).is_err());
+    }
+
+    #[test]
+    fn encode_const() {
+        assert_eq!(APPLETON, "dpc5u6");
+
+        // const codes match runtime encoding
+        for geocode in [Geocode::Geohash, Geocode::Geohash16,
+                Geocode::QuadTile].iter() {
+            let (x, y) = match geocode {
+                Geocode::QuadTile => (-9840642.99, 5506802.68),
+                _ => (-88.4, 44.266667),
+            };
+
+            for precision in 1..13 {
+                let code: Code = geocode.encode_const(x, y, precision)
+                    .unwrap();
+                assert_eq!(code.as_str(),
+                    geocode.encode(x, y, precision).unwrap());
+            }
+        }
+
+        // cell edges belong to the lower cell
+        let geocode = Geocode::Geohash;
+        let code: Code = geocode.encode_const(0.0, 0.0, 4).unwrap();
+        assert_eq!(code.as_str(), geocode.encode(0.0, 0.0, 4).unwrap());
+
+        assert!(geocode.encode_const::<24>(-88.4, 44.266667, 13).is_none());
+        assert!(geocode.encode_const::<4>(-88.4, 44.266667, 6).is_none());
+        assert!(geocode.encode_const::<24>(-88.4, 44.266667, 0).is_none());
+        assert!(geocode.encode_const::<24>(-88.4, 44.266667, usize::MAX)
+            .is_none());
+        assert!(geocode.encode_const::<24>(200.0, 44.266667, 6).is_none());
+        assert!(Geocode::PlusCode
+            .encode_const::<24>(-88.4, 44.266667, 10).is_none());
+    }
+}
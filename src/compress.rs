@@ -0,0 +1,352 @@
+use crate::{Geocode, GeocodeError};
+use crate::error::io_error;
+
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 2] = b"GC";
+const VERSION: u8 = 1;
+// bound on decoded key lengths so corrupt streams fail quickly
+const MAX_KEY_LENGTH: usize = 64;
+
+// alphabets sorted by character so sorted keys have ascending ranks
+struct Alphabets {
+    geocode: Geocode,
+    sorted: Vec<Vec<char>>,
+}
+
+impl Alphabets {
+    fn new(geocode: Geocode) -> Alphabets {
+        Alphabets { geocode, sorted: Vec::new() }
+    }
+
+    fn get(&mut self, index: usize) -> &[char] {
+        while self.sorted.len() <= index {
+            let mut alphabet = self.geocode
+                .get_alphabet(self.sorted.len()).to_vec();
+            alphabet.sort_unstable();
+            self.sorted.push(alphabet);
+        }
+
+        &self.sorted[index]
+    }
+
+    fn get_width(&mut self, index: usize) -> usize {
+        let len = self.get(index).len();
+        (usize::BITS - (len - 1).leading_zeros()) as usize
+    }
+}
+
+pub struct CellWriter<W: Write> {
+    writer: W,
+    alphabets: Alphabets,
+    previous: Vec<char>,
+    buffer: u64,
+    bits: usize,
+}
+
+impl<W: Write> CellWriter<W> {
+    pub fn new(geocode: Geocode, mut writer: W)
+            -> Result<CellWriter<W>, GeocodeError> {
+        let index = Geocode::ALL.iter().position(|x| *x == geocode).unwrap();
+        writer.write_all(MAGIC).map_err(io_error)?;
+        writer.write_all(&[VERSION, index as u8]).map_err(io_error)?;
+
+        Ok(CellWriter { writer, alphabets: Alphabets::new(geocode),
+            previous: Vec::new(), buffer: 0, bits: 0 })
+    }
+
+    pub fn finish(mut self) -> Result<W, GeocodeError> {
+        // an impossible number of dropped characters ends the stream
+        self.write_gamma(self.previous.len() as u64 + 2)?;
+        if self.bits > 0 {
+            self.write_bits(0, 8 - self.bits)?;
+        }
+
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.writer)
+    }
+
+    pub fn write(&mut self, value: &str) -> Result<(), GeocodeError> {
+        let key: Vec<char> = self.alphabets.geocode.parse_key(value)?
+            .chars().collect();
+        if !self.previous.is_empty() && key <= self.previous {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "cell '{}' is out of order or duplicated", value) });
+        }
+
+        // encode the characters dropped from the previous key and the
+        // suffix replacing them
+        let shared = key.iter().zip(self.previous.iter())
+            .take_while(|(a, b)| a == b).count();
+        let dropped = self.previous.len() - shared;
+        self.write_gamma(dropped as u64 + 1)?;
+        self.write_gamma((key.len() - shared) as u64)?;
+
+        for (i, c) in key.iter().enumerate().skip(shared) {
+            let rank = get_rank(self.alphabets.get(i), *c);
+            if i == shared && dropped > 0 {
+                // sorting guarantees the first differing character follows
+                // the previous one, so only the difference is stored
+                let previous = get_rank(self.alphabets.get(i),
+                    self.previous[i]);
+                self.write_gamma((rank - previous) as u64)?;
+            } else {
+                let width = self.alphabets.get_width(i);
+                self.write_bits(rank as u64, width)?;
+            }
+        }
+
+        self.previous = key;
+        Ok(())
+    }
+
+    fn write_bits(&mut self, value: u64, count: usize)
+            -> Result<(), GeocodeError> {
+        self.buffer = (self.buffer << count) | value;
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            let byte = (self.buffer >> self.bits) as u8;
+            self.writer.write_all(&[byte]).map_err(io_error)?;
+        }
+
+        self.buffer &= (1 << self.bits) - 1;
+        Ok(())
+    }
+
+    fn write_gamma(&mut self, value: u64) -> Result<(), GeocodeError> {
+        // elias gamma code, a unary length followed by the value
+        let len = (u64::BITS - value.leading_zeros()) as usize;
+        self.write_bits(0, len - 1)?;
+        self.write_bits(value, len)
+    }
+}
+
+pub struct CellReader<R: Read> {
+    reader: R,
+    alphabets: Alphabets,
+    previous: Vec<char>,
+    buffer: u8,
+    bits: usize,
+    done: bool,
+}
+
+impl<R: Read> CellReader<R> {
+    pub fn new(mut reader: R) -> Result<CellReader<R>, GeocodeError> {
+        let mut header = [0u8; 4];
+        reader.read_exact(&mut header).map_err(io_error)?;
+        if &header[..2] != MAGIC || header[2] != VERSION {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "unrecognized cell list header".to_string() });
+        }
+
+        let geocode = *Geocode::ALL.get(header[3] as usize)
+            .ok_or_else(|| GeocodeError::InvalidFormat {
+                reason: format!("unknown geocode index {}", header[3]) })?;
+
+        Ok(CellReader { reader, alphabets: Alphabets::new(geocode),
+            previous: Vec::new(), buffer: 0, bits: 0, done: false })
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.alphabets.geocode
+    }
+
+    fn read_bit(&mut self) -> Result<u64, GeocodeError> {
+        if self.bits == 0 {
+            let mut byte = [0u8; 1];
+            self.reader.read_exact(&mut byte).map_err(io_error)?;
+            self.buffer = byte[0];
+            self.bits = 8;
+        }
+
+        self.bits -= 1;
+        Ok(((self.buffer >> self.bits) & 1) as u64)
+    }
+
+    fn read_bits(&mut self, count: usize) -> Result<u64, GeocodeError> {
+        let mut value = 0;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()?;
+        }
+
+        Ok(value)
+    }
+
+    fn read_gamma(&mut self) -> Result<u64, GeocodeError> {
+        let mut len = 1;
+        while self.read_bit()? == 0 {
+            len += 1;
+            if len > u64::BITS as usize {
+                return Err(GeocodeError::InvalidFormat {
+                    reason: "invalid length encoding".to_string() });
+            }
+        }
+
+        Ok((1 << (len - 1)) | self.read_bits(len - 1)?)
+    }
+
+    fn read_key(&mut self) -> Result<Option<String>, GeocodeError> {
+        let dropped = self.read_gamma()? as usize - 1;
+        if dropped > self.previous.len() {
+            return Ok(None);
+        }
+
+        let shared = self.previous.len() - dropped;
+        let len = shared.saturating_add(self.read_gamma()? as usize);
+        if len > MAX_KEY_LENGTH {
+            return Err(GeocodeError::InvalidFormat {
+                reason: format!("cell length {} exceeds limit", len) });
+        }
+
+        let mut key = self.previous[..shared].to_vec();
+        for i in shared..len {
+            let rank = match i == shared && dropped > 0 {
+                true => get_rank(self.alphabets.get(i), self.previous[i])
+                    + self.read_gamma()? as usize,
+                false => {
+                    let width = self.alphabets.get_width(i);
+                    self.read_bits(width)? as usize
+                },
+            };
+
+            let c = *self.alphabets.get(i).get(rank).ok_or_else(||
+                GeocodeError::InvalidFormat {
+                    reason: format!("invalid character rank {}", rank) })?;
+            key.push(c);
+        }
+
+        // validate the formatted code in case of corrupt structure
+        let key_string: String = key.iter().collect();
+        let code = self.alphabets.geocode.format_key(&key_string);
+        self.alphabets.geocode.parse_key(&code)?;
+
+        self.previous = key;
+        Ok(Some(code))
+    }
+}
+
+impl<R: Read> Iterator for CellReader<R> {
+    type Item = Result<String, GeocodeError>;
+
+    fn next(&mut self) -> Option<Result<String, GeocodeError>> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_key().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        result
+    }
+}
+
+impl Geocode {
+    pub fn compress_cells(&self, cells: &[String])
+            -> Result<Vec<u8>, GeocodeError> {
+        // sort by key so formatted codes are ordered consistently
+        let mut keys = Vec::new();
+        for cell in cells.iter() {
+            keys.push(self.parse_key(cell)?);
+        }
+
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut writer = CellWriter::new(*self, Vec::new())?;
+        for key in keys.iter() {
+            writer.write(&self.format_key(key))?;
+        }
+
+        writer.finish()
+    }
+
+    pub fn decompress_cells(&self, bytes: &[u8])
+            -> Result<Vec<String>, GeocodeError> {
+        let reader = CellReader::new(bytes)?;
+        if reader.get_geocode() != *self {
+            return Err(GeocodeError::GeocodeMismatch {
+                expected: *self, found: reader.get_geocode() });
+        }
+
+        reader.collect()
+    }
+}
+
+fn get_rank(alphabet: &[char], c: char) -> usize {
+    alphabet.binary_search(&c).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::{CellReader, CellWriter};
+
+    fn cells(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn compress_cells() {
+        for geocode in Geocode::ALL.iter() {
+            let bbox = geocode.get_bounds();
+            let (x, y) = bbox.center();
+            let (width, height) = (bbox.width(), bbox.height());
+            let bbox = BoundingBox::new(x - width / 1e3, x + width / 1e3,
+                y - height / 1e3, y + height / 1e3);
+
+            let mut cells = Vec::new();
+            for precision in [2, 4, 6].iter() {
+                cells.extend(geocode.cover_bbox(&bbox, *precision).unwrap());
+            }
+
+            let bytes = geocode.compress_cells(&cells).unwrap();
+            let mut decompressed = geocode.decompress_cells(&bytes).unwrap();
+            assert_eq!(decompressed.len(), cells.len());
+
+            cells.sort();
+            decompressed.sort();
+            assert_eq!(decompressed, cells);
+        }
+
+        // dense coverings shrink well below the raw codes
+        let geocode = Geocode::Geohash;
+        let cells = geocode.cover_bbox(&BoundingBox::new(-88.5, -88.0,
+            44.0, 44.5), 6).unwrap();
+        let raw: usize = cells.iter().map(|x| x.len() + 1).sum();
+        let bytes = geocode.compress_cells(&cells).unwrap();
+        assert!(bytes.len() * 4 < raw, "{} vs {}", bytes.len(), raw);
+
+        assert_eq!(Geocode::QuadTile.decompress_cells(&bytes),
+            Err(GeocodeError::GeocodeMismatch {
+                expected: Geocode::QuadTile, found: geocode }));
+        assert!(geocode.decompress_cells(&bytes[..bytes.len() / 2]).is_err());
+        assert!(geocode.decompress_cells(b"XX\x01\x00").is_err());
+    }
+
+    #[test]
+    fn stream() {
+        let geocode = Geocode::QuadTile;
+        let mut writer = CellWriter::new(geocode, Vec::new()).unwrap();
+        for cell in ["0", "0302", "031", "1", "12"].iter() {
+            writer.write(cell).unwrap();
+        }
+
+        assert!(writer.write("0").is_err());
+        assert!(writer.write("12").is_err());
+        let bytes = writer.finish().unwrap();
+
+        let reader = CellReader::new(&bytes[..]).unwrap();
+        assert_eq!(reader.get_geocode(), geocode);
+        assert_eq!(reader.collect::<Result<Vec<String>, GeocodeError>>()
+            .unwrap(), cells(&["0", "0302", "031", "1", "12"]));
+
+        // empty lists store only the header and terminator
+        let bytes = CellWriter::new(geocode, Vec::new()).unwrap()
+            .finish().unwrap();
+        assert_eq!(bytes.len(), 5);
+        assert_eq!(CellReader::new(&bytes[..]).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{project, Geocode, GeocodeError};
+
+pub trait Coordinate {
+    fn get_native(&self, geocode: &Geocode) -> (f64, f64);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LonLat {
+    pub lon: f64,
+    pub lat: f64,
+}
+
+impl LonLat {
+    pub fn new(lon: f64, lat: f64) -> LonLat {
+        LonLat { lon, lat }
+    }
+}
+
+impl Coordinate for LonLat {
+    fn get_native(&self, geocode: &Geocode) -> (f64, f64) {
+        geocode.wgs84_to_native(self.lon, self.lat)
+    }
+}
+
+impl From<Mercator> for LonLat {
+    fn from(mercator: Mercator) -> LonLat {
+        let (lon, lat) = project::mercator_to_wgs84(mercator.x, mercator.y);
+        LonLat { lon, lat }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mercator {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Mercator {
+    pub fn new(x: f64, y: f64) -> Mercator {
+        Mercator { x, y }
+    }
+}
+
+impl Coordinate for Mercator {
+    fn get_native(&self, geocode: &Geocode) -> (f64, f64) {
+        // avoid reprojecting coordinates already in web mercator
+        match geocode.get_epsg_code() {
+            3857 => (self.x, self.y),
+            _ => {
+                let lonlat = LonLat::from(*self);
+                geocode.wgs84_to_native(lonlat.lon, lonlat.lat)
+            },
+        }
+    }
+}
+
+impl From<LonLat> for Mercator {
+    fn from(lonlat: LonLat) -> Mercator {
+        let (x, y) = project::wgs84_to_mercator(lonlat.lon, lonlat.lat);
+        Mercator { x, y }
+    }
+}
+
+impl Geocode {
+    pub fn encode_coordinate<C: Coordinate>(&self, coordinate: &C,
+            precision: usize) -> Result<String, GeocodeError> {
+        let (x, y) = coordinate.get_native(self);
+        self.encode(x, y, precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Geocode;
+    use super::{LonLat, Mercator};
+
+    #[test]
+    fn conversion() {
+        let mercator = Mercator::from(LonLat::new(-88.4, 44.266667));
+        assert!((mercator.x - -9840642.99).abs() < 0.01);
+        assert!((mercator.y - 5506802.68).abs() < 0.01);
+
+        let lonlat = LonLat::from(mercator);
+        assert!((lonlat.lon - -88.4).abs() < 1e-9);
+        assert!((lonlat.lat - 44.266667).abs() < 1e-9);
+    }
+
+    #[test]
+    fn encode_coordinate() {
+        let lonlat = LonLat::new(-88.4, 44.266667);
+        let mercator = Mercator::new(-9840642.99, 5506802.68);
+
+        // coordinates are projected to the geocode's native crs
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.encode_coordinate(&lonlat, 6).unwrap(), "030222");
+        assert_eq!(geocode.encode_coordinate(&mercator, 6).unwrap(),
+            "030222");
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_coordinate(&lonlat, 6).unwrap(),
+            geocode.encode(-88.4, 44.266667, 6).unwrap());
+        assert_eq!(geocode.encode_coordinate(&mercator, 6).unwrap(),
+            geocode.encode(-88.4, 44.266667, 6).unwrap());
+
+        assert!(geocode.encode_coordinate(&LonLat::new(0.0, 91.0), 6)
+            .is_err());
+    }
+}
@@ -0,0 +1,630 @@
+use crate::{BoundingBox, Geocode, GeocodeError, Polygon};
+use crate::distance::{self, EARTH_MEAN_RADIUS};
+use crate::polygon;
+
+use std::collections::HashSet;
+
+// tolerance (as a fraction of a cell interval) used to absorb floating
+// point error when snapping bounds to cell edges
+const EDGE_EPSILON: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoverMode {
+    Centroid,
+    Contained,
+    Intersecting,
+}
+
+impl Geocode {
+    pub fn cover_bbox(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        let (bbox, west) = self.split_bbox(bbox, precision)?;
+        let mut cells = self.cover_cells(&bbox, precision)?;
+        if let Some(west) = west {
+            // halves of boxes crossing the antimeridian may share cells
+            let mut visited: HashSet<String> = cells.iter().cloned().collect();
+            for cell in self.cover_cells(&west, precision)? {
+                if visited.insert(cell.clone()) {
+                    cells.push(cell);
+                }
+            }
+        }
+
+        Ok(cells)
+    }
+
+    pub fn cover_polygon(&self, polygon: &Polygon, precision: usize,
+            mode: CoverMode) -> Result<Vec<String>, GeocodeError> {
+        polygon.validate()?;
+
+        // filter candidate cells from the polygon bounding box
+        let mut cells = Vec::new();
+        for cell in self.cover_bbox(&polygon.bounding_box(), precision)? {
+            let bbox = self.decode(&cell)?;
+            let (x, y) = bbox.center();
+            let covered = match mode {
+                CoverMode::Centroid => polygon.contains(x, y),
+                CoverMode::Contained => polygon.contains(x, y)
+                    && !polygon.crosses_interior(&bbox),
+                CoverMode::Intersecting => polygon.contains(x, y)
+                    || polygon.crosses_interior(&bbox),
+            };
+
+            if covered {
+                cells.push(cell);
+            }
+        }
+
+        Ok(cells)
+    }
+
+    pub fn cover_radius(&self, x: f64, y: f64, radius: f64,
+            precision: usize) -> Result<Vec<String>, GeocodeError> {
+        // check if coordinates and radius are valid
+        let bounds = self.get_bounds();
+        if !bounds.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds });
+        } else if radius.is_nan() || radius < 0.0 || radius.is_infinite() {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: format!("invalid radius {}", radius) });
+        }
+
+        let (lon, lat) = self.native_to_wgs84(x, y);
+        let bbox = self.radius_bbox(lon, lat, radius);
+
+        // filter cells by distance to the nearest point of each cell
+        let mut cells = Vec::new();
+        for cell in self.cover_bbox(&bbox, precision)? {
+            let cell_bbox = self.decode(&cell)?;
+
+            // measure against the copy of x nearest to the cell, so
+            // cells across the antimeridian are not considered distant
+            let (center_x, _) = cell_bbox.center();
+            let x = match x - center_x {
+                offset if offset > bounds.width() / 2f64 =>
+                    x - bounds.width(),
+                offset if offset < -bounds.width() / 2f64 =>
+                    x + bounds.width(),
+                _ => x,
+            };
+
+            let cell_x = x.clamp(cell_bbox.min_x, cell_bbox.max_x);
+            let cell_y = y.clamp(cell_bbox.min_y, cell_bbox.max_y);
+            let (cell_lon, cell_lat) = self.native_to_wgs84(cell_x, cell_y);
+
+            if distance::haversine(lon, lat, cell_lon, cell_lat) <= radius {
+                cells.push(cell);
+            }
+        }
+
+        Ok(cells)
+    }
+
+    pub fn cover_polyline(&self, points: &[(f64, f64)], buffer: f64,
+            precision: usize) -> Result<Vec<String>, GeocodeError> {
+        // check if points and buffer are valid
+        let bounds = self.get_bounds();
+        if points.is_empty() {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: "polyline requires at least one point".to_string() });
+        } else if buffer.is_nan() || buffer < 0.0 || buffer.is_infinite() {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: format!("invalid buffer {}", buffer) });
+        }
+
+        for (x, y) in points.iter() {
+            if !bounds.contains(*x, *y) {
+                return Err(GeocodeError::CoordinateOutOfRange {
+                    x: *x, y: *y, bounds });
+            }
+        }
+
+        let mut cells = Vec::new();
+        let mut visited = HashSet::new();
+        let mut push = |cell: String| {
+            if visited.insert(cell.clone()) {
+                cells.push(cell);
+            }
+        };
+
+        // single points are covered by their surrounding radius
+        if points.len() == 1 {
+            for cell in self.cover_radius(points[0].0,
+                    points[0].1, buffer, precision)? {
+                push(cell);
+            }
+
+            return Ok(cells);
+        }
+
+        for segment in points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            if buffer == 0.0 {
+                // compute cells the segment passes through
+                push(self.encode(start.0, start.1, precision)?);
+
+                let bbox = BoundingBox::new(start.0.min(end.0),
+                    start.0.max(end.0), start.1.min(end.1),
+                    start.1.max(end.1));
+                for cell in self.cover_bbox(&bbox, precision)? {
+                    let cell_bbox = self.decode(&cell)?;
+                    if polygon::segment_crosses(start, end, &cell_bbox) {
+                        push(cell);
+                    }
+                }
+
+                push(self.encode(end.0, end.1, precision)?);
+                continue;
+            }
+
+            // compute cells within the buffer distance of the segment
+            let (start_lon, start_lat) = self.native_to_wgs84(start.0, start.1);
+            let (end_lon, end_lat) = self.native_to_wgs84(end.0, end.1);
+            let start_bbox = self.radius_bbox(start_lon, start_lat, buffer);
+            let end_bbox = self.radius_bbox(end_lon, end_lat, buffer);
+            let (min_x, max_x) = match start_bbox.min_x > start_bbox.max_x
+                    || end_bbox.min_x > end_bbox.max_x {
+                // buffers crossing the antimeridian span all x values
+                true => (bounds.min_x, bounds.max_x),
+                false => (start_bbox.min_x.min(end_bbox.min_x),
+                    start_bbox.max_x.max(end_bbox.max_x)),
+            };
+            let bbox = BoundingBox::new(min_x, max_x,
+                start_bbox.min_y.min(end_bbox.min_y),
+                start_bbox.max_y.max(end_bbox.max_y));
+
+            let lat = (start_lat + end_lat) / 2f64;
+            let start = distance::to_local(start_lon, start_lat, lat);
+            let end = distance::to_local(end_lon, end_lat, lat);
+            for cell in self.cover_bbox(&bbox, precision)? {
+                let cell_bbox = self.decode(&cell)?;
+                let (min_lon, min_lat) = self.native_to_wgs84(
+                    cell_bbox.min_x, cell_bbox.min_y);
+                let (max_lon, max_lat) = self.native_to_wgs84(
+                    cell_bbox.max_x, cell_bbox.max_y);
+                let (min_x, min_y) = distance::to_local(min_lon, min_lat, lat);
+                let (max_x, max_y) = distance::to_local(max_lon, max_lat, lat);
+
+                let local_bbox = BoundingBox::new(min_x, max_x, min_y, max_y);
+                if distance::segment_bbox_distance(start, end, &local_bbox)
+                        <= buffer {
+                    push(cell);
+                }
+            }
+        }
+
+        Ok(cells)
+    }
+
+    pub(crate) fn has_upper_edges(&self) -> bool {
+        // coordinates on cell edges belong to the east or north cell,
+        // where bisection geocodes assign them to the west or south cell
+        matches!(self, Geocode::Geohash36 | Geocode::Maidenhead
+            | Geocode::PlusCode)
+    }
+
+    pub(crate) fn split_bbox(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<(BoundingBox, Option<BoundingBox>), GeocodeError> {
+        // check if bounding box and precision are valid
+        if bbox.min_y > bbox.max_y || bbox.min_x.is_nan()
+                || bbox.max_x.is_nan() || bbox.min_y.is_nan()
+                || bbox.max_y.is_nan() {
+            return Err(GeocodeError::InvalidBoundingBox { bounds: *bbox });
+        } else if !self.is_valid_precision(precision) {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // boxes where min_x exceeds max_x cross the antimeridian, and are
+        // split into their east and west halves
+        let bounds = self.get_bounds();
+        match bbox.min_x > bbox.max_x {
+            true => Ok((BoundingBox::new(bbox.min_x, bounds.max_x,
+                    bbox.min_y, bbox.max_y),
+                Some(BoundingBox::new(bounds.min_x, bbox.max_x,
+                    bbox.min_y, bbox.max_y)))),
+            false => Ok((*bbox, None)),
+        }
+    }
+
+    fn cover_cells(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        let bounds = self.get_bounds();
+
+        // compute cell index ranges
+        let (x_interval, y_interval) = self.get_intervals(precision);
+        let upper = self.has_upper_edges();
+        let (min_i, max_i) = match cell_range(bbox.min_x, bbox.max_x,
+                bounds.min_x, bounds.max_x, x_interval, upper) {
+            Some(range) => range,
+            None => return Ok(Vec::new()),
+        };
+        let (min_j, max_j) = match cell_range(bbox.min_y, bbox.max_y,
+                bounds.min_y, bounds.max_y, y_interval, upper) {
+            Some(range) => range,
+            None => return Ok(Vec::new()),
+        };
+
+        // encode the center of each intersecting cell
+        let mut cells = Vec::with_capacity(
+            (max_i - min_i + 1) * (max_j - min_j + 1));
+        for j in min_j..=max_j {
+            let y = bounds.min_y + (j as f64 + 0.5) * y_interval;
+            for i in min_i..=max_i {
+                let x = bounds.min_x + (i as f64 + 0.5) * x_interval;
+                cells.push(self.encode(x, y, precision)?);
+            }
+        }
+
+        Ok(cells)
+    }
+
+    fn radius_bbox(&self, lon: f64, lat: f64, radius: f64) -> BoundingBox {
+        // compute radius bounding box in wgs84 coordinates
+        let lat_delta = (radius / EARTH_MEAN_RADIUS).to_degrees();
+        let (min_lat, max_lat) = (lat - lat_delta, lat + lat_delta);
+        // longitude delta widens with the maximum absolute latitude
+        let max_abs_lat = min_lat.abs().max(max_lat.abs());
+        let lon_delta = lat_delta / max_abs_lat.to_radians().cos();
+        let (min_lon, max_lon) = if min_lat <= -90.0 || max_lat >= 90.0
+                || lon_delta.is_nan() || lon_delta >= 180.0 {
+            // circle contains a pole or spans all longitudes
+            (-180.0, 180.0)
+        } else {
+            // wrap longitudes which cross the antimeridian
+            let wrap = |x: f64| (x + 180.0).rem_euclid(360.0) - 180.0;
+            match (lon - lon_delta < -180.0, lon + lon_delta > 180.0) {
+                (false, false) => (lon - lon_delta, lon + lon_delta),
+                _ => (wrap(lon - lon_delta), wrap(lon + lon_delta)),
+            }
+        };
+
+        // convert to geocode coordinates
+        let (min_x, min_y) = self.wgs84_to_native(min_lon,
+            min_lat.max(-90.0));
+        let (max_x, max_y) = self.wgs84_to_native(max_lon,
+            max_lat.min(90.0));
+        BoundingBox::new(min_x, max_x, min_y, max_y)
+    }
+}
+
+pub(crate) fn cell_range(min: f64, max: f64, bounds_min: f64,
+        bounds_max: f64, interval: f64, upper: bool)
+        -> Option<(usize, usize)> {
+    // check if range intersects geocode bounds, where intervals wider than
+    // the bounds leave no whole cells
+    let count = ((bounds_max - bounds_min) / interval).round() as usize;
+    if max < bounds_min || min > bounds_max || count == 0 {
+        return None;
+    }
+
+    // values within the tolerance of a cell edge lie on it, and include
+    // the cell the edge is assigned to
+    let index = |value: f64| {
+        let scaled = (value.clamp(bounds_min, bounds_max) - bounds_min)
+            / interval;
+        let index = match upper {
+            true => (scaled + EDGE_EPSILON).floor(),
+            false => (scaled - EDGE_EPSILON).ceil() - 1f64,
+        };
+
+        index.clamp(0f64, (count - 1) as f64) as usize
+    };
+
+    Some((index(min), index(max)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CoverMode, Geocode, GeocodeError, Polygon};
+
+    #[test]
+    fn cover_bbox() {
+        let geocode = Geocode::Geohash;
+        let bbox = BoundingBox::new(-88.45, -88.35, 44.24, 44.28);
+        let mut cells = geocode.cover_bbox(&bbox, 5).unwrap();
+        cells.sort();
+        assert_eq!(cells, vec!["dpc5e", "dpc5g", "dpc5s",
+            "dpc5t", "dpc5u", "dpc5v"]);
+
+        // check every cell intersects the bounding box
+        let cells = geocode.cover_bbox(&bbox, 7).unwrap();
+        for cell in cells.iter() {
+            assert!(geocode.decode(cell).unwrap().intersects(&bbox));
+        }
+
+        // check points within the bounding box are covered
+        for (x, y) in [(-88.45, 44.24), (-88.35, 44.28), (-88.4, 44.26)]
+                .iter() {
+            let code = geocode.encode(*x, *y, 7).unwrap();
+            assert!(cells.contains(&code));
+        }
+    }
+
+    #[test]
+    fn cover_bbox_antimeridian() {
+        // boxes crossing the antimeridian cover both sides
+        let geocode = Geocode::Geohash;
+        let bbox = BoundingBox::new(170.0, -170.0, -5.0, 5.0);
+        let cells = geocode.cover_bbox(&bbox, 2).unwrap();
+        for (x, y) in [(175.0, 0.0), (-175.0, 0.0), (180.0, 4.0)].iter() {
+            let code = geocode.encode(*x, *y, 2).unwrap();
+            assert!(cells.contains(&code));
+        }
+
+        let code = geocode.encode(0.0, 0.0, 2).unwrap();
+        assert!(!cells.contains(&code));
+
+        // overlapping halves do not produce duplicate cells
+        let bbox = BoundingBox::new(170.0, 160.0, -5.0, 5.0);
+        let mut cells = geocode.cover_bbox(&bbox, 2).unwrap();
+        let count = cells.len();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), count);
+        assert_eq!(count, geocode.cover_bbox(&BoundingBox::new(-180.0,
+            180.0, -5.0, 5.0), 2).unwrap().len());
+    }
+
+    #[test]
+    fn cover_bbox_cell() {
+        // covering the interior of a cell returns exactly that cell
+        for (geocode, code) in [(Geocode::Geohash, "dpc5u6"),
+                (Geocode::Geohash16, "65565d"),
+                (Geocode::QuadTile, "030222")].iter() {
+            let cell = geocode.decode(code).unwrap();
+            let (x, y) = (cell.width() * 1e-6, cell.height() * 1e-6);
+            let bbox = BoundingBox::new(cell.min_x + x, cell.max_x - x,
+                cell.min_y + y, cell.max_y - y);
+            assert_eq!(geocode.cover_bbox(&bbox, code.len()).unwrap(),
+                vec![code.to_string()]);
+
+            let mut children =
+                geocode.cover_bbox(&bbox, code.len() + 1).unwrap();
+            let mut expected: Vec<String> =
+                geocode.children(code).unwrap().collect();
+            children.sort();
+            expected.sort();
+            assert_eq!(children, expected);
+        }
+    }
+
+    #[test]
+    fn cover_bbox_edges() {
+        // cells of points on the bounding box edges are covered
+        for geocode in Geocode::ALL.iter() {
+            let bounds = geocode.get_bounds();
+            let (x, y) = bounds.center();
+            let bbox = BoundingBox::new(x, x + bounds.width() / 4f64,
+                y, y + bounds.height() / 4f64);
+            for precision in [2, 4].iter() {
+                let cells = geocode.cover_bbox(&bbox, *precision).unwrap();
+                for (x, y) in [(bbox.min_x, bbox.min_y),
+                        (bbox.max_x, bbox.min_y), (bbox.min_x, bbox.max_y),
+                        (bbox.max_x, bbox.max_y)].iter() {
+                    let code = geocode.encode(*x, *y, *precision).unwrap();
+                    assert!(cells.contains(&code), "{:?} {}", geocode, code);
+                }
+            }
+        }
+
+        let bbox = BoundingBox::new(0.0, 1.0, 0.0, 1.0);
+        let cells = Geocode::QuadTile.cover_bbox(&bbox, 1).unwrap();
+        assert!(cells.contains(&"2".to_string()));
+
+        let bbox = BoundingBox::new(0.0, 10.0, 0.0, 10.0);
+        let cells = Geocode::Geohash.cover_bbox(&bbox, 1).unwrap();
+        assert!(cells.contains(&"7".to_string()));
+    }
+
+    #[test]
+    fn cover_bbox_bounds() {
+        let geocode = Geocode::QuadTile;
+        let bbox = BoundingBox::new(-1e10, 1e10, -1e10, 1e10);
+        assert_eq!(geocode.cover_bbox(&bbox, 2).unwrap().len(), 16);
+
+        let bbox = BoundingBox::new(2.1e7, 2.2e7, 0.0, 1.0);
+        assert!(geocode.cover_bbox(&bbox, 2).unwrap().is_empty());
+
+        let bbox = BoundingBox::new(0.0, 1.0, 10.0, 0.0);
+        assert_eq!(geocode.cover_bbox(&bbox, 2),
+            Err(GeocodeError::InvalidBoundingBox { bounds: bbox }));
+
+        // nan in any field is invalid
+        for i in 0..4 {
+            let mut values = [0.0, 1.0, 0.0, 1.0];
+            values[i] = f64::NAN;
+            let bbox = BoundingBox::new(values[0], values[1], values[2],
+                values[3]);
+            assert!(matches!(geocode.cover_bbox(&bbox, 2),
+                Err(GeocodeError::InvalidBoundingBox { .. })));
+        }
+        let bbox = BoundingBox::new(0.0, 1.0, 0.0, 1.0);
+        assert_eq!(geocode.cover_bbox(&bbox, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+
+        // precisions are checked against each geocode
+        for (geocode, precision) in [(Geocode::Maidenhead, 1),
+                (Geocode::Maidenhead, 3), (Geocode::PlusCode, 1),
+                (Geocode::PlusCode, 9), (Geocode::PlusCode, 16)].iter() {
+            assert_eq!(geocode.cover_bbox(&bbox, *precision),
+                Err(GeocodeError::InvalidPrecision {
+                    precision: *precision }));
+        }
+
+        // intervals wider than the bounds leave no cells
+        assert_eq!(super::cell_range(0.0, 1.0, 0.0, 1.0, 3.0, false), None);
+    }
+
+    #[test]
+    fn cover_polygon() {
+        // triangle with a hole spanning many precision 3 geohash cells
+        let geocode = Geocode::Geohash;
+        let polygon = Polygon::new(
+            vec![(-100.0, 30.0), (-80.0, 30.0), (-90.0, 50.0)],
+            vec![vec![(-92.0, 35.0), (-88.0, 35.0), (-90.0, 39.0)]]);
+
+        let intersecting = geocode.cover_polygon(&polygon, 3,
+            CoverMode::Intersecting).unwrap();
+        let centroid = geocode.cover_polygon(&polygon, 3,
+            CoverMode::Centroid).unwrap();
+        let contained = geocode.cover_polygon(&polygon, 3,
+            CoverMode::Contained).unwrap();
+
+        // check each mode is a subset of a less restrictive mode
+        assert!(!contained.is_empty());
+        assert!(contained.len() < centroid.len());
+        assert!(centroid.len() < intersecting.len());
+        assert!(contained.iter().all(|x| centroid.contains(x)));
+        assert!(centroid.iter().all(|x| intersecting.contains(x)));
+
+        // check points within the polygon are covered
+        for (x, y) in [(-90.0, 45.0), (-98.0, 31.0), (-90.0, 34.0)].iter() {
+            let code = geocode.encode(*x, *y, 3).unwrap();
+            assert!(intersecting.contains(&code));
+        }
+
+        // check cells within the hole are excluded
+        let code = geocode.encode(-90.0, 36.5, 4).unwrap();
+        let cells = geocode.cover_polygon(&polygon, 4,
+            CoverMode::Intersecting).unwrap();
+        assert!(!cells.contains(&code));
+
+        for cell in contained.iter() {
+            let bbox = geocode.decode(cell).unwrap();
+            assert!(polygon.contains(bbox.min_x, bbox.min_y));
+            assert!(polygon.contains(bbox.max_x, bbox.max_y));
+        }
+    }
+
+    #[test]
+    fn cover_polygon_cell() {
+        // a polygon matching a cell is covered by that cell
+        let geocode = Geocode::QuadTile;
+        let bbox = geocode.decode("0302").unwrap();
+        let polygon = Polygon::new(vec![(bbox.min_x, bbox.min_y),
+            (bbox.max_x, bbox.min_y), (bbox.max_x, bbox.max_y),
+            (bbox.min_x, bbox.max_y)], Vec::new());
+
+        for mode in [CoverMode::Centroid, CoverMode::Contained,
+                CoverMode::Intersecting].iter() {
+            assert_eq!(geocode.cover_polygon(&polygon, 4, *mode).unwrap(),
+                vec!["0302"]);
+        }
+
+        let polygon = Polygon::new(vec![(0.0, 0.0), (1.0, 1.0)], Vec::new());
+        assert!(geocode.cover_polygon(&polygon, 4,
+            CoverMode::Intersecting).is_err());
+    }
+
+    #[test]
+    fn cover_radius() {
+        let geocode = Geocode::Geohash;
+        let cells = geocode.cover_radius(-88.4, 44.266667, 1000.0, 6).unwrap();
+        assert!(cells.contains(&"dpc5u6".to_string()));
+
+        // check points within the radius are covered
+        for (x, y) in [(-88.4, 44.2756), (-88.4125, 44.266667),
+                (-88.3875, 44.266667), (-88.4, 44.2577)].iter() {
+            let code = geocode.encode(*x, *y, 6).unwrap();
+            assert!(cells.contains(&code), "{}", code);
+        }
+
+        // check cells beyond the radius are excluded
+        let code = geocode.encode(-88.4, 44.2847, 6).unwrap();
+        assert!(!cells.contains(&code));
+        let code = geocode.encode(-88.38, 44.2847, 6).unwrap();
+        assert!(!cells.contains(&code));
+
+        // check longitude extent accounts for latitude
+        let equator = geocode.cover_radius(0.0, 0.0, 10000.0, 5).unwrap();
+        let north = geocode.cover_radius(0.0, 60.0, 10000.0, 5).unwrap();
+        assert!(north.len() > equator.len());
+
+        // circles around the pole span all longitudes
+        let cells = geocode.cover_radius(0.0, 89.9, 50000.0, 2).unwrap();
+        assert_eq!(cells.len(), 32);
+
+        // circles crossing the antimeridian cover both sides
+        let cells = geocode.cover_radius(179.99, 0.0, 5000.0, 6).unwrap();
+        assert!(cells.contains(&geocode.encode(-179.99, 0.0, 6).unwrap()));
+        assert!(cells.contains(&geocode.encode(179.99, 0.0, 6).unwrap()));
+        assert!(!cells.contains(&geocode.encode(-179.9, 0.0, 6).unwrap()));
+
+        assert!(geocode.cover_radius(0.0, 0.0, -1.0, 5).is_err());
+        assert!(geocode.cover_radius(0.0, 91.0, 1.0, 5).is_err());
+    }
+
+    #[test]
+    fn cover_radius_quadtile() {
+        let geocode = Geocode::QuadTile;
+        let (x, y) = (-9840642.99, 5506802.68);
+        let cells = geocode.cover_radius(x, y, 5000.0, 12).unwrap();
+        assert!(cells.contains(&geocode.encode(x, y, 12).unwrap()));
+
+        // mercator distances are scaled by latitude
+        let code = geocode.encode(x + 6000.0, y, 12).unwrap();
+        assert!(cells.contains(&code));
+        let code = geocode.encode(x + 8000.0, y, 12).unwrap();
+        assert!(!cells.contains(&code));
+    }
+
+    #[test]
+    fn cover_polyline() {
+        let geocode = Geocode::Geohash;
+        let points = [(-88.41, 44.26), (-88.39, 44.26), (-88.39, 44.28)];
+        let cells = geocode.cover_polyline(&points, 0.0, 7).unwrap();
+
+        // check cells are ordered along the polyline
+        assert_eq!(cells[0], geocode.encode(-88.41, 44.26, 7).unwrap());
+        assert_eq!(cells[cells.len() - 1],
+            geocode.encode(-88.39, 44.28, 7).unwrap());
+
+        // check points along the polyline are covered
+        for i in 0..=100 {
+            let x = -88.41 + 0.02 * (i as f64 / 100.0);
+            let code = geocode.encode(x, 44.26, 7).unwrap();
+            assert!(cells.contains(&code));
+
+            let y = 44.26 + 0.02 * (i as f64 / 100.0);
+            let code = geocode.encode(-88.39, y, 7).unwrap();
+            assert!(cells.contains(&code));
+        }
+
+        // check cells are unique
+        let mut unique = cells.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), cells.len());
+
+        let code = geocode.encode(-88.40, 44.27, 7).unwrap();
+        assert!(!cells.contains(&code));
+    }
+
+    #[test]
+    fn cover_polyline_buffer() {
+        let geocode = Geocode::Geohash;
+        let points = [(-88.41, 44.26), (-88.39, 44.26)];
+        let line = geocode.cover_polyline(&points, 0.0, 7).unwrap();
+        let corridor = geocode.cover_polyline(&points, 500.0, 7).unwrap();
+        assert!(line.iter().all(|x| corridor.contains(x)));
+
+        // ~333m north of the line is within the buffer, ~667m is not
+        let code = geocode.encode(-88.40, 44.263, 7).unwrap();
+        assert!(corridor.contains(&code));
+        let code = geocode.encode(-88.40, 44.266, 7).unwrap();
+        assert!(!corridor.contains(&code));
+
+        // a single point is covered by its radius
+        let mut cells = geocode.cover_polyline(&points[..1], 500.0, 7)
+            .unwrap();
+        let mut expected = geocode.cover_radius(-88.41, 44.26, 500.0, 7)
+            .unwrap();
+        cells.sort();
+        expected.sort();
+        assert_eq!(cells, expected);
+
+        assert!(geocode.cover_polyline(&[], 0.0, 7).is_err());
+        assert!(geocode.cover_polyline(&points, -1.0, 7).is_err());
+    }
+}
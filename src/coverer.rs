@@ -0,0 +1,201 @@
+use crate::{CoverMode, Geocode, GeocodeError, Polygon};
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverOptions {
+    pub max_cells: usize,
+    pub min_precision: usize,
+    pub max_precision: usize,
+}
+
+impl CoverOptions {
+    pub fn new(max_cells: usize, max_precision: usize) -> CoverOptions {
+        CoverOptions { max_cells, min_precision: 1, max_precision }
+    }
+}
+
+// a partially covered cell ordered by the area removed when subdivided
+struct Candidate {
+    gain: f64,
+    cell: String,
+    children: Vec<(String, bool)>,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.gain.total_cmp(&other.gain)
+            .then_with(|| other.cell.cmp(&self.cell))
+    }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Geocode {
+    pub fn cover_polygon_budget(&self, polygon: &Polygon,
+            options: &CoverOptions) -> Result<Vec<String>, GeocodeError> {
+        // check if options are valid
+        if options.max_cells == 0 {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: "cell budget must be positive".to_string() });
+        } else if options.min_precision == 0
+                || options.min_precision > options.max_precision {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: options.min_precision });
+        }
+
+        // start from the minimum precision covering, which is returned
+        // as is when it already exceeds the budget
+        let cells = self.cover_polygon(polygon, options.min_precision,
+            CoverMode::Intersecting)?;
+        let mut count = cells.len();
+
+        let (mut covering, mut candidates) = (Vec::new(), BinaryHeap::new());
+        for cell in cells {
+            let contained = self.is_contained(polygon, &cell)?;
+            self.push_candidate(polygon, cell, contained, options,
+                &mut covering, &mut candidates)?;
+        }
+
+        // greedily subdivide the cells removing the most excess area
+        // while the budget allows
+        while let Some(candidate) = candidates.pop() {
+            if count - 1 + candidate.children.len() > options.max_cells {
+                covering.push(candidate.cell);
+                continue;
+            }
+
+            count = count + candidate.children.len() - 1;
+            for (child, contained) in candidate.children {
+                self.push_candidate(polygon, child, contained, options,
+                    &mut covering, &mut candidates)?;
+            }
+        }
+
+        self.compact(covering)
+    }
+
+    fn is_contained(&self, polygon: &Polygon, value: &str)
+            -> Result<bool, GeocodeError> {
+        let bbox = self.decode(value)?;
+        let (x, y) = bbox.center();
+        Ok(polygon.contains(x, y) && !polygon.crosses_interior(&bbox))
+    }
+
+    fn push_candidate(&self, polygon: &Polygon, cell: String,
+            contained: bool, options: &CoverOptions,
+            covering: &mut Vec<String>,
+            candidates: &mut BinaryHeap<Candidate>)
+            -> Result<(), GeocodeError> {
+        if contained || self.get_precision(&cell)? >= options.max_precision {
+            covering.push(cell);
+            return Ok(());
+        }
+
+        // retain the children intersecting the polygon
+        let mut children = Vec::new();
+        let mut area = 0.0;
+        for child in self.children(&cell)? {
+            let bbox = self.decode(&child)?;
+            let (x, y) = bbox.center();
+            if polygon.contains(x, y) || polygon.crosses_interior(&bbox) {
+                area += bbox.width() * bbox.height();
+                let contained = !polygon.crosses_interior(&bbox);
+                children.push((child, contained));
+            }
+        }
+
+        let bbox = self.decode(&cell)?;
+        let gain = bbox.width() * bbox.height() - area;
+        candidates.push(Candidate { gain, cell, children });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CoverMode, Geocode, GeocodeError, Polygon};
+    use super::CoverOptions;
+
+    fn get_area(geocode: Geocode, cells: &[String]) -> f64 {
+        cells.iter().map(|x| geocode.decode(x).unwrap())
+            .map(|x| x.width() * x.height()).sum()
+    }
+
+    #[test]
+    fn cover_polygon_budget() {
+        let geocode = Geocode::Geohash;
+        let polygon = Polygon::new(vec![(-89.0, 44.0), (-88.0, 44.0),
+            (-88.0, 45.0), (-89.0, 44.0)], Vec::new());
+
+        let mut previous = f64::INFINITY;
+        for max_cells in [8, 16, 32, 64, 128].iter() {
+            let options = CoverOptions::new(*max_cells, 6);
+            let cells = geocode.cover_polygon_budget(&polygon, &options)
+                .unwrap();
+            assert!(!cells.is_empty() && cells.len() <= *max_cells);
+
+            // larger budgets reduce over-coverage
+            let area = get_area(geocode, &cells);
+            assert!(area <= previous);
+            previous = area;
+
+            // points within the polygon remain covered
+            for (x, y) in [(-88.01, 44.01), (-88.2, 44.5), (-88.01, 44.98),
+                    (-88.98, 44.01)].iter() {
+                let code = geocode.encode(*x, *y, 6).unwrap();
+                assert!(cells.iter().any(|x| code.starts_with(x.as_str())));
+            }
+        }
+
+        // mixed precisions are used within the budget
+        let options = CoverOptions::new(128, 6);
+        let cells = geocode.cover_polygon_budget(&polygon, &options).unwrap();
+        let precisions: Vec<usize> = cells.iter().map(|x| x.len()).collect();
+        assert!(precisions.iter().min() < precisions.iter().max());
+
+        // an unlimited budget matches the compacted fixed precision covering
+        let options = CoverOptions::new(usize::MAX, 4);
+        let mut cells = geocode.cover_polygon_budget(&polygon, &options)
+            .unwrap();
+        let mut expected = geocode.compact(geocode.cover_polygon(&polygon, 4,
+            CoverMode::Intersecting).unwrap()).unwrap();
+        cells.sort();
+        expected.sort();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn invalid() {
+        let geocode = Geocode::Geohash;
+        let polygon = Polygon::new(vec![(-89.0, 44.0), (-88.0, 44.0),
+            (-88.0, 45.0)], Vec::new());
+        assert!(geocode.cover_polygon_budget(&polygon,
+            &CoverOptions::new(0, 6)).is_err());
+
+        let mut options = CoverOptions::new(16, 6);
+        options.min_precision = 7;
+        assert_eq!(geocode.cover_polygon_budget(&polygon, &options),
+            Err(GeocodeError::InvalidPrecision { precision: 7 }));
+
+        // the minimum precision covering is kept when over budget
+        options.min_precision = 4;
+        options.max_cells = 1;
+        assert_eq!(geocode.cover_polygon_budget(&polygon, &options).unwrap()
+            .len(), geocode.cover_polygon(&polygon, 4,
+            CoverMode::Intersecting).unwrap().len());
+    }
+}
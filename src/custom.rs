@@ -0,0 +1,273 @@
+use crate::{decode_bits, encode_bits, math, BoundingBox, GeocodeError,
+    GridSystem, QUADTILE_CHARS};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AxisOrder {
+    XFirst,
+    YFirst,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomGeocode {
+    bounds: BoundingBox,
+    // ordered by bisection value
+    alphabet: Vec<char>,
+    char_bits: usize,
+    epsg_code: u32,
+    order: AxisOrder,
+}
+
+impl CustomGeocode {
+    pub fn builder() -> CustomGeocodeBuilder {
+        CustomGeocodeBuilder { bounds: None, alphabet: Vec::new(),
+            bits_per_char: None, epsg_code: 4326, order: AxisOrder::XFirst }
+    }
+
+    pub fn get_axis_order(&self) -> AxisOrder {
+        self.order
+    }
+
+    pub fn quadtile(bounds: BoundingBox, epsg_code: u32)
+            -> Result<CustomGeocode, GeocodeError> {
+        // quadkey digits over projected bounds, such as polar or national
+        // grids poorly served by the web mercator extent
+        CustomGeocode::builder().bounds(bounds).alphabet(QUADTILE_CHARS)
+            .epsg_code(epsg_code).build()
+    }
+
+    fn get_swapped_bounds(&self) -> BoundingBox {
+        swap(&self.bounds)
+    }
+}
+
+impl GridSystem for CustomGeocode {
+    fn get_alphabet(&self, _: usize) -> &[char] {
+        &self.alphabet
+    }
+
+    fn get_bits_per_char(&self) -> Option<usize> {
+        Some(self.char_bits)
+    }
+
+    fn get_bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn get_epsg_code(&self) -> u32 {
+        self.epsg_code
+    }
+
+    fn decode(&self, value: &str) -> Result<BoundingBox, GeocodeError> {
+        // grids splitting y first bisect with the axes swapped
+        match self.order {
+            AxisOrder::XFirst => decode_bits(&self.bounds, self.char_bits,
+                |_| &self.alphabet, value),
+            AxisOrder::YFirst => decode_bits(&self.get_swapped_bounds(),
+                self.char_bits, |_| &self.alphabet, value)
+                .map(|x| swap(&x)),
+        }
+    }
+
+    fn encode(&self, x: f64, y: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        // check if coordinates and precision are valid
+        if !self.bounds.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds: self.bounds });
+        } else if precision == 0 {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        let mut out = String::with_capacity(precision);
+        match self.order {
+            AxisOrder::XFirst => encode_bits(&self.bounds, x, y, precision,
+                self.char_bits, |_| &self.alphabet, &mut out)?,
+            AxisOrder::YFirst => encode_bits(&self.get_swapped_bounds(),
+                y, x, precision, self.char_bits, |_| &self.alphabet,
+                &mut out)?,
+        };
+
+        Ok(out)
+    }
+
+    fn get_intervals(&self, precision: usize) -> (f64, f64) {
+        // the leading axis holds the extra bit when the total number of
+        // bits is odd
+        let bits = precision * self.char_bits;
+        let (leading, trailing) = (bits - bits / 2, bits / 2);
+        let (x_bits, y_bits) = match self.order {
+            AxisOrder::XFirst => (leading, trailing),
+            AxisOrder::YFirst => (trailing, leading),
+        };
+
+        (self.bounds.width() / math::powi(2f64, x_bits as i32),
+            self.bounds.height() / math::powi(2f64, y_bits as i32))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomGeocodeBuilder {
+    bounds: Option<BoundingBox>,
+    alphabet: Vec<char>,
+    bits_per_char: Option<usize>,
+    epsg_code: u32,
+    order: AxisOrder,
+}
+
+impl CustomGeocodeBuilder {
+    pub fn alphabet(mut self, alphabet: &[char]) -> CustomGeocodeBuilder {
+        self.alphabet = alphabet.to_vec();
+        self
+    }
+
+    pub fn axis_order(mut self, order: AxisOrder) -> CustomGeocodeBuilder {
+        self.order = order;
+        self
+    }
+
+    pub fn bits_per_char(mut self, bits: usize) -> CustomGeocodeBuilder {
+        self.bits_per_char = Some(bits);
+        self
+    }
+
+    pub fn bounds(mut self, bounds: BoundingBox) -> CustomGeocodeBuilder {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn build(self) -> Result<CustomGeocode, GeocodeError> {
+        // check if bounds are valid
+        let bounds = match self.bounds {
+            Some(bounds) if bounds.min_x < bounds.max_x
+                && bounds.min_y < bounds.max_y => bounds,
+            Some(bounds) =>
+                return Err(GeocodeError::InvalidBoundingBox { bounds }),
+            None => return Err(GeocodeError::InvalidFormat {
+                reason: "bounds are required".to_string() }),
+        };
+
+        // check if the alphabet holds exactly one character per value,
+        // inferring the bits per character from its length when unset
+        let len = self.alphabet.len();
+        let char_bits = self.bits_per_char
+            .unwrap_or(len.trailing_zeros() as usize);
+        if char_bits == 0 || char_bits > 8 || len != 1 << char_bits {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "alphabet of {} characters does not match {} bits",
+                len, char_bits) });
+        }
+
+        for (i, c) in self.alphabet.iter().enumerate() {
+            if self.alphabet[..i].contains(c) {
+                return Err(GeocodeError::InvalidFormat { reason: format!(
+                    "alphabet character '{}' is repeated", c) });
+            }
+        }
+
+        Ok(CustomGeocode { bounds, alphabet: self.alphabet, char_bits,
+            epsg_code: self.epsg_code, order: self.order })
+    }
+
+    pub fn epsg_code(mut self, epsg_code: u32) -> CustomGeocodeBuilder {
+        self.epsg_code = epsg_code;
+        self
+    }
+}
+
+fn swap(bbox: &BoundingBox) -> BoundingBox {
+    BoundingBox::new(bbox.min_y, bbox.max_y, bbox.min_x, bbox.max_x)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError, GridSystem,
+        GEOHASH32_CHARS, QUADTILE_BOUNDS};
+    use super::{AxisOrder, CustomGeocode};
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn builder() {
+        // geohash parameters reproduce geohash codes
+        let geocode = CustomGeocode::builder()
+            .bounds(BoundingBox::new(-180.0, 180.0, -90.0, 90.0))
+            .alphabet(GEOHASH32_CHARS).build().unwrap();
+        assert_eq!(geocode.get_bits_per_char(), Some(5));
+        assert_eq!(geocode.get_epsg_code(), 4326);
+        assert_eq!(geocode.encode(-88.4, 44.266667, 8).unwrap(),
+            Geocode::Geohash.encode(-88.4, 44.266667, 8).unwrap());
+        assert_eq!(geocode.decode("dpc5u6"),
+            Geocode::Geohash.decode("dpc5u6"));
+        assert_eq!(geocode.get_intervals(7),
+            Geocode::Geohash.get_intervals(7));
+
+        assert!(CustomGeocode::builder().alphabet(GEOHASH32_CHARS)
+            .build().is_err());
+        assert!(CustomGeocode::builder().bounds(BoundingBox::new(1.0, 0.0,
+            0.0, 1.0)).alphabet(&['0', '1']).build().is_err());
+        assert_eq!(CustomGeocode::builder()
+            .bounds(BoundingBox::new(0.0, 1.0, 0.0, 1.0))
+            .alphabet(&['0', '1', '2']).build().err(),
+            Some(GeocodeError::InvalidFormat { reason: "alphabet of 3 \
+                characters does not match 0 bits".to_string() }));
+        assert!(CustomGeocode::builder()
+            .bounds(BoundingBox::new(0.0, 1.0, 0.0, 1.0))
+            .alphabet(&['0', '1', '2', '3']).bits_per_char(3).build()
+            .is_err());
+        assert!(CustomGeocode::builder()
+            .bounds(BoundingBox::new(0.0, 1.0, 0.0, 1.0))
+            .alphabet(&['0', '1', '1', '3']).build().is_err());
+    }
+
+    #[test]
+    fn axis_order() {
+        let builder = CustomGeocode::builder()
+            .bounds(BoundingBox::new(0.0, 1000.0, 0.0, 500.0))
+            .alphabet(&['a', 'b']).epsg_code(32616);
+        let x_first = builder.clone().build().unwrap();
+        let y_first = builder.axis_order(AxisOrder::YFirst).build().unwrap();
+        assert_eq!(y_first.get_axis_order(), AxisOrder::YFirst);
+        assert_eq!(y_first.get_epsg_code(), 32616);
+
+        // the first character splits the leading axis
+        assert_eq!(x_first.encode(750.0, 100.0, 1).unwrap(), "b");
+        assert_eq!(y_first.encode(750.0, 100.0, 1).unwrap(), "a");
+        assert_eq!(y_first.encode(750.0, 100.0, 3).unwrap(), "aba");
+        assert_eq!(y_first.decode("a").unwrap(),
+            BoundingBox::new(0.0, 1000.0, 0.0, 250.0));
+        assert_eq!(y_first.decode("abb").unwrap(),
+            BoundingBox::new(500.0, 1000.0, 125.0, 250.0));
+
+        assert_eq!(x_first.get_intervals(3), (250.0, 250.0));
+        assert_eq!(y_first.get_intervals(3), (500.0, 125.0));
+        assert!(y_first.encode(1001.0, 0.0, 2).is_err());
+        assert!(y_first.decode("abc").is_err());
+    }
+
+    #[test]
+    fn quadtile() {
+        // the web mercator extent reproduces quadtile codes
+        let geocode = CustomGeocode::quadtile(QUADTILE_BOUNDS, 3857).unwrap();
+        assert_eq!(geocode.encode(-9840642.99, 5506802.68, 12).unwrap(),
+            Geocode::QuadTile.encode(-9840642.99, 5506802.68, 12).unwrap());
+        assert_eq!(geocode.decode("0302"), Geocode::QuadTile.decode("0302"));
+
+        // polar stereographic tiles split the arctic extent
+        let bounds = BoundingBox::new(-4194304.0, 4194304.0,
+            -4194304.0, 4194304.0);
+        let geocode = CustomGeocode::quadtile(bounds, 3413).unwrap();
+        assert_eq!(geocode.get_epsg_code(), 3413);
+        assert_eq!(geocode.encode(-1000.0, 1000.0, 1).unwrap(), "0");
+        assert_eq!(geocode.encode(1000.0, -1000.0, 1).unwrap(), "3");
+        assert_eq!(geocode.decode("1").unwrap(),
+            BoundingBox::new(0.0, 4194304.0, 0.0, 4194304.0));
+        assert_eq!(geocode.get_intervals(10), (8192.0, 8192.0));
+
+        assert!(CustomGeocode::quadtile(BoundingBox::new(0.0, 0.0, 0.0, 1.0),
+            3413).is_err());
+    }
+}
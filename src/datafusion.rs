@@ -0,0 +1,260 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use datafusion_common::arrow::array::{Array, ArrayRef, Float64Array,
+    StringArray, StructArray};
+use datafusion_common::arrow::datatypes::{DataType, Field, Fields};
+use datafusion_common::cast::{as_float64_array, as_int64_array,
+    as_string_array};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::{ColumnarValue, ScalarFunctionArgs, ScalarUDF,
+    ScalarUDFImpl, Signature, Volatility};
+
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Function {
+    DecodeBounds,
+    DecodeCenter,
+    Encode,
+    Parent,
+}
+
+// scalar functions taking the geocode name as their first argument, where
+// rows with a null argument produce a null result
+#[derive(Debug, Eq, Hash, PartialEq)]
+struct GeocodeUdf {
+    function: Function,
+    name: &'static str,
+    signature: Signature,
+}
+
+impl GeocodeUdf {
+    fn new(function: Function) -> GeocodeUdf {
+        let (name, arg_types) = match function {
+            Function::DecodeBounds => ("geocode_decode_bounds",
+                vec![DataType::Utf8, DataType::Utf8]),
+            Function::DecodeCenter => ("geocode_decode_center",
+                vec![DataType::Utf8, DataType::Utf8]),
+            Function::Encode => ("geocode_encode", vec![DataType::Utf8,
+                DataType::Float64, DataType::Float64, DataType::Int64]),
+            Function::Parent => ("geocode_parent",
+                vec![DataType::Utf8, DataType::Utf8]),
+        };
+
+        GeocodeUdf { function, name,
+            signature: Signature::exact(arg_types, Volatility::Immutable) }
+    }
+}
+
+impl ScalarUDFImpl for GeocodeUdf {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _: &[DataType]) -> Result<DataType> {
+        Ok(match self.function {
+            Function::DecodeBounds => DataType::Struct(get_fields(
+                &["min_x", "max_x", "min_y", "max_y"])),
+            Function::DecodeCenter => DataType::Struct(get_fields(
+                &["x", "y"])),
+            Function::Encode | Function::Parent => DataType::Utf8,
+        })
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs)
+            -> Result<ColumnarValue> {
+        let arrays = args.args.iter()
+            .map(|x| x.to_array(args.number_rows))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        let geocodes = as_string_array(&arrays[0])?;
+
+        let array: ArrayRef = match self.function {
+            Function::DecodeBounds => {
+                let codes = as_string_array(&arrays[1])?;
+                let bounds = map_codes(geocodes, codes, |geocode, code|
+                    geocode.decode(code))?;
+                let columns: Vec<Float64Array> = [
+                    |x: &BoundingBox| x.min_x, |x: &BoundingBox| x.max_x,
+                    |x: &BoundingBox| x.min_y, |x: &BoundingBox| x.max_y,
+                ].iter().map(|f| bounds.iter().map(|x| x.as_ref().map(f))
+                    .collect()).collect();
+                to_struct(&["min_x", "max_x", "min_y", "max_y"], columns,
+                    &bounds)
+            },
+            Function::DecodeCenter => {
+                let codes = as_string_array(&arrays[1])?;
+                let centers = map_codes(geocodes, codes, |geocode, code|
+                    geocode.decode_center(code).map(|x| (x.0, x.1)))?;
+                let columns = vec![
+                    centers.iter().map(|x| x.map(|x| x.0)).collect(),
+                    centers.iter().map(|x| x.map(|x| x.1)).collect(),
+                ];
+                to_struct(&["x", "y"], columns, &centers)
+            },
+            Function::Encode => {
+                let xs = as_float64_array(&arrays[1])?;
+                let ys = as_float64_array(&arrays[2])?;
+                let precisions = as_int64_array(&arrays[3])?;
+
+                let mut codes = Vec::with_capacity(args.number_rows);
+                for i in 0..args.number_rows {
+                    if geocodes.is_null(i) || xs.is_null(i) || ys.is_null(i)
+                            || precisions.is_null(i) {
+                        codes.push(None);
+                        continue;
+                    }
+
+                    let geocode: Geocode = geocodes.value(i).parse()
+                        .map_err(to_datafusion_error)?;
+                    let precision = precisions.value(i).max(0) as usize;
+                    codes.push(Some(geocode.encode(xs.value(i), ys.value(i),
+                        precision).map_err(to_datafusion_error)?));
+                }
+
+                Arc::new(StringArray::from(codes))
+            },
+            Function::Parent => {
+                let codes = as_string_array(&arrays[1])?;
+                Arc::new(StringArray::from(map_codes(geocodes, codes,
+                    |geocode, code| geocode.parent(code))?))
+            },
+        };
+
+        Ok(ColumnarValue::Array(array))
+    }
+}
+
+pub fn udfs() -> Vec<ScalarUDF> {
+    // geocode_decode_bounds(geocode, code), geocode_decode_center(geocode,
+    // code), geocode_encode(geocode, x, y, precision), and
+    // geocode_parent(geocode, code)
+    [Function::DecodeBounds, Function::DecodeCenter, Function::Encode,
+        Function::Parent].iter()
+        .map(|x| ScalarUDF::new_from_impl(GeocodeUdf::new(*x))).collect()
+}
+
+fn get_fields(names: &[&str]) -> Fields {
+    names.iter().map(|x| Field::new(*x, DataType::Float64, false))
+        .collect()
+}
+
+fn map_codes<T, F>(geocodes: &StringArray, codes: &StringArray, f: F)
+        -> Result<Vec<Option<T>>>
+        where F: Fn(Geocode, &str) -> Result<T, GeocodeError> {
+    let mut values = Vec::with_capacity(codes.len());
+    for i in 0..codes.len() {
+        if geocodes.is_null(i) || codes.is_null(i) {
+            values.push(None);
+            continue;
+        }
+
+        let geocode: Geocode = geocodes.value(i).parse()
+            .map_err(to_datafusion_error)?;
+        values.push(Some(f(geocode, codes.value(i))
+            .map_err(to_datafusion_error)?));
+    }
+
+    Ok(values)
+}
+
+fn to_datafusion_error(error: GeocodeError) -> DataFusionError {
+    DataFusionError::Execution(error.to_string())
+}
+
+fn to_struct<T>(names: &[&str], columns: Vec<Float64Array>,
+        values: &[Option<T>]) -> ArrayRef {
+    // struct fields are only null where the struct itself is null
+    let nulls = values.iter().map(|x| x.is_some()).collect();
+    let columns = columns.into_iter().map(|x| Arc::new(x) as ArrayRef)
+        .collect();
+    Arc::new(StructArray::new(get_fields(names), columns, Some(nulls)))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_common::arrow::array::{Array, ArrayRef, Float64Array,
+        Int64Array, StringArray, StructArray};
+    use datafusion_common::arrow::datatypes::{DataType, Field};
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::{ColumnarValue, ScalarFunctionArgs, ScalarUDF};
+
+    use std::sync::Arc;
+
+    fn invoke(name: &str, args: Vec<ColumnarValue>, number_rows: usize)
+            -> datafusion_common::Result<ArrayRef> {
+        let udf: ScalarUDF = super::udfs().into_iter()
+            .find(|x| x.name() == name).unwrap();
+        let arg_fields = args.iter().map(|x|
+            Arc::new(Field::new("", x.data_type(), true))).collect();
+        let return_type = udf.return_type(&[]).unwrap();
+        let args = ScalarFunctionArgs { args, arg_fields, number_rows,
+            return_field: Arc::new(Field::new("", return_type, true)),
+            config_options: Arc::new(ConfigOptions::default()) };
+        udf.invoke_with_args(args)?.into_array(number_rows)
+    }
+
+    fn geohash() -> ColumnarValue {
+        ColumnarValue::Scalar(ScalarValue::Utf8(Some("geohash".to_string())))
+    }
+
+    fn codes(values: Vec<Option<&str>>) -> ColumnarValue {
+        ColumnarValue::Array(Arc::new(StringArray::from(values)))
+    }
+
+    #[test]
+    fn decode() {
+        let array = invoke("geocode_decode_center", vec![geohash(),
+            codes(vec![Some("dp"), None])], 2).unwrap();
+        let centers = array.as_any().downcast_ref::<StructArray>().unwrap();
+        assert!(centers.is_valid(0) && centers.is_null(1));
+        assert_eq!(centers.column_by_name("x").unwrap().as_any()
+            .downcast_ref::<Float64Array>().unwrap().value(0), -84.375);
+        assert_eq!(centers.data_type(), &DataType::Struct(
+            super::get_fields(&["x", "y"])));
+
+        let array = invoke("geocode_decode_bounds", vec![geohash(),
+            codes(vec![Some("dp")])], 1).unwrap();
+        let bounds = array.as_any().downcast_ref::<StructArray>().unwrap();
+        let max_y = bounds.column_by_name("max_y").unwrap();
+        assert_eq!(max_y.as_any().downcast_ref::<Float64Array>().unwrap()
+            .value(0), 45.0);
+
+        assert!(invoke("geocode_decode_center", vec![geohash(),
+            codes(vec![Some("dpa")])], 1).is_err());
+    }
+
+    #[test]
+    fn encode() {
+        let xs = Float64Array::from(vec![Some(-88.4), Some(-122.4), None]);
+        let ys = Float64Array::from(vec![44.266667, 37.8, 0.0]);
+        let array = invoke("geocode_encode", vec![geohash(),
+            ColumnarValue::Array(Arc::new(xs)),
+            ColumnarValue::Array(Arc::new(ys)),
+            ColumnarValue::Scalar(ScalarValue::Int64(Some(4)))], 3).unwrap();
+        assert_eq!(array.as_any().downcast_ref::<StringArray>().unwrap(),
+            &StringArray::from(vec![Some("dpc5"), Some("9q8z"), None]));
+
+        // geocode names are resolved per row
+        let geocodes = StringArray::from(vec!["quadtile", "unknown"]);
+        let args = vec![ColumnarValue::Array(Arc::new(geocodes)),
+            ColumnarValue::Scalar(ScalarValue::Float64(Some(0.0))),
+            ColumnarValue::Scalar(ScalarValue::Float64(Some(0.0))),
+            ColumnarValue::Array(Arc::new(Int64Array::from(vec![2, 2])))];
+        assert!(invoke("geocode_encode", args, 2).is_err());
+    }
+
+    #[test]
+    fn parent() {
+        let array = invoke("geocode_parent", vec![geohash(),
+            codes(vec![Some("dpc5"), None, Some("9q8z")])], 3).unwrap();
+        assert_eq!(array.as_any().downcast_ref::<StringArray>().unwrap(),
+            &StringArray::from(vec![Some("dpc"), None, Some("9q8")]));
+        assert!(invoke("geocode_parent", vec![geohash(),
+            codes(vec![Some("d")])], 1).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+use crate::polygon;
+use crate::project::{WGS84_FLATTENING, WGS84_SEMI_MAJOR_AXIS};
+
+pub const EARTH_MEAN_RADIUS: f64 = 6371008.8;
+
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DistanceMethod {
+    Haversine,
+    Vincenty,
+}
+
+impl Geocode {
+    pub fn bearing(&self, a: &str, b: &str) -> Result<f64, GeocodeError> {
+        // compute initial bearing between wgs84 cell centers
+        let (lon_a, lat_a) = self.decode_lonlat(a)?.center();
+        let (lon_b, lat_b) = self.decode_lonlat(b)?.center();
+        Ok(bearing(lon_a, lat_a, lon_b, lat_b))
+    }
+
+    pub fn destination(&self, x: f64, y: f64, bearing: f64, distance: f64,
+            precision: usize) -> Result<String, GeocodeError> {
+        // check if coordinates are valid
+        let bounds = self.get_bounds();
+        if !bounds.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds });
+        }
+
+        let (lon, lat) = self.native_to_wgs84(x, y);
+        let (lon, lat) = destination(lon, lat, bearing, distance);
+        self.encode_lonlat(lon, lat, precision)
+    }
+
+    pub fn distance(&self, a: &str, b: &str, method: DistanceMethod)
+            -> Result<f64, GeocodeError> {
+        // compute distance between wgs84 cell centers
+        let (lon_a, lat_a) = self.decode_lonlat(a)?.center();
+        let (lon_b, lat_b) = self.decode_lonlat(b)?.center();
+
+        Ok(match method {
+            DistanceMethod::Haversine => haversine(lon_a, lat_a, lon_b, lat_b),
+            // vincenty may fail to converge for nearly antipodal points
+            DistanceMethod::Vincenty => vincenty(lon_a, lat_a, lon_b, lat_b)
+                .unwrap_or_else(|| haversine(lon_a, lat_a, lon_b, lat_b)),
+        })
+    }
+}
+
+pub fn bearing(lon_a: f64, lat_a: f64, lon_b: f64, lat_b: f64) -> f64 {
+    let (lat_a, lat_b) = (lat_a.to_radians(), lat_b.to_radians());
+    let lon_delta = (lon_b - lon_a).to_radians();
+
+    let y = lon_delta.sin() * lat_b.cos();
+    let x = lat_a.cos() * lat_b.sin()
+        - lat_a.sin() * lat_b.cos() * lon_delta.cos();
+    y.atan2(x).to_degrees().rem_euclid(360f64)
+}
+
+pub fn destination(lon: f64, lat: f64, bearing: f64, distance: f64)
+        -> (f64, f64) {
+    // follow the great circle from the origin along the bearing
+    let angle = distance / EARTH_MEAN_RADIUS;
+    let (lat, bearing) = (lat.to_radians(), bearing.to_radians());
+
+    let lat_b = (lat.sin() * angle.cos()
+        + lat.cos() * angle.sin() * bearing.cos()).asin();
+    let lon_delta = (bearing.sin() * angle.sin() * lat.cos())
+        .atan2(angle.cos() - lat.sin() * lat_b.sin());
+
+    // normalize longitude
+    let lon_b = (lon + lon_delta.to_degrees() + 180f64).rem_euclid(360f64)
+        - 180f64;
+    (lon_b, lat_b.to_degrees())
+}
+
+pub fn haversine(lon_a: f64, lat_a: f64,
+        lon_b: f64, lat_b: f64) -> f64 {
+    let (lat_a, lat_b) = (lat_a.to_radians(), lat_b.to_radians());
+    let lat_delta = lat_b - lat_a;
+    let lon_delta = (lon_b - lon_a).to_radians();
+
+    let a = (lat_delta / 2f64).sin().powi(2) + lat_a.cos() * lat_b.cos()
+        * (lon_delta / 2f64).sin().powi(2);
+    2f64 * EARTH_MEAN_RADIUS * a.sqrt().min(1f64).asin()
+}
+
+fn point_bbox_distance(point: (f64, f64), bbox: &BoundingBox) -> f64 {
+    let dx = (bbox.min_x - point.0).max(point.0 - bbox.max_x).max(0f64);
+    let dy = (bbox.min_y - point.1).max(point.1 - bbox.max_y).max(0f64);
+    dx.hypot(dy)
+}
+
+fn point_segment_distance(point: (f64, f64),
+        start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0f64 {
+        0f64
+    } else {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy)
+            / length).clamp(0f64, 1f64)
+    };
+
+    (point.0 - start.0 - t * dx).hypot(point.1 - start.1 - t * dy)
+}
+
+pub(crate) fn segment_bbox_distance(start: (f64, f64),
+        end: (f64, f64), bbox: &BoundingBox) -> f64 {
+    if bbox.contains(start.0, start.1)
+            || polygon::segment_crosses(start, end, bbox) {
+        return 0f64;
+    }
+
+    // otherwise the minimum distance is between an endpoint and the
+    // bounding box or a bounding box corner and the segment
+    let corners = [(bbox.min_x, bbox.min_y), (bbox.min_x, bbox.max_y),
+        (bbox.max_x, bbox.min_y), (bbox.max_x, bbox.max_y)];
+    corners.iter()
+        .map(|corner| point_segment_distance(*corner, start, end))
+        .fold(point_bbox_distance(start, bbox)
+            .min(point_bbox_distance(end, bbox)), f64::min)
+}
+
+pub(crate) fn to_local(lon: f64, lat: f64, origin_lat: f64) -> (f64, f64) {
+    // equirectangular projection to meters around the origin latitude
+    let x = lon.to_radians() * origin_lat.to_radians().cos();
+    (x * EARTH_MEAN_RADIUS, lat.to_radians() * EARTH_MEAN_RADIUS)
+}
+
+pub fn vincenty(lon_a: f64, lat_a: f64,
+        lon_b: f64, lat_b: f64) -> Option<f64> {
+    let (a, f) = (WGS84_SEMI_MAJOR_AXIS, WGS84_FLATTENING);
+    let b = a * (1f64 - f);
+
+    // compute reduced latitudes
+    let l = (lon_b - lon_a).to_radians();
+    let u_a = ((1f64 - f) * lat_a.to_radians().tan()).atan();
+    let u_b = ((1f64 - f) * lat_b.to_radians().tan()).atan();
+    let (sin_u_a, cos_u_a) = u_a.sin_cos();
+    let (sin_u_b, cos_u_b) = u_b.sin_cos();
+
+    // iterate until the auxiliary sphere longitude converges
+    let mut lambda = l;
+    for _ in 0..VINCENTY_MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u_b * sin_lambda).powi(2) + (cos_u_a * sin_u_b
+            - sin_u_a * cos_u_b * cos_lambda).powi(2)).sqrt();
+        if sin_sigma == 0f64 {
+            return Some(0f64);
+        }
+
+        let cos_sigma = sin_u_a * sin_u_b + cos_u_a * cos_u_b * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u_a * cos_u_b * sin_lambda / sin_sigma;
+        let cos2_alpha = 1f64 - sin_alpha * sin_alpha;
+        let cos_2sigma_m = if cos2_alpha == 0f64 {
+            // equatorial lines
+            0f64
+        } else {
+            cos_sigma - 2f64 * sin_u_a * sin_u_b / cos2_alpha
+        };
+
+        let c = f / 16f64 * cos2_alpha * (4f64 + f * (4f64
+            - 3f64 * cos2_alpha));
+        let previous = lambda;
+        lambda = l + (1f64 - c) * f * sin_alpha * (sigma + c * sin_sigma
+            * (cos_2sigma_m + c * cos_sigma * (-1f64
+                + 2f64 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u2 = cos2_alpha * (a * a - b * b) / (b * b);
+            let big_a = 1f64 + u2 / 16384f64 * (4096f64 + u2 * (-768f64
+                + u2 * (320f64 - 175f64 * u2)));
+            let big_b = u2 / 1024f64 * (256f64 + u2 * (-128f64
+                + u2 * (74f64 - 47f64 * u2)));
+            let delta_sigma = big_b * sin_sigma * (cos_2sigma_m + big_b
+                / 4f64 * (cos_sigma * (-1f64 + 2f64 * cos_2sigma_m.powi(2))
+                - big_b / 6f64 * cos_2sigma_m * (-3f64 + 4f64
+                    * sin_sigma.powi(2)) * (-3f64 + 4f64
+                    * cos_2sigma_m.powi(2))));
+
+            return Some(b * big_a * (sigma - delta_sigma));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::DistanceMethod;
+
+    #[test]
+    fn bearing() {
+        assert_eq!(super::bearing(0.0, 0.0, 0.0, 1.0), 0.0);
+        assert!((super::bearing(0.0, 0.0, 1.0, 0.0) - 90.0).abs() < 1e-9);
+        assert!((super::bearing(0.0, 0.0, 0.0, -1.0) - 180.0).abs() < 1e-9);
+        assert!((super::bearing(0.0, 0.0, -1.0, 0.0) - 270.0).abs() < 1e-9);
+
+        // appleton to fort collins heads west south west
+        let geocode = Geocode::Geohash;
+        let a = geocode.encode(-88.4, 44.266667, 12).unwrap();
+        let b = geocode.encode(-105.078056, 40.559167, 12).unwrap();
+        let bearing = geocode.bearing(&a, &b).unwrap();
+        assert!((bearing - 259.007).abs() < 0.001);
+    }
+
+    #[test]
+    fn destination() {
+        let quarter = super::EARTH_MEAN_RADIUS * std::f64::consts::PI / 2.0;
+        let (lon, lat) = super::destination(0.0, 0.0, 90.0, quarter);
+        assert!((lon - 90.0).abs() < 1e-9 && lat.abs() < 1e-9);
+        let (_, lat) = super::destination(0.0, 0.0, 0.0, quarter);
+        assert!((lat - 90.0).abs() < 1e-9);
+
+        // destinations wrap across the antimeridian
+        let (lon, _) = super::destination(179.5, 0.0, 90.0, 111195.0);
+        assert!((lon - -179.5).abs() < 1e-3);
+
+        // check destination and bearing agree
+        let geocode = Geocode::QuadTile;
+        let code = geocode.destination(-9840642.99, 5506802.68, 45.0,
+            10000.0, 16).unwrap();
+        let origin = geocode.encode(-9840642.99, 5506802.68, 16).unwrap();
+        assert!((geocode.bearing(&origin, &code).unwrap() - 45.0).abs() < 1.0);
+        assert!((geocode.distance(&origin, &code,
+            DistanceMethod::Haversine).unwrap() - 10000.0).abs() < 1000.0);
+
+        assert!(geocode.destination(0.0, 3e7, 0.0, 0.0, 1).is_err());
+    }
+
+    #[test]
+    fn distance() {
+        let geocode = Geocode::Geohash;
+        let a = geocode.encode(-88.4, 44.266667, 12).unwrap();
+        let b = geocode.encode(-105.078056, 40.559167, 12).unwrap();
+        let distance = geocode.distance(&a, &b, DistanceMethod::Haversine)
+            .unwrap();
+        assert!((distance - 1426997.006).abs() < 1.0);
+
+        // ellipsoidal distance differs slightly from the spherical model
+        let distance = geocode.distance(&a, &b, DistanceMethod::Vincenty)
+            .unwrap();
+        assert!((distance - 1426997.006).abs() < 5000.0);
+        assert_eq!(geocode.distance(&a, &a, DistanceMethod::Vincenty), Ok(0.0));
+
+        // antipodal cells fall back to the spherical model
+        let a = geocode.encode(0.0, 0.0, 12).unwrap();
+        let b = geocode.encode(179.9999, 0.0, 12).unwrap();
+        assert!(geocode.distance(&a, &b, DistanceMethod::Vincenty).is_ok());
+
+        assert_eq!(geocode.distance("dpca", &b, DistanceMethod::Haversine),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 3 }));
+    }
+
+    #[test]
+    fn haversine() {
+        // appleton to fort collins
+        let distance = super::haversine(-88.4, 44.266667,
+            -105.078056, 40.559167);
+        assert!((distance - 1426997.006).abs() < 0.01);
+
+        assert_eq!(super::haversine(10.0, 10.0, 10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn vincenty() {
+        // flinders peak to buninyong
+        let distance = super::vincenty(144.42486788889, -37.95103341666,
+            143.92649552778, -37.65282113889).unwrap();
+        assert!((distance - 54972.271).abs() < 0.001);
+
+        assert_eq!(super::vincenty(10.0, 10.0, 10.0, 10.0), Some(0.0));
+        assert!(super::vincenty(0.0, 0.0, 179.7, 0.5).is_none());
+    }
+
+    #[test]
+    fn segment_bbox_distance() {
+        let bbox = BoundingBox::new(0.0, 2.0, 0.0, 2.0);
+        assert_eq!(super::segment_bbox_distance(
+            (-1.0, 1.0), (3.0, 1.0), &bbox), 0.0);
+        assert_eq!(super::segment_bbox_distance(
+            (1.0, 1.0), (1.0, 1.0), &bbox), 0.0);
+        assert_eq!(super::segment_bbox_distance(
+            (-1.0, 3.0), (3.0, 3.0), &bbox), 1.0);
+        assert_eq!(super::segment_bbox_distance(
+            (3.0, 5.0), (6.0, 2.0), &bbox), 2f64.sqrt() * 2.0);
+        assert_eq!(super::segment_bbox_distance(
+            (5.0, 6.0), (5.0, 6.0), &bbox), 5.0);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{morton, GeocodeError, Tile, GEOHASH32_CHARS, GEOHASH_BOUNDS};
+
+use std::f64::consts::PI;
+
+const MAX_GEOHASH_PRECISION: usize = 12;
+const MAX_GEOTILE_PRECISION: usize = 29;
+
+// lucene stores coordinates as 32 bit integers, so aggregations compute
+// keys from the quantized rather than the indexed coordinates
+const LAT_DECODE: f64 = 1f64 / (4294967296f64 / 180f64);
+const LON_DECODE: f64 = 1f64 / (4294967296f64 / 360f64);
+
+pub fn geohash_key(lon: f64, lat: f64, precision: usize)
+        -> Result<String, GeocodeError> {
+    // check if precision is valid
+    if precision == 0 || precision > MAX_GEOHASH_PRECISION {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    // flipping the sign bits orders the encoded integers, after which
+    // codes hold the leading bits with longitude first
+    let (x, y) = quantize(lon, lat)?;
+    let bits = morton::interleave(x as u32 ^ (1 << 31),
+        y as u32 ^ (1 << 31));
+
+    let mut key = String::with_capacity(precision);
+    for i in 0..precision {
+        let index = (bits >> (64 - (i + 1) * 5)) & 0x1f;
+        key.push(GEOHASH32_CHARS[index as usize]);
+    }
+
+    Ok(key)
+}
+
+pub fn geotile(lon: f64, lat: f64, precision: usize)
+        -> Result<Tile, GeocodeError> {
+    // check if precision is valid
+    if precision > MAX_GEOTILE_PRECISION {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    let (x, y) = quantize(lon, lat)?;
+    let (lon, lat) = (x as f64 * LON_DECODE, y as f64 * LAT_DECODE);
+
+    // tiles beyond the web mercator extent are clamped to the edges
+    let tiles = (1u64 << precision) as f64;
+    let lat_sin = lat.to_radians().sin();
+    let x = ((lon + 180f64) / 360f64 * tiles).floor();
+    let y = ((0.5 - ((1f64 + lat_sin) / (1f64 - lat_sin)).ln()
+        / (4f64 * PI)) * tiles).floor();
+    let max = tiles - 1f64;
+    Tile::new(precision, x.clamp(0f64, max) as u32,
+        y.clamp(0f64, max) as u32)
+}
+
+pub fn geotile_key(lon: f64, lat: f64, precision: usize)
+        -> Result<String, GeocodeError> {
+    let tile = geotile(lon, lat, precision)?;
+    Ok(format!("{}/{}/{}", tile.z, tile.x, tile.y))
+}
+
+pub fn parse_geotile_key(value: &str) -> Result<Tile, GeocodeError> {
+    // keys are formatted as 'zoom/x/y'
+    let invalid = || GeocodeError::InvalidFormat {
+        reason: format!("invalid geotile key '{}'", value) };
+    let parsed = value.split('/').map(|x| x.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>().map_err(|_| invalid())?;
+    if parsed.len() != 3 {
+        return Err(invalid());
+    }
+
+    let precision = parsed[0] as usize;
+    if precision > MAX_GEOTILE_PRECISION {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    Tile::new(precision, parsed[1], parsed[2])
+}
+
+fn quantize(lon: f64, lat: f64) -> Result<(i32, i32), GeocodeError> {
+    // check if coordinates are valid
+    if !GEOHASH_BOUNDS.contains(lon, lat) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x: lon, y: lat, bounds: GEOHASH_BOUNDS });
+    }
+
+    // the maximum bounds are stepped down to remain within range
+    let lon = if lon == 180f64 { f64::from_bits(lon.to_bits() - 1) }
+        else { lon };
+    let lat = if lat == 90f64 { f64::from_bits(lat.to_bits() - 1) }
+        else { lat };
+    Ok(((lon / LON_DECODE).floor() as i32,
+        (lat / LAT_DECODE).floor() as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn geohash_key() {
+        // keys of the museum points within the elasticsearch documentation
+        assert_eq!(super::geohash_key(4.912350, 52.374081, 3).unwrap(),
+            "u17");
+        assert_eq!(super::geohash_key(2.351, 48.861, 3).unwrap(), "u09");
+
+        assert_eq!(super::geohash_key(-88.4, 44.266667, 12),
+            Geocode::Geohash.encode(-88.4, 44.266667, 12));
+        assert_eq!(super::geohash_key(180.0, 90.0, 2).unwrap(), "zz");
+        assert_eq!(super::geohash_key(-180.0, -90.0, 2).unwrap(), "00");
+
+        assert_eq!(super::geohash_key(0.0, 0.0, 13),
+            Err(GeocodeError::InvalidPrecision { precision: 13 }));
+        assert!(super::geohash_key(0.0, 91.0, 4).is_err());
+    }
+
+    #[test]
+    fn geotile_key() {
+        assert_eq!(super::geotile_key(4.912350, 52.374081, 8).unwrap(),
+            "8/131/84");
+        assert_eq!(super::geotile_key(2.351, 48.861, 8).unwrap(),
+            "8/129/88");
+        assert_eq!(super::geotile_key(0.0, 0.0, 0).unwrap(), "0/0/0");
+
+        // tiles beyond the mercator extent are clamped
+        assert_eq!(super::geotile_key(180.0, 90.0, 2).unwrap(), "2/3/0");
+        assert_eq!(super::geotile_key(-180.0, -90.0, 2).unwrap(), "2/0/3");
+        assert!(super::geotile_key(0.0, 0.0, 30).is_err());
+    }
+
+    #[test]
+    fn parse_geotile_key() {
+        let tile = super::parse_geotile_key("8/131/84").unwrap();
+        assert_eq!((tile.z, tile.x, tile.y), (8, 131, 84));
+        assert!(tile.get_wgs84_bounds().contains(4.912350, 52.374081));
+
+        assert!(super::parse_geotile_key("8/131").is_err());
+        assert!(super::parse_geotile_key("8/a/84").is_err());
+        assert!(super::parse_geotile_key("2/4/0").is_err());
+        assert_eq!(super::parse_geotile_key("30/0/0").err(),
+            Some(GeocodeError::InvalidPrecision { precision: 30 }));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{BoundingBox, BoundingVolume, Direction, Geocode};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::error::Error;
+use core::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeocodeError {
+    CoordinateOutOfRange { x: f64, y: f64, bounds: BoundingBox },
+    CoordinateOutOfVolume { x: f64, y: f64, z: f64, bounds: BoundingVolume },
+    EmptyCode,
+    GeocodeMismatch { expected: Geocode, found: Geocode },
+    InvalidBoundingBox { bounds: BoundingBox },
+    InvalidCellId { id: u64 },
+    InvalidCharacter { ch: char, index: usize },
+    #[cfg(feature = "alloc")]
+    InvalidFormat { reason: String },
+    #[cfg(feature = "alloc")]
+    InvalidGeometry { reason: String },
+    InvalidPrecision { precision: usize },
+    #[cfg(feature = "alloc")]
+    InvalidProjection { reason: String },
+    InvalidResolution { resolution: f64 },
+    PoleCrossing { direction: Direction },
+    #[cfg(feature = "alloc")]
+    UnknownGeocode { name: String },
+    UnsupportedGeocode { geocode: Geocode },
+    UnsupportedGrid,
+    WriteFailure,
+}
+
+impl Display for GeocodeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            GeocodeError::CoordinateOutOfRange { x, y, bounds } =>
+                write!(f, "coordinate ({}, {}) is outside of geocode range ({} - {}, {} - {})", x, y, bounds.min_x,
+                    bounds.max_x, bounds.min_y, bounds.max_y),
+            GeocodeError::CoordinateOutOfVolume { x, y, z, bounds } =>
+                write!(f, "coordinate ({}, {}, {}) is outside of volume \
+                    ({} - {}, {} - {}, {} - {})", x, y, z, bounds.min_x,
+                    bounds.max_x, bounds.min_y, bounds.max_y, bounds.min_z,
+                    bounds.max_z),
+            GeocodeError::EmptyCode =>
+                write!(f, "unable to process empty geocode"),
+            GeocodeError::GeocodeMismatch { expected, found } =>
+                write!(f, "expected {:?} geocode but found {:?}",
+                    expected, found),
+            GeocodeError::InvalidBoundingBox { bounds } =>
+                write!(f, "invalid bounding box ({} - {}, {} - {})",
+                    bounds.min_x, bounds.max_x, bounds.min_y, bounds.max_y),
+            GeocodeError::InvalidCellId { id } =>
+                write!(f, "invalid cell id {:#018x}", id),
+            GeocodeError::InvalidCharacter { ch, index } =>
+                write!(f, "invalid character '{}' at index {}", ch, index),
+            #[cfg(feature = "alloc")]
+            GeocodeError::InvalidFormat { reason } =>
+                write!(f, "invalid format: {}", reason),
+            #[cfg(feature = "alloc")]
+            GeocodeError::InvalidGeometry { reason } =>
+                write!(f, "invalid geometry: {}", reason),
+            GeocodeError::InvalidPrecision { precision } =>
+                write!(f, "invalid precision {}", precision),
+            #[cfg(feature = "alloc")]
+            GeocodeError::InvalidProjection { reason } =>
+                write!(f, "invalid projection: {}", reason),
+            GeocodeError::InvalidResolution { resolution } =>
+                write!(f, "invalid resolution {}", resolution),
+            GeocodeError::PoleCrossing { direction } =>
+                write!(f, "no {:?} neighbor beyond the pole", direction),
+            #[cfg(feature = "alloc")]
+            GeocodeError::UnknownGeocode { name } =>
+                write!(f, "unknown geocode '{}'", name),
+            GeocodeError::UnsupportedGeocode { geocode } =>
+                write!(f, "operation is unsupported for {:?} geocode",
+                    geocode),
+            GeocodeError::UnsupportedGrid =>
+                write!(f, "grid does not support bisection encoding"),
+            GeocodeError::WriteFailure =>
+                write!(f, "unable to write code"),
+        }
+    }
+}
+
+impl Error for GeocodeError {}
+
+impl From<fmt::Error> for GeocodeError {
+    fn from(_: fmt::Error) -> GeocodeError {
+        GeocodeError::WriteFailure
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn io_error(error: std::io::Error) -> GeocodeError {
+    GeocodeError::InvalidFormat { reason: error.to_string() }
+}
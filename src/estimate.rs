@@ -0,0 +1,134 @@
+use crate::{BoundingBox, CoverMode, Geocode, GeocodeError, Polygon};
+use crate::cover::cell_range;
+
+impl Geocode {
+    pub fn estimate_cover_bbox_size(&self, bbox: &BoundingBox,
+            precision: usize) -> Result<usize, GeocodeError> {
+        let (bbox, west) = self.split_bbox(bbox, precision)?;
+        let size = self.count_cells(&bbox, precision);
+        Ok(match west {
+            Some(west) => size.saturating_add(
+                self.count_cells(&west, precision)),
+            None => size,
+        })
+    }
+
+    pub fn estimate_cover_size(&self, polygon: &Polygon, precision: usize,
+            mode: CoverMode) -> Result<usize, GeocodeError> {
+        polygon.validate()?;
+        let limit = self.estimate_cover_bbox_size(
+            &polygon.bounding_box(), precision)?;
+
+        // interior cells follow from the area, while cells along the
+        // boundary are split evenly between inside and outside
+        let (x_interval, y_interval) = self.get_intervals(precision);
+        let area = get_area(&polygon.exterior) - polygon.interiors.iter()
+            .map(|x| get_area(x)).sum::<f64>();
+        let boundary: f64 = polygon.edges().map(|(start, end)|
+            (end.0 - start.0).abs() / x_interval
+                + (end.1 - start.1).abs() / y_interval).sum();
+
+        let interior = area / (x_interval * y_interval);
+        let estimate = match mode {
+            CoverMode::Centroid => interior,
+            CoverMode::Contained => interior - boundary / 2f64,
+            CoverMode::Intersecting => interior + boundary / 2f64 + 1f64,
+        };
+
+        Ok((estimate.max(0.0).round() as usize).min(limit))
+    }
+
+    fn count_cells(&self, bbox: &BoundingBox, precision: usize) -> usize {
+        let bounds = self.get_bounds();
+
+        // count cells from the index ranges rather than encoding them
+        let (x_interval, y_interval) = self.get_intervals(precision);
+        let upper = self.has_upper_edges();
+        let columns = cell_range(bbox.min_x, bbox.max_x,
+            bounds.min_x, bounds.max_x, x_interval, upper);
+        let rows = cell_range(bbox.min_y, bbox.max_y,
+            bounds.min_y, bounds.max_y, y_interval, upper);
+        match (columns, rows) {
+            (Some((min_i, max_i)), Some((min_j, max_j))) =>
+                (max_i - min_i + 1).saturating_mul(max_j - min_j + 1),
+            _ => 0,
+        }
+    }
+}
+
+fn get_area(ring: &[(f64, f64)]) -> f64 {
+    // shoelace formula, where a repeated closing point adds nothing
+    let sum: f64 = (0..ring.len()).map(|i| {
+        let (start, end) = (ring[i], ring[(i + 1) % ring.len()]);
+        start.0 * end.1 - end.0 * start.1
+    }).sum();
+
+    sum.abs() / 2f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CoverMode, Geocode, GeocodeError, Polygon};
+
+    #[test]
+    fn estimate_cover_bbox_size() {
+        let geocode = Geocode::Geohash;
+        for bbox in [BoundingBox::new(-88.45, -88.35, 44.24, 44.28),
+                BoundingBox::new(170.0, -170.0, -10.0, 10.0),
+                BoundingBox::new(0.0, 1.0, 100.0, 110.0)].iter() {
+            for precision in 1..=5 {
+                assert_eq!(geocode.estimate_cover_bbox_size(bbox, precision)
+                    .unwrap(), geocode.cover_bbox(bbox, precision)
+                    .unwrap().len());
+            }
+        }
+
+        // large coverings are counted without materializing cells
+        let bbox = BoundingBox::new(-180.0, 180.0, -90.0, 90.0);
+        assert_eq!(geocode.estimate_cover_bbox_size(&bbox, 12).unwrap(),
+            1 << 60);
+        assert!(geocode.estimate_cover_bbox_size(&bbox, 0).is_err());
+
+        // inputs are accepted and rejected exactly as cover_bbox does
+        let bbox = BoundingBox::new(-89.0, -88.0, 44.0, f64::NAN);
+        assert!(matches!(geocode.estimate_cover_bbox_size(&bbox, 3),
+            Err(GeocodeError::InvalidBoundingBox { .. })));
+        assert!(geocode.cover_bbox(&bbox, 3).is_err());
+
+        let bbox = BoundingBox::new(-89.0, -88.0, 44.0, 45.0);
+        for (geocode, precision) in [(Geocode::Maidenhead, 1),
+                (Geocode::Maidenhead, 3), (Geocode::Maidenhead, 4),
+                (Geocode::PlusCode, 1), (Geocode::PlusCode, 4)].iter() {
+            assert_eq!(geocode.estimate_cover_bbox_size(&bbox, *precision),
+                geocode.cover_bbox(&bbox, *precision).map(|x| x.len()));
+        }
+    }
+
+    #[test]
+    fn estimate_cover_size() {
+        let geocode = Geocode::Geohash;
+        let polygon = Polygon::new(vec![(-89.0, 44.0), (-88.0, 44.0),
+            (-88.0, 45.0), (-89.0, 44.0)], vec![vec![(-88.4, 44.2),
+            (-88.2, 44.2), (-88.2, 44.4), (-88.4, 44.4)]]);
+
+        for mode in [CoverMode::Centroid, CoverMode::Contained,
+                CoverMode::Intersecting].iter() {
+            for precision in 4..=6 {
+                let estimate = geocode.estimate_cover_size(&polygon,
+                    precision, *mode).unwrap() as f64;
+                let actual = geocode.cover_polygon(&polygon, precision,
+                    *mode).unwrap().len() as f64;
+                assert!((estimate - actual).abs() <= actual * 0.1 + 4.0,
+                    "{:?} {}: {} vs {}", mode, precision, estimate, actual);
+            }
+        }
+
+        // estimates never exceed the bounding box covering
+        let polygon = Polygon::new(vec![(-88.4, 44.26), (-88.39, 44.26),
+            (-88.39, 44.27)], Vec::new());
+        assert_eq!(geocode.estimate_cover_size(&polygon, 1,
+            CoverMode::Intersecting).unwrap(), 1);
+        assert!(geocode.estimate_cover_size(&Polygon::new(Vec::new(),
+            Vec::new()), 4, CoverMode::Centroid).is_err());
+    }
+}
@@ -0,0 +1,420 @@
+// safety requirements for each function are documented in include/geocode.h
+#![allow(clippy::missing_safety_doc)]
+
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+pub const GEOCODE_GEOHASH: u32 = 0;
+pub const GEOCODE_GEOHASH16: u32 = 1;
+pub const GEOCODE_GEOHASH36: u32 = 2;
+pub const GEOCODE_MAIDENHEAD: u32 = 3;
+pub const GEOCODE_PLUSCODE: u32 = 4;
+pub const GEOCODE_QUADTILE: u32 = 5;
+
+pub const GEOCODE_OK: i32 = 0;
+pub const GEOCODE_ERROR: i32 = -1;
+pub const GEOCODE_ERROR_NULL_POINTER: i32 = -2;
+pub const GEOCODE_ERROR_INVALID_TYPE: i32 = -3;
+pub const GEOCODE_ERROR_INVALID_UTF8: i32 = -4;
+pub const GEOCODE_ERROR_BUFFER_TOO_SMALL: i32 = -5;
+pub const GEOCODE_ERROR_PANIC: i32 = -6;
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+#[repr(C)]
+pub struct GeocodeStrings {
+    pub data: *mut *mut c_char,
+    pub len: usize,
+}
+
+enum FfiError {
+    BufferTooSmall { required: usize },
+    Geocode(GeocodeError),
+    InvalidType { geocode: u32 },
+    InvalidUtf8,
+    NullPointer,
+    Panic,
+}
+
+impl From<GeocodeError> for FfiError {
+    fn from(e: GeocodeError) -> FfiError {
+        FfiError::Geocode(e)
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn geocode_cover_bbox(geocode: u32,
+        bbox: *const BoundingBox, precision: usize,
+        out: *mut GeocodeStrings) -> i32 {
+    finish(|| {
+        let geocode = get_geocode(geocode)?;
+        let bbox = bbox.as_ref().ok_or(FfiError::NullPointer)?;
+        let out = out.as_mut().ok_or(FfiError::NullPointer)?;
+
+        *out = to_strings(geocode.cover_bbox(bbox, precision)?);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn geocode_decode(geocode: u32, code: *const c_char,
+        out: *mut BoundingBox) -> i32 {
+    finish(|| {
+        let geocode = get_geocode(geocode)?;
+        let code = get_str(code)?;
+        let out = out.as_mut().ok_or(FfiError::NullPointer)?;
+
+        *out = geocode.decode(code)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn geocode_encode(geocode: u32, x: f64, y: f64,
+        precision: usize, buf: *mut c_char, buf_len: usize) -> i32 {
+    finish(|| {
+        let geocode = get_geocode(geocode)?;
+        if buf.is_null() {
+            return Err(FfiError::NullPointer);
+        }
+
+        // check if the code and its nul terminator fit within the buffer
+        let code = geocode.encode(x, y, precision)?;
+        if code.len() >= buf_len {
+            return Err(FfiError::BufferTooSmall { required: code.len() + 1 });
+        }
+
+        ptr::copy_nonoverlapping(code.as_ptr() as *const c_char,
+            buf, code.len());
+        *buf.add(code.len()) = 0;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn geocode_last_error() -> *const c_char {
+    // the message remains valid until the next failure on this thread
+    panic::catch_unwind(|| LAST_ERROR.with(|x| x.borrow().as_ptr()))
+        .unwrap_or(ptr::null())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn geocode_neighbors(geocode: u32,
+        code: *const c_char, out: *mut GeocodeStrings) -> i32 {
+    finish(|| {
+        let geocode = get_geocode(geocode)?;
+        let code = get_str(code)?;
+        let out = out.as_mut().ok_or(FfiError::NullPointer)?;
+
+        *out = to_strings(geocode.neighbors(code)?);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn geocode_strings_free(strings: *mut GeocodeStrings) {
+    // panics must not unwind into the caller, and there is no status to
+    // report them through
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let strings = match strings.as_mut() {
+            Some(strings) if !strings.data.is_null() => strings,
+            _ => return,
+        };
+
+        let data = Box::from_raw(ptr::slice_from_raw_parts_mut(strings.data,
+            strings.len));
+        for value in data.iter() {
+            drop(CString::from_raw(*value));
+        }
+
+        strings.data = ptr::null_mut();
+        strings.len = 0;
+    }));
+}
+
+fn finish<F: FnOnce() -> Result<(), FfiError>>(f: F) -> i32 {
+    // panics must not unwind into the caller, so they are reported as
+    // failures like any other error
+    let result = panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or(Err(FfiError::Panic));
+
+    // record the error message before returning its status code
+    let (status, message) = match result {
+        Ok(()) => return GEOCODE_OK,
+        Err(FfiError::BufferTooSmall { required }) =>
+            (GEOCODE_ERROR_BUFFER_TOO_SMALL,
+                format!("buffer requires {} bytes", required)),
+        Err(FfiError::Geocode(e)) => (GEOCODE_ERROR, e.to_string()),
+        Err(FfiError::InvalidType { geocode }) => (GEOCODE_ERROR_INVALID_TYPE,
+            format!("invalid geocode type {}", geocode)),
+        Err(FfiError::InvalidUtf8) => (GEOCODE_ERROR_INVALID_UTF8,
+            "code is not valid utf-8".to_string()),
+        Err(FfiError::NullPointer) => (GEOCODE_ERROR_NULL_POINTER,
+            "unexpected null pointer".to_string()),
+        Err(FfiError::Panic) => (GEOCODE_ERROR_PANIC,
+            "unexpected internal panic".to_string()),
+    };
+
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|x| *x.borrow_mut() = message);
+    status
+}
+
+fn get_geocode(geocode: u32) -> Result<Geocode, FfiError> {
+    match geocode {
+        GEOCODE_GEOHASH => Ok(Geocode::Geohash),
+        GEOCODE_GEOHASH16 => Ok(Geocode::Geohash16),
+        GEOCODE_GEOHASH36 => Ok(Geocode::Geohash36),
+        GEOCODE_MAIDENHEAD => Ok(Geocode::Maidenhead),
+        GEOCODE_PLUSCODE => Ok(Geocode::PlusCode),
+        GEOCODE_QUADTILE => Ok(Geocode::QuadTile),
+        _ => Err(FfiError::InvalidType { geocode }),
+    }
+}
+
+unsafe fn get_str<'a>(value: *const c_char) -> Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Err(FfiError::NullPointer);
+    }
+
+    CStr::from_ptr(value).to_str().map_err(|_| FfiError::InvalidUtf8)
+}
+
+fn to_strings(values: Vec<String>) -> GeocodeStrings {
+    // codes are ascii, so they never contain interior nul bytes
+    let data: Box<[*mut c_char]> = values.into_iter()
+        .map(|x| CString::new(x).unwrap_or_default().into_raw())
+        .collect();
+
+    let len = data.len();
+    GeocodeStrings { data: Box::into_raw(data) as *mut *mut c_char, len }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode};
+    use super::*;
+
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+
+    unsafe fn from_strings(strings: &GeocodeStrings) -> Vec<String> {
+        (0..strings.len).map(|i| CStr::from_ptr(*strings.data.add(i))
+            .to_str().unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn encode() {
+        let mut buf = [0 as c_char; 16];
+        unsafe {
+            assert_eq!(geocode_encode(GEOCODE_GEOHASH, -88.4, 44.266667, 6,
+                buf.as_mut_ptr(), buf.len()), GEOCODE_OK);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(),
+                "dpc5u6");
+
+            // codes require space for the nul terminator
+            assert_eq!(geocode_encode(GEOCODE_GEOHASH, -88.4, 44.266667, 6,
+                buf.as_mut_ptr(), 6), GEOCODE_ERROR_BUFFER_TOO_SMALL);
+            assert_eq!(geocode_encode(GEOCODE_GEOHASH, -88.4, 44.266667, 6,
+                ptr::null_mut(), 16), GEOCODE_ERROR_NULL_POINTER);
+            assert_eq!(geocode_encode(6, -88.4, 44.266667, 6,
+                buf.as_mut_ptr(), buf.len()), GEOCODE_ERROR_INVALID_TYPE);
+
+            assert_eq!(geocode_encode(GEOCODE_GEOHASH, 200.0, 44.266667, 6,
+                buf.as_mut_ptr(), buf.len()), GEOCODE_ERROR);
+            let message = CStr::from_ptr(geocode_last_error());
+            assert!(message.to_str().unwrap().starts_with("coordinate"));
+        }
+    }
+
+    #[test]
+    fn decode() {
+        let code = CString::new("dpc5u6").unwrap();
+        let mut bbox = BoundingBox::new(0.0, 0.0, 0.0, 0.0);
+        unsafe {
+            assert_eq!(geocode_decode(GEOCODE_GEOHASH, code.as_ptr(),
+                &mut bbox), GEOCODE_OK);
+            assert_eq!(bbox, Geocode::Geohash.decode("dpc5u6").unwrap());
+
+            let code = CString::new("dpca").unwrap();
+            assert_eq!(geocode_decode(GEOCODE_GEOHASH, code.as_ptr(),
+                &mut bbox), GEOCODE_ERROR);
+            assert_eq!(CStr::from_ptr(geocode_last_error()).to_str().unwrap(),
+                "invalid character 'a' at index 3");
+            assert_eq!(geocode_decode(GEOCODE_GEOHASH, ptr::null(),
+                &mut bbox), GEOCODE_ERROR_NULL_POINTER);
+        }
+    }
+
+    #[test]
+    fn panic() {
+        // panics are caught before reaching the caller
+        assert_eq!(finish(|| panic!("failure")), GEOCODE_ERROR_PANIC);
+        unsafe {
+            assert_eq!(CStr::from_ptr(geocode_last_error()).to_str().unwrap(),
+                "unexpected internal panic");
+        }
+        assert_eq!(finish(|| Ok(())), GEOCODE_OK);
+    }
+
+    #[test]
+    fn strings() {
+        let code = CString::new("0302").unwrap();
+        let mut strings = GeocodeStrings { data: ptr::null_mut(), len: 0 };
+        unsafe {
+            assert_eq!(geocode_neighbors(GEOCODE_QUADTILE, code.as_ptr(),
+                &mut strings), GEOCODE_OK);
+            assert_eq!(from_strings(&strings),
+                Geocode::QuadTile.neighbors("0302").unwrap());
+            geocode_strings_free(&mut strings);
+            assert!(strings.data.is_null());
+
+            let bbox = BoundingBox::new(-89.0, -88.0, 44.0, 45.0);
+            assert_eq!(geocode_cover_bbox(GEOCODE_GEOHASH, &bbox, 4,
+                &mut strings), GEOCODE_OK);
+            assert_eq!(from_strings(&strings),
+                Geocode::Geohash.cover_bbox(&bbox, 4).unwrap());
+            geocode_strings_free(&mut strings);
+
+            // freeing released or null strings is a no-op
+            geocode_strings_free(&mut strings);
+            geocode_strings_free(ptr::null_mut());
+        }
+    }
+
+    fn parse_header() -> (Vec<String>, Vec<(String, String)>) {
+        // strip comments and preprocessor lines, then split statements
+        // into struct definitions and function prototypes
+        let header = include_str!("../include/geocode.h");
+        let mut text: String = header.lines()
+            .filter(|x| !x.starts_with('#')).collect::<Vec<&str>>()
+            .join(" ");
+        while let Some(start) = text.find("/*") {
+            let end = text[start..].find("*/").unwrap() + start + 2;
+            text.replace_range(start..end, "");
+        }
+
+        let text = text.replace("extern \"C\" {", "");
+        let mut structs = Vec::new();
+        let mut prototypes = Vec::new();
+        let mut remaining = text.as_str();
+        while let Some(start) = remaining.find("typedef struct {") {
+            let end = remaining[start..].find('}').unwrap() + start;
+            let name_end = remaining[end..].find(';').unwrap() + end;
+            structs.push((remaining[end + 1..name_end].trim().to_string(),
+                normalize(&remaining[start + 16..end])));
+            prototypes.push(&remaining[..start]);
+            remaining = &remaining[name_end + 1..];
+        }
+
+        prototypes.push(remaining);
+        let prototypes = prototypes.iter()
+            .flat_map(|x| x.split(';'))
+            .map(|x| normalize(x.trim_matches(|c: char| c.is_whitespace()
+                || c == '}')))
+            .filter(|x| !x.is_empty()).collect();
+        (prototypes, structs)
+    }
+
+    fn normalize(value: &str) -> String {
+        // whitespace is ignored when comparing declarations
+        value.chars().filter(|x| !x.is_whitespace()).collect()
+    }
+
+    fn to_c_type(value: &str) -> &'static str {
+        match value.trim() {
+            "" => "void",
+            "f64" => "double",
+            "i32" => "int32_t",
+            "u32" => "uint32_t",
+            "usize" => "size_t",
+            "*const c_char" => "const char *",
+            "*mut c_char" => "char *",
+            "*mut *mut c_char" => "char **",
+            "*const BoundingBox" => "const geocode_bbox_t *",
+            "*mut BoundingBox" => "geocode_bbox_t *",
+            "*mut GeocodeStrings" => "geocode_strings_t *",
+            x => panic!("no c type for '{}'", x),
+        }
+    }
+
+    fn to_c_declarations(source: &str) -> String {
+        // convert rust parameters or fields into c declarations
+        source.split(',').filter(|x| !x.trim().is_empty()).map(|x| {
+            let (name, value) = x.split_at(x.find(':').unwrap());
+            let name = name.trim().trim_start_matches("pub ");
+            format!("{} {}", to_c_type(&value[1..]), name)
+        }).collect::<Vec<String>>().join(", ")
+    }
+
+    #[test]
+    fn header() {
+        let (prototypes, structs) = parse_header();
+
+        // each exported function is declared with matching c types
+        let source = include_str!("ffi.rs");
+        let mut count = 0;
+        for signature in source.split("extern \"C\" fn ").skip(1) {
+            let signature = &signature[..signature.find('{').unwrap()];
+            let open = signature.find('(').unwrap();
+            let close = signature.rfind(')').unwrap();
+            let parameters = to_c_declarations(&signature[open + 1..close]);
+            let declaration = format!("{} {}({})",
+                to_c_type(signature[close + 1..].trim()
+                    .trim_start_matches("->")),
+                &signature[..open], match parameters.is_empty() {
+                    true => "void",
+                    false => &parameters,
+                });
+
+            assert!(prototypes.contains(&normalize(&declaration)),
+                "{} is not declared in the header", declaration);
+            count += 1;
+        }
+
+        assert_eq!(count, 6);
+        assert_eq!(prototypes.len(), count, "{:?}", prototypes);
+
+        // structs share the field order and types of their rust types
+        let fields = |source: &str, name: &str| -> String {
+            let body = &source[source.find(&format!("pub struct {} {{",
+                name)).unwrap()..];
+            let body = &body[body.find('{').unwrap() + 1
+                ..body.find('}').unwrap()];
+            normalize(&to_c_declarations(body).replace(", ", ";")) + ";"
+        };
+        assert_eq!(structs, vec![
+            ("geocode_bbox_t".to_string(),
+                fields(include_str!("bbox.rs"), "BoundingBox")),
+            ("geocode_strings_t".to_string(),
+                fields(source, "GeocodeStrings"))]);
+
+        // constants match their rust values
+        let header = include_str!("../include/geocode.h");
+        for (name, value) in [("GEOCODE_GEOHASH", GEOCODE_GEOHASH as i32),
+                ("GEOCODE_GEOHASH16", GEOCODE_GEOHASH16 as i32),
+                ("GEOCODE_GEOHASH36", GEOCODE_GEOHASH36 as i32),
+                ("GEOCODE_MAIDENHEAD", GEOCODE_MAIDENHEAD as i32),
+                ("GEOCODE_PLUSCODE", GEOCODE_PLUSCODE as i32),
+                ("GEOCODE_QUADTILE", GEOCODE_QUADTILE as i32),
+                ("GEOCODE_OK", GEOCODE_OK),
+                ("GEOCODE_ERROR", GEOCODE_ERROR),
+                ("GEOCODE_ERROR_NULL_POINTER", GEOCODE_ERROR_NULL_POINTER),
+                ("GEOCODE_ERROR_INVALID_TYPE", GEOCODE_ERROR_INVALID_TYPE),
+                ("GEOCODE_ERROR_INVALID_UTF8", GEOCODE_ERROR_INVALID_UTF8),
+                ("GEOCODE_ERROR_BUFFER_TOO_SMALL",
+                    GEOCODE_ERROR_BUFFER_TOO_SMALL),
+                ("GEOCODE_ERROR_PANIC", GEOCODE_ERROR_PANIC)].iter() {
+            assert!(header.lines().any(|x| x == format!("#define {} {}",
+                name, value)), "{} is not defined as {}", name, value);
+        }
+    }
+
+}
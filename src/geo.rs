@@ -0,0 +1,104 @@
+use crate::{BoundingBox, CoverMode, Geocode, GeocodeError, Polygon};
+
+use geo_types::{Coord, LineString, Point, Rect};
+
+impl From<BoundingBox> for Rect<f64> {
+    fn from(bbox: BoundingBox) -> Rect<f64> {
+        Rect::new(Coord { x: bbox.min_x, y: bbox.min_y },
+            Coord { x: bbox.max_x, y: bbox.max_y })
+    }
+}
+
+impl From<Rect<f64>> for BoundingBox {
+    fn from(rect: Rect<f64>) -> BoundingBox {
+        BoundingBox::new(rect.min().x, rect.max().x,
+            rect.min().y, rect.max().y)
+    }
+}
+
+impl From<&geo_types::Polygon<f64>> for Polygon {
+    fn from(polygon: &geo_types::Polygon<f64>) -> Polygon {
+        Polygon::new(get_points(polygon.exterior()),
+            polygon.interiors().iter().map(get_points).collect())
+    }
+}
+
+impl From<&Polygon> for geo_types::Polygon<f64> {
+    fn from(polygon: &Polygon) -> geo_types::Polygon<f64> {
+        geo_types::Polygon::new(LineString::from(polygon.exterior.clone()),
+            polygon.interiors.iter().cloned().map(LineString::from).collect())
+    }
+}
+
+impl Geocode {
+    pub fn cover_geo_polygon(&self, polygon: &geo_types::Polygon<f64>,
+            precision: usize, mode: CoverMode)
+            -> Result<Vec<String>, GeocodeError> {
+        self.cover_polygon(&Polygon::from(polygon), precision, mode)
+    }
+
+    pub fn cover_rect(&self, rect: &Rect<f64>, precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        self.cover_bbox(&BoundingBox::from(*rect), precision)
+    }
+
+    pub fn decode_rect(&self, value: &str)
+            -> Result<Rect<f64>, GeocodeError> {
+        self.decode(value).map(Rect::from)
+    }
+
+    pub fn encode_point(&self, point: &Point<f64>, precision: usize)
+            -> Result<String, GeocodeError> {
+        self.encode(point.x(), point.y(), precision)
+    }
+}
+
+fn get_points(line_string: &LineString<f64>) -> Vec<(f64, f64)> {
+    line_string.coords().map(|x| (x.x, x.y)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CoverMode, Geocode, Polygon};
+
+    use geo_types::{coord, point, polygon, Rect};
+
+    #[test]
+    fn conversions() {
+        let bbox = BoundingBox::new(-1.0, 1.0, -2.0, 2.0);
+        let rect = Rect::from(bbox);
+        assert_eq!(rect, Rect::new(coord! { x: 1.0, y: 2.0 },
+            coord! { x: -1.0, y: -2.0 }));
+        assert_eq!(BoundingBox::from(rect), bbox);
+
+        // geo types close rings which are otherwise left unchanged
+        let polygon = Polygon::new(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0),
+            (0.0, 0.0)], vec![vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0),
+            (1.0, 1.0)]]);
+        let geo_polygon = geo_types::Polygon::from(&polygon);
+        assert_eq!(geo_polygon.interiors().len(), 1);
+        assert_eq!(Polygon::from(&geo_polygon), polygon);
+    }
+
+    #[test]
+    fn geocode() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_point(&point! { x: -88.4, y: 44.266667 }, 6)
+            .unwrap(), "dpc5u6");
+        assert_eq!(geocode.decode_rect("dpc5u6").unwrap(),
+            Rect::from(geocode.decode("dpc5u6").unwrap()));
+        assert!(geocode.encode_point(&point! { x: 200.0, y: 0.0 }, 6)
+            .is_err());
+
+        let rect = Rect::new(coord! { x: -89.0, y: 44.0 },
+            coord! { x: -88.0, y: 45.0 });
+        assert_eq!(geocode.cover_rect(&rect, 3).unwrap(),
+            geocode.cover_bbox(&BoundingBox::from(rect), 3).unwrap());
+
+        let polygon = polygon![(x: -89.0, y: 44.0), (x: -88.0, y: 44.0),
+            (x: -88.0, y: 45.0)];
+        assert_eq!(geocode.cover_geo_polygon(&polygon, 4,
+            CoverMode::Intersecting).unwrap(), geocode.cover_polygon(
+            &Polygon::from(&polygon), 4, CoverMode::Intersecting).unwrap());
+    }
+}
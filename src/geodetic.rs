@@ -0,0 +1,199 @@
+use crate::{BoundingBox, GeocodeError, GridSystem, GEOHASH_BOUNDS};
+
+const MAX_LEVEL: usize = 30;
+static GEODETIC_ROOT_CHARS: &[char] = &['0', '1'];
+static GEODETIC_CHARS: &[char] = &['0', '1', '2', '3'];
+
+// a tile of the wmts geodetic tile matrix set, where level zero holds two
+// 180 degree tiles and rows are numbered from the north
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GeodeticTile {
+    pub level: usize,
+    pub row: u32,
+    pub col: u32,
+}
+
+impl GeodeticTile {
+    pub fn new(level: usize, row: u32, col: u32)
+            -> Result<GeodeticTile, GeocodeError> {
+        // check if level and tile indices are valid
+        if level > MAX_LEVEL {
+            return Err(GeocodeError::InvalidPrecision { precision: level });
+        } else if row >> level != 0 || col >> (level + 1) != 0 {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "tile (row {}, col {}) is outside of level {}",
+                row, col, level) });
+        }
+
+        Ok(GeodeticTile { level, row, col })
+    }
+
+    pub fn from_code(value: &str) -> Result<GeodeticTile, GeocodeError> {
+        // check if code is valid
+        let precision = value.chars().count();
+        if precision == 0 {
+            return Err(GeocodeError::EmptyCode);
+        } else if precision > MAX_LEVEL + 1 {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // the root digit selects the hemisphere column, after which each
+        // quadkey digit contributes one bit to the column and row
+        let (mut row, mut col) = (0u32, 0u32);
+        for (index, c) in value.chars().enumerate() {
+            let alphabet = GeodeticGrid.get_alphabet(index);
+            let digit = match alphabet.iter().position(|x| *x == c) {
+                Some(digit) => digit as u32,
+                None => return Err(GeocodeError::InvalidCharacter {
+                    ch: c, index }),
+            };
+
+            col = (col << 1) | (digit & 1);
+            row = match index {
+                0 => row,
+                _ => (row << 1) | (digit >> 1),
+            };
+        }
+
+        Ok(GeodeticTile { level: precision - 1, row, col })
+    }
+
+    pub fn from_lonlat(lon: f64, lat: f64, level: usize)
+            -> Result<GeodeticTile, GeocodeError> {
+        // check if coordinates and level are valid
+        if !GEOHASH_BOUNDS.contains(lon, lat) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x: lon, y: lat, bounds: GEOHASH_BOUNDS });
+        } else if level > MAX_LEVEL {
+            return Err(GeocodeError::InvalidPrecision { precision: level });
+        }
+
+        // the eastern and southern edges belong to the final tiles
+        let size = get_tile_size(level);
+        let (rows, cols) = (1u64 << level, 1u64 << (level + 1));
+        let row = (((90f64 - lat) / size) as u64).min(rows - 1);
+        let col = (((lon + 180f64) / size) as u64).min(cols - 1);
+        Ok(GeodeticTile { level, row: row as u32, col: col as u32 })
+    }
+
+    pub fn get_bounds(&self) -> BoundingBox {
+        let size = get_tile_size(self.level);
+        BoundingBox::new(-180f64 + self.col as f64 * size,
+            -180f64 + (self.col as f64 + 1f64) * size,
+            90f64 - (self.row as f64 + 1f64) * size,
+            90f64 - self.row as f64 * size)
+    }
+
+    pub fn get_code(&self) -> String {
+        let mut code = String::with_capacity(self.level + 1);
+        code.push(GEODETIC_ROOT_CHARS[(self.col >> self.level) as usize]);
+        for i in (0..self.level).rev() {
+            let digit = ((self.col >> i) & 1) | (((self.row >> i) & 1) << 1);
+            code.push(GEODETIC_CHARS[digit as usize]);
+        }
+
+        code
+    }
+}
+
+// the wmts geodetic tile matrix set as a grid, where codes hold a root
+// digit followed by one quadkey digit per level
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GeodeticGrid;
+
+impl GridSystem for GeodeticGrid {
+    fn get_alphabet(&self, index: usize) -> &[char] {
+        match index {
+            0 => GEODETIC_ROOT_CHARS,
+            _ => GEODETIC_CHARS,
+        }
+    }
+
+    fn get_bits_per_char(&self) -> Option<usize> {
+        None
+    }
+
+    fn get_bounds(&self) -> BoundingBox {
+        GEOHASH_BOUNDS
+    }
+
+    fn get_epsg_code(&self) -> u32 {
+        4326
+    }
+
+    fn decode(&self, value: &str) -> Result<BoundingBox, GeocodeError> {
+        Ok(GeodeticTile::from_code(value)?.get_bounds())
+    }
+
+    fn encode(&self, x: f64, y: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        match precision {
+            0 => Err(GeocodeError::InvalidPrecision { precision }),
+            _ => Ok(GeodeticTile::from_lonlat(x, y, precision - 1)?
+                .get_code()),
+        }
+    }
+
+    fn get_intervals(&self, precision: usize) -> (f64, f64) {
+        let size = get_tile_size(precision.saturating_sub(1));
+        (size, size)
+    }
+}
+
+fn get_tile_size(level: usize) -> f64 {
+    180f64 / 2f64.powi(level as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, GeocodeError, GridSystem};
+    use super::{GeodeticGrid, GeodeticTile};
+
+    #[test]
+    fn geodetic_grid() {
+        let grid = GeodeticGrid;
+        assert_eq!(grid.encode(-88.4, 44.266667, 1).unwrap(), "0");
+        assert_eq!(grid.encode(88.4, -44.266667, 1).unwrap(), "1");
+        assert_eq!(grid.decode("1").unwrap(),
+            BoundingBox::new(0.0, 180.0, -90.0, 90.0));
+        assert_eq!(grid.decode("02").unwrap(),
+            BoundingBox::new(-180.0, -90.0, -90.0, 0.0));
+
+        let code = grid.encode(-88.4, 44.266667, 12).unwrap();
+        assert!(grid.decode(&code).unwrap().contains(-88.4, 44.266667));
+        assert_eq!(grid.get_intervals(12), (180.0 / 2048.0, 180.0 / 2048.0));
+
+        assert_eq!(grid.decode("2"), Err(GeocodeError::InvalidCharacter {
+            ch: '2', index: 0 }));
+        assert_eq!(grid.decode(""), Err(GeocodeError::EmptyCode));
+        assert!(grid.encode(-88.4, 44.266667, 0).is_err());
+        assert!(grid.encode(-188.4, 44.266667, 4).is_err());
+    }
+
+    #[test]
+    fn geodetic_tile() {
+        // level zero holds two tiles in a single row
+        let tile = GeodeticTile::from_lonlat(120.0, 10.0, 0).unwrap();
+        assert_eq!(tile, GeodeticTile { level: 0, row: 0, col: 1 });
+        assert_eq!(tile.get_code(), "1");
+
+        let tile = GeodeticTile::from_lonlat(-88.4, 44.266667, 3).unwrap();
+        assert_eq!(tile, GeodeticTile::new(3, 2, 4).unwrap());
+        assert_eq!(tile.get_bounds(),
+            BoundingBox::new(-90.0, -67.5, 22.5, 45.0));
+        assert_eq!(GeodeticTile::from_code(&tile.get_code()), Ok(tile));
+
+        // the south east corner lies in the final tile at every level
+        for level in [0, 1, 7, 30].iter() {
+            let tile = GeodeticTile::from_lonlat(180.0, -90.0, *level)
+                .unwrap();
+            assert_eq!(tile.row, ((1u64 << level) - 1) as u32);
+            assert_eq!(tile.col, ((1u64 << (level + 1)) - 1) as u32);
+            assert_eq!(GeodeticTile::from_code(&tile.get_code()), Ok(tile));
+        }
+
+        assert!(GeodeticTile::new(1, 2, 0).is_err());
+        assert!(GeodeticTile::new(1, 0, 4).is_err());
+        assert!(GeodeticTile::new(31, 0, 0).is_err());
+    }
+}
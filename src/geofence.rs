@@ -0,0 +1,204 @@
+use crate::{BoundingBox, CellSet, Geocode, GeocodeError, Polygon};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub struct Geofence {
+    geocode: Geocode,
+    precision: usize,
+    polygons: Vec<Polygon>,
+    bbox: BoundingBox,
+    // cells entirely within a polygon are matched without further checks
+    interior: CellSet,
+    // cells crossed by polygon edges, mapped to the crossing polygons
+    boundary: HashMap<String, Vec<usize>>,
+}
+
+impl Geofence {
+    pub fn new(geocode: Geocode, polygons: Vec<Polygon>, precision: usize)
+            -> Result<Geofence, GeocodeError> {
+        // check if polygons and precision are valid
+        if polygons.is_empty() {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: "geofences require at least one polygon"
+                    .to_string() });
+        }
+
+        for polygon in polygons.iter() {
+            polygon.validate()?;
+        }
+
+        let (x, y) = geocode.get_bounds().center();
+        geocode.encode(x, y, precision)?;
+
+        // boundary cells must be reachable by refinement
+        let mut cell_precision = 0;
+        while cell_precision < precision {
+            cell_precision = geocode.get_child_precision(cell_precision)
+                .unwrap_or(usize::MAX);
+        }
+
+        if cell_precision != precision {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        let mut bbox = BoundingBox::new(f64::INFINITY,
+            f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+        for polygon_bbox in polygons.iter().map(|x| x.bounding_box()) {
+            bbox.min_x = bbox.min_x.min(polygon_bbox.min_x);
+            bbox.max_x = bbox.max_x.max(polygon_bbox.max_x);
+            bbox.min_y = bbox.min_y.min(polygon_bbox.min_y);
+            bbox.max_y = bbox.max_y.max(polygon_bbox.max_y);
+        }
+
+        let mut geofence = Geofence { geocode, precision, polygons, bbox,
+            interior: CellSet::new(geocode), boundary: HashMap::new() };
+
+        // subdivide from the coarsest cells, only refining those crossed
+        // by an edge of some polygon
+        let candidates: Vec<usize> = (0..geofence.polygons.len()).collect();
+        let min_precision = geocode.get_child_precision(0).unwrap();
+        let mut interior = Vec::new();
+        for code in geocode.cover_bbox(&bbox, min_precision)? {
+            let key = geocode.parse_key(&code)?;
+            geofence.classify(key, &candidates, &mut interior)?;
+        }
+
+        geofence.interior = CellSet::from_cells(geocode, interior)?;
+        Ok(geofence)
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> Result<bool, GeocodeError> {
+        // check if coordinates are valid
+        let bounds = self.geocode.get_bounds();
+        if !bounds.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds });
+        } else if !self.bbox.contains(x, y) {
+            return Ok(false);
+        }
+
+        let code = self.geocode.encode(x, y, self.precision)?;
+        if self.interior.contains(&code) {
+            return Ok(true);
+        }
+
+        // boundary cells fall back to exact point in polygon tests
+        Ok(match self.boundary.get(&code) {
+            Some(indices) => indices.iter()
+                .any(|i| self.polygons[*i].contains(x, y)),
+            None => false,
+        })
+    }
+
+    pub fn get_boundary_len(&self) -> usize {
+        self.boundary.len()
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_interior(&self) -> &CellSet {
+        &self.interior
+    }
+
+    pub fn get_polygons(&self) -> &[Polygon] {
+        &self.polygons
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    fn classify(&mut self, key: String, candidates: &[usize],
+            interior: &mut Vec<String>) -> Result<(), GeocodeError> {
+        let code = self.geocode.format_key(&key);
+        let bbox = self.geocode.decode(&code)?;
+        let (x, y) = bbox.center();
+
+        // cells not crossed by a polygon edge lie entirely inside or
+        // outside of that polygon
+        let mut crossing = Vec::new();
+        for i in candidates.iter() {
+            let polygon = &self.polygons[*i];
+            if polygon.crosses_interior(&bbox) {
+                crossing.push(*i);
+            } else if polygon.contains(x, y) {
+                interior.push(code);
+                return Ok(());
+            }
+        }
+
+        if crossing.is_empty() {
+            return Ok(());
+        } else if key.chars().count() >= self.precision {
+            self.boundary.insert(code, crossing);
+            return Ok(());
+        }
+
+        for child in self.geocode.child_keys(&key).unwrap_or_default() {
+            self.classify(child, &crossing, interior)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError, Polygon};
+    use super::Geofence;
+
+    fn square(min: f64, max: f64) -> Vec<(f64, f64)> {
+        vec![(min, min), (max, min), (max, max), (min, max)]
+    }
+
+    #[test]
+    fn contains() {
+        // a square with a hole alongside a triangle
+        let polygons = vec![
+            Polygon::new(square(0.0, 10.0), vec![square(4.0, 6.0)]),
+            Polygon::new(vec![(20.0, 0.0), (30.0, 0.0), (25.0, 10.0)],
+                Vec::new()),
+        ];
+        let geofence = Geofence::new(Geocode::Geohash, polygons.clone(), 6)
+            .unwrap();
+        assert!(!geofence.get_interior().is_empty());
+        assert!(geofence.get_boundary_len() > 0);
+
+        assert!(geofence.contains(1.0, 1.0).unwrap());
+        assert!(!geofence.contains(5.0, 5.0).unwrap());
+        assert!(geofence.contains(25.0, 5.0).unwrap());
+        assert!(!geofence.contains(21.0, 9.0).unwrap());
+        assert!(!geofence.contains(-50.0, 50.0).unwrap());
+        assert!(geofence.contains(0.0, 91.0).is_err());
+
+        // results match exact tests across both polygons
+        for i in 0..200 {
+            let x = -2.0 + (i as f64 * 0.173) % 34.0;
+            let y = -2.0 + (i as f64 * 0.291) % 14.0;
+            assert_eq!(geofence.contains(x, y).unwrap(),
+                polygons.iter().any(|p| p.contains(x, y)));
+        }
+    }
+
+    #[test]
+    fn new() {
+        let polygon = Polygon::new(square(0.0, 10.0), Vec::new());
+        let geofence = Geofence::new(Geocode::PlusCode,
+            vec![polygon.clone()], 6).unwrap();
+        assert_eq!(geofence.get_precision(), 6);
+        assert_eq!(geofence.get_polygons().len(), 1);
+        assert!(geofence.contains(5.0, 5.0).unwrap());
+
+        assert!(Geofence::new(Geocode::Geohash, Vec::new(), 6).is_err());
+        assert!(Geofence::new(Geocode::PlusCode,
+            vec![polygon.clone()], 5).is_err());
+        assert_eq!(Geofence::new(Geocode::Geohash,
+            vec![polygon], 0).err(),
+            Some(GeocodeError::InvalidPrecision { precision: 0 }));
+        assert!(Geofence::new(Geocode::Geohash,
+            vec![Polygon::new(vec![(0.0, 0.0)], Vec::new())], 6).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{math, BoundingBox, GeocodeError, GEOHASH_BOUNDS};
+
+use core::fmt::Write;
+
+pub(crate) static GEOHASH36_CHARS: &[char] = &['2', '3', '4', '5', '6',
+    '7', '8', '9', 'b', 'B', 'C', 'd', 'D', 'F', 'g', 'G', 'h', 'H', 'j',
+    'J', 'K', 'l', 'L', 'M', 'n', 'N', 'P', 'q', 'Q', 'r', 'R', 't', 'T',
+    'V', 'W', 'X'];
+
+// characters are laid out in a 6x6 grid from the north west corner
+const GRID_SIZE: usize = 6;
+
+pub(crate) fn decode(value: &str) -> Result<BoundingBox, GeocodeError> {
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    }
+
+    // compute bounds
+    let mut bbox = GEOHASH_BOUNDS;
+    for (index, c) in value.chars().enumerate() {
+        let hash_value = match GEOHASH36_CHARS.iter().position(|x| *x == c) {
+            Some(hash_value) => hash_value,
+            None => return Err(GeocodeError::InvalidCharacter {
+                ch: c, index }),
+        };
+
+        let column = hash_value % GRID_SIZE;
+        let row = GRID_SIZE - 1 - hash_value / GRID_SIZE;
+        bbox = get_cell(&bbox, column, row);
+    }
+
+    Ok(bbox)
+}
+
+pub(crate) fn encode<W: Write>(x: f64, y: f64, precision: usize,
+        out: &mut W) -> Result<(), GeocodeError> {
+    // check if coordinates and precision are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    } else if precision == 0 {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    // compute geocode code
+    let mut bbox = GEOHASH_BOUNDS;
+    for _ in 0..precision {
+        let column = get_index(x - bbox.min_x, bbox.width());
+        let row = get_index(y - bbox.min_y, bbox.height());
+
+        out.write_char(
+            GEOHASH36_CHARS[(GRID_SIZE - 1 - row) * GRID_SIZE + column])?;
+        bbox = get_cell(&bbox, column, row);
+    }
+
+    Ok(())
+}
+
+fn get_cell(bbox: &BoundingBox, column: usize, row: usize) -> BoundingBox {
+    let width = bbox.width() / GRID_SIZE as f64;
+    let height = bbox.height() / GRID_SIZE as f64;
+    BoundingBox::new(bbox.min_x + column as f64 * width,
+        bbox.min_x + (column + 1) as f64 * width,
+        bbox.min_y + row as f64 * height,
+        bbox.min_y + (row + 1) as f64 * height)
+}
+
+fn get_index(offset: f64, length: f64) -> usize {
+    // coordinates on cell edges belong to the east or north cell, except
+    // at the maximum longitude and latitude
+    let index = math::floor(offset / length * GRID_SIZE as f64) as usize;
+    index.min(GRID_SIZE - 1)
+}
+
+pub(crate) fn get_intervals(precision: usize) -> (f64, f64) {
+    let divisions = math::powi(GRID_SIZE as f64, precision as i32);
+    (GEOHASH_BOUNDS.width() / divisions, GEOHASH_BOUNDS.height() / divisions)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn decode() {
+        let geocode = Geocode::Geohash36;
+        let bbox = geocode.decode("2").unwrap();
+        assert_eq!((bbox.min_x, bbox.max_x), (-180.0, -120.0));
+        assert_eq!((bbox.min_y, bbox.max_y), (60.0, 90.0));
+
+        let bbox = geocode.decode("X").unwrap();
+        assert_eq!((bbox.min_x, bbox.max_x), (120.0, 180.0));
+        assert_eq!((bbox.min_y, bbox.max_y), (-90.0, -60.0));
+
+        // characters are case sensitive
+        assert_ne!(geocode.decode("b").unwrap(),
+            geocode.decode("B").unwrap());
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("bdc"),
+            Err(GeocodeError::InvalidCharacter { ch: 'c', index: 2 }));
+    }
+
+    #[test]
+    fn encode() {
+        // reference test vector
+        let geocode = Geocode::Geohash36;
+        assert_eq!(geocode.encode(-0.086666, 51.504444, 10).unwrap(),
+            "bdrdC26BqH");
+
+        // points on cell edges belong to the east and north cells
+        assert_eq!(geocode.encode(-180.0, -90.0, 1).unwrap(), "R");
+        assert_eq!(geocode.encode(-120.0, 60.0, 1).unwrap(), "3");
+        assert_eq!(geocode.encode(0.0, 0.0, 2).unwrap(), "GR");
+        assert_eq!(geocode.encode(-180.0, -90.0, 2).unwrap(), "RR");
+        assert_eq!(geocode.encode(180.0, 90.0, 2).unwrap(), "77");
+
+        assert_eq!(geocode.encode(0.0, 0.0, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+        assert!(geocode.encode(181.0, 0.0, 1).is_err());
+    }
+
+    #[test]
+    fn hierarchy() {
+        let geocode = Geocode::Geohash36;
+        assert_eq!(geocode.parent("bdrdC").unwrap(), "bdrd");
+        assert_eq!(geocode.children("bdrd").unwrap().count(), 36);
+
+        let cells: Vec<String> = geocode.children("bdrd").unwrap().collect();
+        assert_eq!(geocode.compact(cells).unwrap(), vec!["bdrd"]);
+    }
+
+    #[test]
+    fn intervals() {
+        let geocode = Geocode::Geohash36;
+        assert_eq!(geocode.get_intervals(1), (60.0, 30.0));
+        assert_eq!(geocode.get_intervals(2), (10.0, 5.0));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{BoundingVolume, GeocodeError, GEOHASH_BOUNDS, GEOHASH32_CHARS};
+use crate::volume::{decode_volume, encode_volume};
+
+use alloc::string::{String, ToString};
+
+// geohash characters interleaving longitude, latitude, and altitude bits
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geohash3d {
+    bounds: BoundingVolume,
+}
+
+impl Geohash3d {
+    pub fn new(min_altitude: f64, max_altitude: f64)
+            -> Result<Geohash3d, GeocodeError> {
+        // check if altitude bounds are valid
+        if min_altitude >= max_altitude || !min_altitude.is_finite()
+                || !max_altitude.is_finite() {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "invalid altitude bounds".to_string() });
+        }
+
+        Ok(Geohash3d { bounds: BoundingVolume::new(GEOHASH_BOUNDS.min_x,
+            GEOHASH_BOUNDS.max_x, GEOHASH_BOUNDS.min_y, GEOHASH_BOUNDS.max_y,
+            min_altitude, max_altitude) })
+    }
+
+    pub fn decode(&self, value: &str)
+            -> Result<BoundingVolume, GeocodeError> {
+        decode_volume(&self.bounds, 5, GEOHASH32_CHARS, value)
+    }
+
+    pub fn encode(&self, lon: f64, lat: f64, altitude: f64,
+            precision: usize) -> Result<String, GeocodeError> {
+        encode_volume(&self.bounds, [lon, lat, altitude], precision, 5,
+            GEOHASH32_CHARS)
+    }
+
+    pub fn get_bounds(&self) -> BoundingVolume {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingVolume, GeocodeError};
+    use super::Geohash3d;
+
+    #[test]
+    fn decode() {
+        let geocode = Geohash3d::new(0.0, 10000.0).unwrap();
+
+        // the first character splits longitude, latitude, and altitude
+        // before a second longitude and latitude split
+        assert_eq!(geocode.decode("0").unwrap(), BoundingVolume::new(
+            -180.0, -90.0, -90.0, -45.0, 0.0, 5000.0));
+        assert_eq!(geocode.decode("z").unwrap(), BoundingVolume::new(
+            90.0, 180.0, 45.0, 90.0, 5000.0, 10000.0));
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("dpa"), Err(GeocodeError::InvalidCharacter {
+            ch: 'a', index: 2 }));
+    }
+
+    #[test]
+    fn encode() {
+        let geocode = Geohash3d::new(-500.0, 20000.0).unwrap();
+        let code = geocode.encode(-88.4, 44.266667, 1200.0, 9).unwrap();
+        assert_eq!(code.len(), 9);
+
+        let volume = geocode.decode(&code).unwrap();
+        assert!(volume.contains(-88.4, 44.266667, 1200.0));
+        assert!(volume.depth() < 1.0);
+
+        // nearby altitudes share a prefix, distant altitudes diverge
+        let above = geocode.encode(-88.4, 44.266667, 1250.0, 9).unwrap();
+        let high = geocode.encode(-88.4, 44.266667, 15000.0, 9).unwrap();
+        assert_eq!(code[..5], above[..5]);
+        assert_ne!(code[..1], high[..1]);
+
+        assert_eq!(geocode.get_bounds().min_z, -500.0);
+        assert!(matches!(geocode.encode(-88.4, 44.266667, 25000.0, 4),
+            Err(GeocodeError::CoordinateOutOfVolume { .. })));
+        assert!(geocode.encode(-88.4, 44.266667, 1200.0, 0).is_err());
+        assert!(Geohash3d::new(100.0, 100.0).is_err());
+        assert!(Geohash3d::new(f64::NAN, 100.0).is_err());
+    }
+}
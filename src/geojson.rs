@@ -0,0 +1,104 @@
+use crate::{Binner, CellSet, Geocode, GeocodeError};
+
+impl Geocode {
+    pub(crate) fn decode_ring(&self, value: &str)
+            -> Result<Vec<(f64, f64)>, GeocodeError> {
+        // closed counter-clockwise ring of wgs84 cell corners
+        let bbox = self.decode_lonlat(value)?;
+        Ok(vec![(bbox.min_x, bbox.min_y), (bbox.max_x, bbox.min_y),
+            (bbox.max_x, bbox.max_y), (bbox.min_x, bbox.max_y),
+            (bbox.min_x, bbox.min_y)])
+    }
+
+    pub fn to_geojson(&self, value: &str) -> Result<String, GeocodeError> {
+        let ring = self.decode_ring(value)?;
+        Ok(format!("{{\"type\":\"Polygon\",\"coordinates\":[{}]}}",
+            format_ring(&ring)))
+    }
+}
+
+impl Binner {
+    pub fn to_geojson(&self) -> Result<String, GeocodeError> {
+        // each bin is a feature labeled with its code and aggregates
+        let mut features = Vec::new();
+        for (cell, aggregate) in self.iter() {
+            features.push(format!("{{\"type\":\"Feature\",\
+                \"properties\":{{\"code\":\"{}\",\"count\":{},\
+                \"sum\":{},\"min\":{},\"max\":{},\"mean\":{}}},\
+                \"geometry\":{}}}", cell, aggregate.count, aggregate.sum,
+                aggregate.min, aggregate.max, aggregate.mean(),
+                self.get_geocode().to_geojson(&cell)?));
+        }
+
+        Ok(format!("{{\"type\":\"FeatureCollection\",\"features\":[{}]}}",
+            features.join(",")))
+    }
+}
+
+impl CellSet {
+    pub fn to_geojson(&self) -> Result<String, GeocodeError> {
+        // each cell is a feature labeled with its code and precision
+        let mut features = Vec::new();
+        for cell in self.iter() {
+            features.push(format!("{{\"type\":\"Feature\",\
+                \"properties\":{{\"code\":\"{}\",\"precision\":{}}},\
+                \"geometry\":{}}}", cell,
+                self.get_geocode().get_precision(&cell)?,
+                self.get_geocode().to_geojson(&cell)?));
+        }
+
+        Ok(format!("{{\"type\":\"FeatureCollection\",\"features\":[{}]}}",
+            features.join(",")))
+    }
+}
+
+fn format_ring(ring: &[(f64, f64)]) -> String {
+    let points: Vec<String> = ring.iter()
+        .map(|(x, y)| format!("[{},{}]", x, y)).collect();
+    format!("[{}]", points.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Binner, CellSet, Geocode, GeocodeError};
+
+    #[test]
+    fn to_geojson() {
+        let geocode = Geocode::QuadTile;
+        let bbox = geocode.decode_lonlat("0").unwrap();
+        assert_eq!(geocode.to_geojson("0").unwrap(), format!(
+            "{{\"type\":\"Polygon\",\"coordinates\":[[[{0},{2}],[{1},{2}],\
+            [{1},{3}],[{0},{3}],[{0},{2}]]]}}", bbox.min_x, bbox.max_x,
+            bbox.min_y, bbox.max_y));
+        assert_eq!(geocode.to_geojson(""), Err(GeocodeError::EmptyCode));
+
+        let cell_set = CellSet::from_cells(Geocode::Geohash,
+            vec!["dpc5u6".to_string(), "9xjq".to_string()]).unwrap();
+        let geojson = cell_set.to_geojson().unwrap();
+        assert!(geojson.starts_with("{\"type\":\"FeatureCollection\",\
+            \"features\":[{\"type\":\"Feature\",\"properties\":\
+            {\"code\":\"9xjq\",\"precision\":4},\"geometry\":"));
+        assert_eq!(geojson.matches("\"type\":\"Polygon\"").count(), 2);
+        assert!(geojson.contains(&Geocode::Geohash.to_geojson("dpc5u6")
+            .unwrap()));
+
+        assert_eq!(CellSet::new(Geocode::Geohash).to_geojson().unwrap(),
+            "{\"type\":\"FeatureCollection\",\"features\":[]}");
+    }
+
+    #[test]
+    fn binner_to_geojson() {
+        let mut binner = Binner::new(Geocode::Geohash, 4).unwrap();
+        binner.insert(-88.4, 44.26, 1.0).unwrap();
+        binner.insert(-88.39, 44.27, 2.0).unwrap();
+        assert_eq!(binner.to_geojson().unwrap(), format!(
+            "{{\"type\":\"FeatureCollection\",\"features\":[{{\
+            \"type\":\"Feature\",\"properties\":{{\"code\":\"dpc5\",\
+            \"count\":2,\"sum\":3,\"min\":1,\"max\":2,\"mean\":1.5}},\
+            \"geometry\":{}}}]}}",
+            Geocode::Geohash.to_geojson("dpc5").unwrap()));
+
+        assert_eq!(Binner::new(Geocode::Geohash, 4).unwrap().to_geojson()
+            .unwrap(), "{\"type\":\"FeatureCollection\",\"features\":[]}");
+    }
+}
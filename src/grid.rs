@@ -0,0 +1,429 @@
+use crate::{decode_bits, geohash36, maidenhead, math, pluscode, BoundingBox,
+    Geocode, GeocodeError, GEOHASH_BOUNDS, GEOHASH16_CHARS, GEOHASH32_CHARS,
+    QUADTILE_BOUNDS, QUADTILE_CHARS};
+#[cfg(feature = "alloc")]
+use crate::encode_bits;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+// characters of bisection grids encode at most 64 values
+const MAX_CHAR_BITS: usize = 6;
+
+pub trait GridSystem {
+    // characters valid at the given index of a code
+    fn get_alphabet(&self, index: usize) -> &[char];
+
+    // bits encoded by each character of bisection grids, where grids
+    // without one provide their own encoding and decoding
+    fn get_bits_per_char(&self) -> Option<usize>;
+
+    fn get_bounds(&self) -> BoundingBox;
+
+    fn get_epsg_code(&self) -> u32;
+
+    fn decode(&self, value: &str) -> Result<BoundingBox, GeocodeError> {
+        decode_grid(self, value)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn encode(&self, x: f64, y: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        encode_grid(self, x, y, precision)
+    }
+
+    fn get_intervals(&self, precision: usize) -> (f64, f64) {
+        // the first bit splits on x, so x holds the extra bit when the
+        // total number of bits is odd
+        let bits = precision * self.get_bits_per_char().unwrap_or(0);
+        let bbox = self.get_bounds();
+        (bbox.width() / math::powi(2f64, (bits - bits / 2) as i32),
+            bbox.height() / math::powi(2f64, (bits / 2) as i32))
+    }
+}
+
+// built-in geocodes are grid systems, where bisection geocodes share the
+// default encoding and the others provide their own
+impl GridSystem for Geocode {
+    fn get_alphabet(&self, index: usize) -> &[char] {
+        match self {
+            Geocode::Geohash => GEOHASH32_CHARS,
+            Geocode::Geohash16 => GEOHASH16_CHARS,
+            Geocode::Geohash36 => geohash36::GEOHASH36_CHARS,
+            Geocode::Maidenhead => maidenhead::get_alphabet(index),
+            Geocode::PlusCode => pluscode::PLUSCODE_CHARS,
+            Geocode::QuadTile => QUADTILE_CHARS,
+        }
+    }
+
+    fn get_bits_per_char(&self) -> Option<usize> {
+        self.get_parameters().map(|x| x.0)
+    }
+
+    fn get_bounds(&self) -> BoundingBox {
+        Geocode::get_bounds(self)
+    }
+
+    fn get_epsg_code(&self) -> u32 {
+        match self {
+            Geocode::Geohash => 4326,
+            Geocode::Geohash16 => 4326,
+            Geocode::Geohash36 => 4326,
+            Geocode::Maidenhead => 4326,
+            Geocode::PlusCode => 4326,
+            Geocode::QuadTile => 3857,
+        }
+    }
+
+    fn decode(&self, value: &str) -> Result<BoundingBox, GeocodeError> {
+        match self {
+            Geocode::Geohash36 => geohash36::decode(value),
+            #[cfg(feature = "alloc")]
+            Geocode::Maidenhead => maidenhead::parse(value)
+                .map(|x| maidenhead::decode(&x)),
+            #[cfg(feature = "alloc")]
+            Geocode::PlusCode => pluscode::parse(value)
+                .map(|x| pluscode::decode(&x)),
+            // codes are normalized into owned keys before decoding
+            #[cfg(not(feature = "alloc"))]
+            Geocode::Maidenhead | Geocode::PlusCode =>
+                Err(GeocodeError::UnsupportedGeocode { geocode: *self }),
+            _ => decode_grid(self, value),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn encode(&self, x: f64, y: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        let mut out = String::with_capacity(precision);
+        match self {
+            Geocode::Geohash36 =>
+                geohash36::encode(x, y, precision, &mut out)?,
+            Geocode::Maidenhead =>
+                maidenhead::encode(x, y, precision, &mut out)?,
+            Geocode::PlusCode => pluscode::encode(x, y, precision, &mut out)?,
+            _ => return encode_grid(self, x, y, precision),
+        }
+
+        Ok(out)
+    }
+
+    fn get_intervals(&self, precision: usize) -> (f64, f64) {
+        match self {
+            Geocode::Geohash => {
+                // calculate number of bits for latitude and longitude
+                let lat_bits = (2 * precision) as f64
+                    + math::floor(precision as f64 / 2.0);
+                let long_bits = (2 * precision) as f64
+                    + math::ceil(precision as f64 / 2.0);
+
+                // calculate deltas
+                let lat_delta = GEOHASH_BOUNDS.height() /
+                    math::powi(2f64, lat_bits as i32);
+                let long_delta = GEOHASH_BOUNDS.width() /
+                    math::powi(2f64, long_bits as i32);
+
+                (long_delta, lat_delta)
+            },
+            Geocode::Geohash16 => {
+                // calculate deltas
+                let lat_delta = GEOHASH_BOUNDS.height() /
+                    math::powi(2f64, 2 * precision as i32);
+                let long_delta = GEOHASH_BOUNDS.width() /
+                    math::powi(2f64, 2 * precision as i32);
+
+                (long_delta, lat_delta)
+            },
+            Geocode::Geohash36 => geohash36::get_intervals(precision),
+            Geocode::Maidenhead => maidenhead::get_intervals(precision),
+            Geocode::PlusCode => pluscode::get_intervals(precision),
+            Geocode::QuadTile => {
+                // calculate delta
+                let delta = QUADTILE_BOUNDS.width() /
+                    math::powi(2f64, precision as i32);
+
+                (delta, delta)
+            },
+        }
+    }
+}
+
+// bounds and bisection parameters are const so codes may be computed in
+// const contexts
+impl Geocode {
+    pub const fn get_bounds(&self) -> BoundingBox {
+        match self {
+            Geocode::Geohash => GEOHASH_BOUNDS,
+            Geocode::Geohash16 => GEOHASH_BOUNDS,
+            Geocode::Geohash36 => GEOHASH_BOUNDS,
+            Geocode::Maidenhead => GEOHASH_BOUNDS,
+            Geocode::PlusCode => GEOHASH_BOUNDS,
+            Geocode::QuadTile => QUADTILE_BOUNDS,
+        }
+    }
+
+    pub(crate) const fn get_parameters(&self)
+            -> Option<(usize, &'static [char])> {
+        match self {
+            Geocode::Geohash => Some((5, GEOHASH32_CHARS)),
+            Geocode::Geohash16 => Some((4, GEOHASH16_CHARS)),
+            Geocode::Geohash36 => None,
+            Geocode::Maidenhead => None,
+            Geocode::PlusCode => None,
+            Geocode::QuadTile => Some((2, QUADTILE_CHARS)),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub struct GridRegistry {
+    // grids are stored by lowercase name
+    grids: BTreeMap<String, Box<dyn GridSystem>>,
+}
+
+#[cfg(feature = "alloc")]
+impl Default for GridRegistry {
+    fn default() -> GridRegistry {
+        // built-in geocodes are registered under their names
+        let mut registry = GridRegistry { grids: BTreeMap::new() };
+        for geocode in Geocode::ALL.iter() {
+            registry.register(geocode.as_str(), Box::new(*geocode));
+        }
+
+        registry
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl GridRegistry {
+    pub fn get(&self, name: &str) -> Option<&dyn GridSystem> {
+        self.grids.get(&name.to_ascii_lowercase()).map(|x| x.as_ref())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.grids.keys().map(|x| x.as_str())
+    }
+
+    pub fn register(&mut self, name: &str, grid: Box<dyn GridSystem>)
+            -> Option<Box<dyn GridSystem>> {
+        // a grid registered under an existing name replaces it
+        self.grids.insert(name.to_ascii_lowercase(), grid)
+    }
+
+    pub fn resolve(&self, name: &str)
+            -> Result<&dyn GridSystem, GeocodeError> {
+        self.get(name).ok_or_else(|| GeocodeError::UnknownGeocode {
+            name: name.to_string() })
+    }
+}
+
+fn decode_grid<G: GridSystem + ?Sized>(grid: &G, value: &str)
+        -> Result<BoundingBox, GeocodeError> {
+    let char_bits = get_bits_per_char(grid, value.chars().count())?;
+    decode_bits(&grid.get_bounds(), char_bits, |x| grid.get_alphabet(x),
+        value)
+}
+
+#[cfg(feature = "alloc")]
+fn encode_grid<G: GridSystem + ?Sized>(grid: &G, x: f64, y: f64,
+        precision: usize) -> Result<String, GeocodeError> {
+    let char_bits = get_bits_per_char(grid, precision)?;
+    let bbox = grid.get_bounds();
+
+    // check if coordinates and precision are valid
+    if !bbox.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: bbox });
+    } else if precision == 0 {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    let mut out = String::with_capacity(precision);
+    encode_bits(&bbox, x, y, precision, char_bits,
+        |x| grid.get_alphabet(x), &mut out)?;
+    Ok(out)
+}
+
+fn get_bits_per_char<G: GridSystem + ?Sized>(grid: &G, len: usize)
+        -> Result<usize, GeocodeError> {
+    // bisection requires between one and six bits per character, where
+    // the alphabet at each index holds a character for every value
+    match grid.get_bits_per_char() {
+        Some(char_bits @ 1..=MAX_CHAR_BITS) if (0..len)
+                .all(|x| grid.get_alphabet(x).len() == 1 << char_bits) =>
+            Ok(char_bits),
+        _ => Err(GeocodeError::UnsupportedGrid),
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::{GridRegistry, GridSystem};
+
+    use alloc::boxed::Box;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    // an in-house style grid with octal characters over a local extent
+    struct OctalGrid;
+
+    impl GridSystem for OctalGrid {
+        fn get_alphabet(&self, _: usize) -> &[char] {
+            &['0', '1', '2', '3', '4', '5', '6', '7']
+        }
+
+        fn get_bits_per_char(&self) -> Option<usize> {
+            Some(3)
+        }
+
+        fn get_bounds(&self) -> BoundingBox {
+            BoundingBox::new(0.0, 800000.0, 0.0, 400000.0)
+        }
+
+        fn get_epsg_code(&self) -> u32 {
+            27700
+        }
+    }
+
+    // a grid alternating between digit and letter characters
+    struct AlternatingGrid;
+
+    impl GridSystem for AlternatingGrid {
+        fn get_alphabet(&self, index: usize) -> &[char] {
+            match index % 2 {
+                0 => &['0', '1', '2', '3'],
+                _ => &['a', 'b', 'c', 'd'],
+            }
+        }
+
+        fn get_bits_per_char(&self) -> Option<usize> {
+            Some(2)
+        }
+
+        fn get_bounds(&self) -> BoundingBox {
+            BoundingBox::new(0.0, 16.0, 0.0, 16.0)
+        }
+
+        fn get_epsg_code(&self) -> u32 {
+            0
+        }
+    }
+
+    // a grid whose bits and alphabet may not describe a valid bisection
+    struct InvalidGrid {
+        alphabet: &'static [char],
+        bits: usize,
+    }
+
+    impl GridSystem for InvalidGrid {
+        fn get_alphabet(&self, _: usize) -> &[char] {
+            self.alphabet
+        }
+
+        fn get_bits_per_char(&self) -> Option<usize> {
+            Some(self.bits)
+        }
+
+        fn get_bounds(&self) -> BoundingBox {
+            BoundingBox::new(0.0, 16.0, 0.0, 16.0)
+        }
+
+        fn get_epsg_code(&self) -> u32 {
+            0
+        }
+    }
+
+    #[test]
+    fn alphabet_index() {
+        // each character is drawn from the alphabet at its index
+        let grid = AlternatingGrid;
+        let code = grid.encode(13.5, 2.5, 4).unwrap();
+        assert_eq!(code, "2c1c");
+        assert_eq!(grid.decode(&code).unwrap(),
+            BoundingBox::new(13.0, 14.0, 2.0, 3.0));
+
+        // codes beyond the leading 64 bits are refined by bisection
+        let code = grid.encode(13.5, 2.5, 40).unwrap();
+        assert!(code.chars().enumerate()
+            .all(|(i, c)| grid.get_alphabet(i).contains(&c)));
+        assert!(grid.decode(&code).unwrap().contains(13.5, 2.5));
+
+        assert_eq!(grid.decode("2c1c3"), Ok(BoundingBox::new(13.5, 14.0,
+            2.5, 3.0)));
+        assert_eq!(grid.decode("22"), Err(GeocodeError::InvalidCharacter {
+            ch: '2', index: 1 }));
+    }
+
+    #[test]
+    fn grid_system() {
+        let grid = OctalGrid;
+        let code = grid.encode(312345.0, 123456.0, 6).unwrap();
+        assert_eq!(code.len(), 6);
+
+        let bbox = grid.decode(&code).unwrap();
+        assert!(bbox.contains(312345.0, 123456.0));
+        assert_eq!((bbox.width(), bbox.height()), grid.get_intervals(6));
+        assert_eq!(grid.get_intervals(1), (200000.0, 200000.0));
+
+        assert!(grid.encode(-1.0, 0.0, 6).is_err());
+        assert!(grid.encode(1.0, 1.0, 0).is_err());
+        assert_eq!(grid.decode("08"), Err(GeocodeError::InvalidCharacter {
+            ch: '8', index: 1 }));
+
+        // built-in geocodes implement the trait with their own encoding
+        for geocode in Geocode::ALL.iter() {
+            let grid: &dyn GridSystem = geocode;
+            let (x, y) = geocode.get_bounds().center();
+            let code = grid.encode(x + 1.0, y + 1.0, 8).unwrap();
+            assert_eq!(code, geocode.encode(x + 1.0, y + 1.0, 8).unwrap());
+            assert_eq!(grid.decode(&code), geocode.decode(&code));
+            assert_eq!(grid.get_intervals(8), geocode.get_intervals(8));
+        }
+
+        assert_eq!(Geocode::Geohash.get_bits_per_char(), Some(5));
+        assert_eq!(Geocode::PlusCode.get_bits_per_char(), None);
+    }
+
+    #[test]
+    fn invalid_grid() {
+        // grids without between one and six bits per character, or with
+        // alphabets of another length, are rejected
+        let alphabet = &['0', '1', '2', '3'];
+        for bits in [0, 1, 7].iter() {
+            let grid = InvalidGrid { alphabet, bits: *bits };
+            assert_eq!(grid.encode(1.0, 1.0, 4),
+                Err(GeocodeError::UnsupportedGrid));
+            assert_eq!(grid.decode("0123"),
+                Err(GeocodeError::UnsupportedGrid));
+        }
+
+        let grid = InvalidGrid { alphabet, bits: 2 };
+        assert_eq!(grid.decode(&grid.encode(1.5, 1.5, 4).unwrap()),
+            Ok(BoundingBox::new(1.0, 2.0, 1.0, 2.0)));
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = GridRegistry::default();
+        assert_eq!(registry.names().count(), Geocode::ALL.len());
+        assert_eq!(registry.get("QuadTile").unwrap().get_epsg_code(), 3857);
+
+        assert!(registry.register("octal", Box::new(OctalGrid)).is_none());
+        let grid = registry.resolve("Octal").unwrap();
+        assert_eq!(grid.get_epsg_code(), 27700);
+        assert_eq!(grid.encode(0.0, 0.0, 2).unwrap(), "00");
+
+        let names: Vec<&str> = registry.names().collect();
+        assert!(names.contains(&"octal"));
+        assert!(registry.register("octal", Box::new(Geocode::Geohash))
+            .is_some());
+        assert_eq!(registry.resolve("unknown").err(),
+            Some(GeocodeError::UnknownGeocode {
+                name: "unknown".to_string() }));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{BoundingBox, GeocodeError, GEOHASH_BOUNDS};
+
+use h3o::{CellIndex, LatLng, Resolution};
+
+use std::convert::TryFrom;
+
+pub fn decode(value: &str) -> Result<BoundingBox, GeocodeError> {
+    let boundary = decode_boundary(value)?;
+
+    // compute bounding box of the cell boundary
+    let mut bbox = BoundingBox::new(f64::INFINITY,
+        f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for (x, y) in boundary.iter() {
+        bbox.min_x = bbox.min_x.min(*x);
+        bbox.max_x = bbox.max_x.max(*x);
+        bbox.min_y = bbox.min_y.min(*y);
+        bbox.max_y = bbox.max_y.max(*y);
+    }
+
+    Ok(bbox)
+}
+
+pub fn decode_boundary(value: &str)
+        -> Result<Vec<(f64, f64)>, GeocodeError> {
+    let cell = parse(value)?;
+    Ok(cell.boundary().iter().map(|x| (x.lng(), x.lat())).collect())
+}
+
+pub fn decode_center(value: &str) -> Result<(f64, f64), GeocodeError> {
+    let center = LatLng::from(parse(value)?);
+    Ok((center.lng(), center.lat()))
+}
+
+pub fn encode(x: f64, y: f64, resolution: usize)
+        -> Result<String, GeocodeError> {
+    // check if coordinates and resolution are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    }
+
+    let resolution = get_resolution(resolution)?;
+    let coordinate = LatLng::new(y, x).map_err(|_|
+        GeocodeError::CoordinateOutOfRange { x, y, bounds: GEOHASH_BOUNDS })?;
+
+    Ok(coordinate.to_cell(resolution).to_string())
+}
+
+pub fn get_intervals(resolution: usize) -> Result<f64, GeocodeError> {
+    // h3 cells are hexagons, report the average edge length in meters
+    Ok(get_resolution(resolution)?.edge_length_m())
+}
+
+fn get_resolution(resolution: usize) -> Result<Resolution, GeocodeError> {
+    u8::try_from(resolution).ok()
+        .and_then(|x| Resolution::try_from(x).ok())
+        .ok_or(GeocodeError::InvalidPrecision { precision: resolution })
+}
+
+fn parse(value: &str) -> Result<CellIndex, GeocodeError> {
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    }
+
+    for (index, ch) in value.chars().enumerate() {
+        if !ch.is_ascii_hexdigit() {
+            return Err(GeocodeError::InvalidCharacter { ch, index });
+        }
+    }
+
+    let id = u64::from_str_radix(value, 16).map_err(|_|
+        GeocodeError::InvalidPrecision { precision: value.len() })?;
+    CellIndex::try_from(id).map_err(|_| GeocodeError::InvalidCellId { id })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GeocodeError;
+
+    #[test]
+    fn decode() {
+        let (x, y) = super::decode_center("8928308280fffff").unwrap();
+        assert!((x - -122.41795063018799).abs() < 0.002);
+        assert!((y - 37.775938728915946).abs() < 0.002);
+        assert_eq!(super::encode(x, y, 9).unwrap(), "8928308280fffff");
+
+        let boundary = super::decode_boundary("8928308280fffff").unwrap();
+        assert_eq!(boundary.len(), 6);
+
+        let bbox = super::decode("8928308280fffff").unwrap();
+        assert!(bbox.contains(x, y));
+        assert!(bbox.width() < 0.01 && bbox.height() < 0.01);
+
+        assert_eq!(super::decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(super::decode("8928308280fffgf"),
+            Err(GeocodeError::InvalidCharacter { ch: 'g', index: 13 }));
+        assert_eq!(super::decode("ffffffffffffffff"),
+            Err(GeocodeError::InvalidCellId { id: u64::MAX }));
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(super::encode(-122.41795063018799,
+            37.775938728915946, 9).unwrap(), "8928308280fffff");
+        assert_eq!(super::encode(0.0, 0.0, 16),
+            Err(GeocodeError::InvalidPrecision { precision: 16 }));
+        assert!(super::encode(0.0, 91.0, 9).is_err());
+
+        // check encoded cells contain the coordinate
+        let code = super::encode(-88.4, 44.266667, 7).unwrap();
+        assert!(super::decode(&code).unwrap().contains(-88.4, 44.266667));
+    }
+}
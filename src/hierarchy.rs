@@ -0,0 +1,291 @@
+use crate::{Geocode, GeocodeError};
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+impl Geocode {
+    pub(crate) fn child_keys(&self, key: &str) -> Option<Vec<String>> {
+        // append every combination of characters required to reach the
+        // next precision
+        let precision = key.chars().count();
+        let child_precision = self.get_child_precision(precision)?;
+        Some(self.extend_key(key, child_precision - precision))
+    }
+
+    pub fn children(&self, value: &str)
+            -> Result<impl Iterator<Item = String>, GeocodeError> {
+        // check if code is valid
+        let key = self.parse_key(value)?;
+
+        let geocode = *self;
+        let children = self.child_keys(&key).ok_or(
+            GeocodeError::InvalidPrecision { precision: key.len() + 1 })?;
+        Ok(children.into_iter().map(move |x| geocode.format_key(&x)))
+    }
+
+    pub fn common_ancestor(&self, a: &str, b: &str)
+            -> Result<Option<(String, usize)>, GeocodeError> {
+        // check if codes are valid
+        let (key_a, key_b) = (self.parse_key(a)?, self.parse_key(b)?);
+
+        // walk up from the first cell until it contains the second
+        let mut precision = Some(key_a.chars().count());
+        while let Some(value) = precision {
+            let key: String = key_a.chars().take(value).collect();
+            if key_b.starts_with(&key) {
+                return Ok(Some((self.format_key(&key), value)));
+            }
+
+            precision = self.get_parent_precision(value);
+        }
+
+        Ok(None)
+    }
+
+    pub fn compact(&self, cells: Vec<String>)
+            -> Result<Vec<String>, GeocodeError> {
+        // check if codes are valid
+        let mut keys = Vec::new();
+        for cell in cells.iter() {
+            keys.push(self.parse_key(cell)?);
+        }
+
+        Ok(self.compact_keys(keys).iter()
+            .map(|x| self.format_key(x)).collect())
+    }
+
+    pub(crate) fn compact_keys(&self, keys: Vec<String>) -> Vec<String> {
+        // remove keys contained within other keys
+        let mut compacted = BTreeSet::new();
+        let mut ancestor: Option<String> = None;
+        for key in keys.into_iter().collect::<BTreeSet<String>>() {
+            match ancestor {
+                Some(ref ancestor) if key.starts_with(ancestor.as_str()) => {},
+                _ => {
+                    ancestor = Some(key.clone());
+                    compacted.insert(key);
+                },
+            }
+        }
+
+        // merge complete sibling groups into their parent from the
+        // finest precision upward
+        let mut precision = compacted.iter()
+            .map(|x| x.chars().count()).max().unwrap_or(0);
+        while let Some(parent_precision) =
+                self.get_parent_precision(precision) {
+            let group_size: usize = (parent_precision..precision)
+                .map(|x| self.get_alphabet(x).len()).product();
+
+            let mut groups = BTreeMap::new();
+            for key in compacted.iter()
+                    .filter(|x| x.chars().count() == precision) {
+                let parent: String =
+                    key.chars().take(parent_precision).collect();
+                groups.entry(parent).or_insert_with(Vec::new)
+                    .push(key.clone());
+            }
+
+            for (parent, children) in groups {
+                if children.len() == group_size {
+                    for child in children.iter() {
+                        compacted.remove(child);
+                    }
+
+                    compacted.insert(parent);
+                }
+            }
+
+            precision = parent_precision;
+        }
+
+        compacted.into_iter().collect()
+    }
+
+    fn extend_key(&self, key: &str, length: usize) -> Vec<String> {
+        let mut keys = vec![key.to_string()];
+        let precision = key.chars().count();
+        for index in precision..precision + length {
+            let codes = self.get_alphabet(index);
+            keys = keys.iter().flat_map(|x| codes.iter()
+                .map(move |c| format!("{}{}", x, c))).collect();
+        }
+
+        keys
+    }
+
+    pub fn parent(&self, value: &str) -> Result<String, GeocodeError> {
+        // check if code is valid
+        let key = self.parse_key(value)?;
+
+        match self.get_parent_precision(key.chars().count()) {
+            Some(precision) => Ok(self.format_key(
+                &key.chars().take(precision).collect::<String>())),
+            None => Err(GeocodeError::InvalidPrecision { precision: 0 }),
+        }
+    }
+
+    pub fn siblings(&self, value: &str)
+            -> Result<Vec<String>, GeocodeError> {
+        // check if code is valid
+        let key = self.parse_key(value)?;
+
+        // compute all cells sharing the same parent
+        let precision = key.chars().count();
+        let parent_precision =
+            self.get_parent_precision(precision).unwrap_or(0);
+        let parent: String = key.chars().take(parent_precision).collect();
+
+        // top level cells may not span the entire alphabet
+        Ok(self.extend_key(&parent, precision - parent_precision)
+            .into_iter()
+            .filter(|x| *x != key)
+            .map(|x| self.format_key(&x))
+            .filter(|x| self.parse_key(x).is_ok())
+            .collect())
+    }
+
+    pub fn uncompact(&self, cells: Vec<String>, precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        let mut uncompacted = BTreeSet::new();
+        let mut keys = Vec::new();
+        for cell in cells.iter() {
+            // check if code is valid
+            keys.push(self.parse_key(cell)?);
+        }
+
+        while let Some(key) = keys.pop() {
+            let key_precision = key.chars().count();
+            if key_precision > precision {
+                return Err(GeocodeError::InvalidPrecision {
+                    precision: key_precision });
+            } else if key_precision == precision {
+                uncompacted.insert(key);
+            } else {
+                match self.child_keys(&key) {
+                    Some(children) => keys.extend(children),
+                    None => return Err(
+                        GeocodeError::InvalidPrecision { precision }),
+                }
+            }
+        }
+
+        Ok(uncompacted.iter().map(|x| self.format_key(x)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn children() {
+        let geocode = Geocode::QuadTile;
+        let children: Vec<String> = geocode.children("03").unwrap().collect();
+        assert_eq!(children, vec!["032", "030", "033", "031"]);
+
+        let geocode = Geocode::Geohash;
+        let bbox = geocode.decode("dpc5").unwrap();
+        let children: Vec<String> =
+            geocode.children("dpc5").unwrap().collect();
+        assert_eq!(children.len(), 32);
+
+        // check children lie within the parent cell
+        for child in children.iter() {
+            let (x, y) = geocode.decode(child).unwrap().center();
+            assert!(bbox.contains(x, y));
+        }
+
+        assert!(geocode.children("dpca").is_err());
+    }
+
+    #[test]
+    fn parent() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.parent("dpc5u6").unwrap(), "dpc5u");
+        assert_eq!(geocode.parent("d").unwrap_err(),
+            GeocodeError::InvalidPrecision { precision: 0 });
+        assert_eq!(geocode.parent("").unwrap_err(), GeocodeError::EmptyCode);
+
+        let geocode = Geocode::Geohash16;
+        assert_eq!(geocode.parent("65565d").unwrap(), "65565");
+    }
+
+    #[test]
+    fn siblings() {
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.siblings("030").unwrap(),
+            vec!["032", "033", "031"]);
+        assert_eq!(geocode.siblings("1").unwrap(), vec!["2", "0", "3"]);
+
+        let geocode = Geocode::Geohash16;
+        let siblings = geocode.siblings("65565d").unwrap();
+        assert_eq!(siblings.len(), 15);
+        assert!(siblings.iter().all(|x| x.starts_with("65565")));
+        assert!(!siblings.contains(&"65565d".to_string()));
+    }
+
+    #[test]
+    fn common_ancestor() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.common_ancestor("dpc5u6", "dpc5vz").unwrap(),
+            Some(("dpc5".to_string(), 4)));
+        assert_eq!(geocode.common_ancestor("dpc", "dpc5u6").unwrap(),
+            Some(("dpc".to_string(), 3)));
+        assert_eq!(geocode.common_ancestor("dpc5u6", "dpc5u6").unwrap(),
+            Some(("dpc5u6".to_string(), 6)));
+        assert_eq!(geocode.common_ancestor("dpc5", "9xjq").unwrap(), None);
+        assert!(geocode.common_ancestor("dpc5", "").is_err());
+
+        // ancestors are limited to valid precisions
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.common_ancestor("86HJ7JR9+CV", "86HJ7X00+")
+            .unwrap(), Some(("86HJ0000+".to_string(), 4)));
+        let geocode = Geocode::Maidenhead;
+        assert_eq!(geocode.common_ancestor("EN54cq", "EN55cq").unwrap(),
+            Some(("EN".to_string(), 2)));
+    }
+
+    #[test]
+    fn compact() {
+        let geocode = Geocode::QuadTile;
+        let cells = vec!["030", "031", "032", "033", "0201", "02", "1",
+            "2100", "2101", "2102", "2103", "211", "212", "213", "0201"]
+            .into_iter().map(|x| x.to_string()).collect();
+        assert_eq!(geocode.compact(cells).unwrap(),
+            vec!["02", "03", "1", "21"]);
+
+        let geocode = Geocode::Geohash;
+        let cells: Vec<String> = geocode.children("dpc5").unwrap().collect();
+        assert_eq!(geocode.compact(cells).unwrap(), vec!["dpc5"]);
+
+        let mut cells: Vec<String> = geocode.children("dpc5").unwrap()
+            .collect();
+        cells.pop();
+        assert_eq!(geocode.compact(cells).unwrap().len(), 31);
+
+        assert!(geocode.compact(vec!["dpca".to_string()]).is_err());
+        assert_eq!(geocode.compact(Vec::new()).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn uncompact() {
+        let geocode = Geocode::QuadTile;
+        let cells = vec!["02".to_string(), "0311".to_string()];
+        assert_eq!(geocode.uncompact(cells.clone(), 4).unwrap().len(), 17);
+        assert_eq!(geocode.uncompact(cells.clone(), 3),
+            Err(GeocodeError::InvalidPrecision { precision: 4 }));
+
+        // check uncompact reverses compact
+        let geocode = Geocode::Geohash16;
+        let cells = geocode.uncompact(vec!["65".to_string(),
+            "6f3".to_string()], 4).unwrap();
+        assert_eq!(cells.len(), 256 + 16);
+        assert_eq!(geocode.uncompact(geocode.compact(cells.clone())
+            .unwrap(), 4).unwrap(), cells);
+        assert_eq!(geocode.compact(cells).unwrap(), vec!["65", "6f3"]);
+    }
+}
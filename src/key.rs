@@ -0,0 +1,374 @@
+use crate::{Geocode, GeocodeError};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum KeyField {
+    Bytes { width: usize },
+    Code { precision: usize },
+    Salt { buckets: u8 },
+    Tenant,
+    Timestamp { reversed: bool },
+}
+
+impl KeyField {
+    fn get_name(&self) -> &'static str {
+        match self {
+            KeyField::Bytes { .. } => "bytes",
+            KeyField::Code { .. } => "code",
+            KeyField::Salt { .. } => "salt",
+            KeyField::Tenant => "tenant",
+            KeyField::Timestamp { .. } => "timestamp",
+        }
+    }
+
+    fn get_width(&self) -> usize {
+        match self {
+            KeyField::Bytes { width } => *width,
+            KeyField::Code { precision } => *precision,
+            KeyField::Salt { .. } => 1,
+            KeyField::Tenant | KeyField::Timestamp { .. } => 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyValue {
+    Bytes(Vec<u8>),
+    Code(String),
+    Tenant(u64),
+    Timestamp(i64),
+}
+
+// a row key layout of fixed-width fields, where salts are computed from
+// the remaining fields rather than provided as values
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyBuilder {
+    geocode: Geocode,
+    fields: Vec<KeyField>,
+}
+
+impl KeyBuilder {
+    pub fn new(geocode: Geocode) -> KeyBuilder {
+        KeyBuilder { geocode, fields: Vec::new() }
+    }
+
+    pub fn bytes(mut self, width: usize) -> KeyBuilder {
+        self.fields.push(KeyField::Bytes { width });
+        self
+    }
+
+    pub fn code(mut self, precision: usize) -> KeyBuilder {
+        self.fields.push(KeyField::Code { precision });
+        self
+    }
+
+    pub fn decode(&self, key: &[u8]) -> Result<Vec<KeyValue>, GeocodeError> {
+        // check if key matches the layout width
+        if key.len() != self.get_width() {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "key of {} bytes does not match layout of {} bytes",
+                key.len(), self.get_width()) });
+        }
+
+        let mut values = Vec::with_capacity(self.fields.len());
+        let mut offset = 0;
+        for field in self.fields.iter() {
+            let bytes = &key[offset..offset + field.get_width()];
+            offset += bytes.len();
+
+            match field {
+                KeyField::Bytes { .. } =>
+                    values.push(KeyValue::Bytes(bytes.to_vec())),
+                KeyField::Code { .. } => {
+                    let value = core::str::from_utf8(bytes).map_err(|_|
+                        GeocodeError::InvalidFormat {
+                            reason: "code is not valid utf-8".to_string() })?;
+                    let code = self.geocode.format_key(value);
+                    self.geocode.parse_key(&code)?;
+                    values.push(KeyValue::Code(code));
+                },
+                KeyField::Salt { .. } => {},
+                KeyField::Tenant => values.push(KeyValue::Tenant(
+                    u64::from_be_bytes(get_u64_bytes(bytes)))),
+                KeyField::Timestamp { reversed } => {
+                    let value = u64::from_be_bytes(get_u64_bytes(bytes));
+                    let value = if *reversed { !value } else { value };
+                    values.push(KeyValue::Timestamp(
+                        (value ^ (1 << 63)) as i64));
+                },
+            }
+        }
+
+        // salts must match those computed from the decoded values
+        if self.encode(&values)? != key {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "key salt does not match its fields".to_string() });
+        }
+
+        Ok(values)
+    }
+
+    pub fn encode(&self, values: &[KeyValue])
+            -> Result<Vec<u8>, GeocodeError> {
+        let value_count = self.fields.iter()
+            .filter(|x| !matches!(x, KeyField::Salt { .. })).count();
+        if values.len() != value_count {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "expected {} key values, found {}",
+                value_count, values.len()) });
+        }
+
+        let mut key = self.encode_fields(values, false)?;
+        self.fill_salts(&mut key);
+        Ok(key)
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.fields.iter().map(|x| x.get_width()).sum()
+    }
+
+    pub fn prefixes(&self, values: &[KeyValue])
+            -> Result<Vec<Vec<u8>>, GeocodeError> {
+        // scans over leading values start with every bucket of each salt
+        // and may end with a partial code to match its descendants
+        let prefix = self.encode_fields(values, true)?;
+        let mut prefixes = vec![Vec::new()];
+        let mut offset = 0;
+        for field in self.fields.iter() {
+            if offset >= prefix.len() {
+                break;
+            }
+
+            match field {
+                KeyField::Salt { buckets } => {
+                    prefixes = prefixes.into_iter().flat_map(|x|
+                        (0..*buckets).map(move |salt| {
+                            let mut bytes = x.clone();
+                            bytes.push(salt);
+                            bytes
+                        })).collect();
+                },
+                _ => {
+                    let end = (offset + field.get_width()).min(prefix.len());
+                    for bytes in prefixes.iter_mut() {
+                        bytes.extend_from_slice(&prefix[offset..end]);
+                    }
+                },
+            }
+
+            offset += field.get_width();
+        }
+
+        Ok(prefixes)
+    }
+
+    pub fn reversed_timestamp(mut self) -> KeyBuilder {
+        self.fields.push(KeyField::Timestamp { reversed: true });
+        self
+    }
+
+    pub fn salt(mut self, buckets: u8) -> KeyBuilder {
+        // a single bucket leaves keys unsalted
+        self.fields.push(KeyField::Salt { buckets: buckets.max(1) });
+        self
+    }
+
+    pub fn tenant(mut self) -> KeyBuilder {
+        self.fields.push(KeyField::Tenant);
+        self
+    }
+
+    pub fn timestamp(mut self) -> KeyBuilder {
+        self.fields.push(KeyField::Timestamp { reversed: false });
+        self
+    }
+
+    fn encode_fields(&self, values: &[KeyValue], partial: bool)
+            -> Result<Vec<u8>, GeocodeError> {
+        // salt bytes are left zeroed to be filled once the key is complete
+        let mut key = Vec::with_capacity(self.get_width());
+        let mut values = values.iter().peekable();
+        for field in self.fields.iter() {
+            if let KeyField::Salt { .. } = field {
+                if partial && values.peek().is_none() {
+                    break;
+                }
+
+                key.push(0);
+                continue;
+            }
+
+            let value = match values.next() {
+                Some(value) => value,
+                None => break,
+            };
+
+            match (field, value) {
+                (KeyField::Bytes { width }, KeyValue::Bytes(bytes))
+                        if bytes.len() == *width =>
+                    key.extend_from_slice(bytes),
+                (KeyField::Bytes { width }, KeyValue::Bytes(bytes)) =>
+                    return Err(GeocodeError::InvalidFormat { reason: format!(
+                        "bytes of length {} do not match width {}",
+                        bytes.len(), width) }),
+                (KeyField::Code { precision }, KeyValue::Code(value)) => {
+                    let code = self.geocode.parse_key(value)?;
+                    let length = code.chars().count();
+                    let last = values.peek().is_none();
+                    if length != *precision
+                            && !(partial && last && length < *precision) {
+                        return Err(GeocodeError::InvalidPrecision {
+                            precision: length });
+                    }
+
+                    key.extend_from_slice(code.as_bytes());
+                    if length < *precision {
+                        break;
+                    }
+                },
+                (KeyField::Tenant, KeyValue::Tenant(tenant)) =>
+                    key.extend_from_slice(&tenant.to_be_bytes()),
+                (KeyField::Timestamp { reversed }, KeyValue::Timestamp(x)) => {
+                    // flipping the sign bit orders negative timestamps first
+                    let value = (*x as u64) ^ (1 << 63);
+                    let value = if *reversed { !value } else { value };
+                    key.extend_from_slice(&value.to_be_bytes());
+                },
+                (field, value) =>
+                    return Err(GeocodeError::InvalidFormat { reason: format!(
+                        "expected {} value, found {:?}",
+                        field.get_name(), value) }),
+            }
+        }
+
+        if values.next().is_some() {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "too many key values".to_string() });
+        }
+
+        Ok(key)
+    }
+
+    fn fill_salts(&self, key: &mut [u8]) {
+        // salts hash the key with every salt byte zeroed, so writes of
+        // a cell spread across buckets by their other fields as well
+        let mut hash = 0xcbf29ce484222325u64;
+        for byte in key.iter() {
+            hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+
+        let mut offset = 0;
+        for field in self.fields.iter() {
+            if let KeyField::Salt { buckets } = field {
+                key[offset] = (hash % *buckets as u64) as u8;
+            }
+
+            offset += field.get_width();
+        }
+    }
+}
+
+fn get_u64_bytes(bytes: &[u8]) -> [u8; 8] {
+    let mut array = [0u8; 8];
+    array.copy_from_slice(bytes);
+    array
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::{KeyBuilder, KeyValue};
+
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn get_values(code: &str, timestamp: i64) -> Vec<KeyValue> {
+        vec![KeyValue::Tenant(42), KeyValue::Code(code.to_string()),
+            KeyValue::Timestamp(timestamp), KeyValue::Bytes(b"ab".to_vec())]
+    }
+
+    #[test]
+    fn encode_decode() {
+        let builder = KeyBuilder::new(Geocode::Geohash).salt(16).tenant()
+            .code(6).reversed_timestamp().bytes(2);
+        assert_eq!(builder.get_width(), 25);
+
+        let values = get_values("dpc5u6", 1704067200);
+        let key = builder.encode(&values).unwrap();
+        assert_eq!(key.len(), 25);
+        assert!(key[0] < 16);
+        assert_eq!(&key[1..9], &42u64.to_be_bytes());
+        assert_eq!(&key[9..15], b"dpc5u6");
+        assert_eq!(builder.decode(&key).unwrap(), values);
+
+        // reversed timestamps order the most recent rows first
+        let earlier = builder.encode(&get_values("dpc5u6", -1)).unwrap();
+        let later = builder.encode(&get_values("dpc5u6", 1704067201))
+            .unwrap();
+        assert!(later[15..] < key[15..] && key[15..] < earlier[15..]);
+        assert_eq!(builder.decode(&earlier).unwrap()[2],
+            KeyValue::Timestamp(-1));
+
+        // formatted codes are stored as keys
+        let builder = KeyBuilder::new(Geocode::PlusCode).code(8).timestamp();
+        let values = vec![KeyValue::Code("86HWGGGP+".to_string()),
+            KeyValue::Timestamp(i64::MIN)];
+        let key = builder.encode(&values).unwrap();
+        assert_eq!(&key[..8], b"86HWGGGP");
+        assert_eq!(&key[8..], &[0u8; 8]);
+        assert_eq!(builder.decode(&key).unwrap(), values);
+    }
+
+    #[test]
+    fn invalid() {
+        let builder = KeyBuilder::new(Geocode::Geohash).salt(16).tenant()
+            .code(6).reversed_timestamp().bytes(2);
+        let mut key = builder.encode(&get_values("dpc5u6", 0)).unwrap();
+
+        assert_eq!(builder.encode(&get_values("dpc5u", 0)),
+            Err(GeocodeError::InvalidPrecision { precision: 5 }));
+        assert!(builder.encode(&get_values("dpc5ua", 0)).is_err());
+        assert!(builder.encode(&get_values("dpc5u6", 0)[..3]).is_err());
+        assert!(builder.encode(&[KeyValue::Timestamp(0),
+            KeyValue::Tenant(0), KeyValue::Code("dpc5u6".to_string()),
+            KeyValue::Bytes(b"ab".to_vec())]).is_err());
+        assert!(builder.decode(&key[1..]).is_err());
+
+        key[0] = (key[0] + 1) % 16;
+        assert!(builder.decode(&key).is_err());
+    }
+
+    #[test]
+    fn prefixes() {
+        let builder = KeyBuilder::new(Geocode::Geohash).salt(4).tenant()
+            .code(6).reversed_timestamp();
+
+        // partial codes match the keys of descendant cells
+        let values = [KeyValue::Tenant(7), KeyValue::Code("dp".to_string())];
+        let prefixes = builder.prefixes(&values).unwrap();
+        assert_eq!(prefixes.len(), 4);
+        assert!(prefixes.iter().all(|x| x.len() == 11 && &x[9..] == b"dp"));
+
+        let key = builder.encode(&[KeyValue::Tenant(7),
+            KeyValue::Code("dpc5u6".to_string()),
+            KeyValue::Timestamp(0)]).unwrap();
+        assert!(prefixes.iter().any(|x| key.starts_with(x)));
+
+        // unsalted layouts scan a single prefix
+        let builder = KeyBuilder::new(Geocode::QuadTile).code(8).salt(2);
+        assert_eq!(builder.prefixes(&[KeyValue::Code("03".to_string())]),
+            Ok(vec![b"03".to_vec()]));
+        assert_eq!(builder.prefixes(&[]), Ok(vec![Vec::new()]));
+        assert!(builder.prefixes(&[KeyValue::Code("0123012301".to_string())])
+            .is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{CellSet, Geocode, GeocodeError};
+
+use std::collections::BTreeSet;
+
+// fill colors (aabbggrr) cycled across precision levels
+const COLORS: &[&str] = &["7f0000ff", "7f00a5ff", "7f00ffff", "7f00ff00",
+    "7fff0000", "7f82004b", "7fee82ee"];
+
+impl Geocode {
+    fn format_placemark(&self, value: &str, precision: usize)
+            -> Result<String, GeocodeError> {
+        let points: Vec<String> = self.decode_ring(value)?.iter()
+            .map(|(x, y)| format!("{},{},0", x, y)).collect();
+        Ok(format!("<Placemark><name>{}</name>\
+            <styleUrl>#precision-{}</styleUrl><Polygon><outerBoundaryIs>\
+            <LinearRing><coordinates>{}</coordinates></LinearRing>\
+            </outerBoundaryIs></Polygon></Placemark>", escape(value),
+            precision, points.join(" ")))
+    }
+
+    pub fn to_kml(&self, value: &str) -> Result<String, GeocodeError> {
+        let precision = self.get_precision(value)?;
+        let placemark = self.format_placemark(value, precision)?;
+        Ok(format_document(value, &[precision], &[placemark]))
+    }
+}
+
+impl CellSet {
+    pub fn to_kml(&self, name: &str) -> Result<String, GeocodeError> {
+        // cells are styled by precision so mixed coverings stand out
+        let (mut precisions, mut placemarks) = (BTreeSet::new(), Vec::new());
+        for cell in self.iter() {
+            let precision = self.get_geocode().get_precision(&cell)?;
+            precisions.insert(precision);
+            placemarks.push(self.get_geocode()
+                .format_placemark(&cell, precision)?);
+        }
+
+        let precisions: Vec<usize> = precisions.into_iter().collect();
+        Ok(format_document(name, &precisions, &placemarks))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+        .replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn format_document(name: &str, precisions: &[usize],
+        placemarks: &[String]) -> String {
+    let styles: Vec<String> = precisions.iter().map(|x| format!(
+        "<Style id=\"precision-{}\"><LineStyle><color>ff000000</color>\
+        </LineStyle><PolyStyle><color>{}</color></PolyStyle></Style>",
+        x, COLORS[x % COLORS.len()])).collect();
+
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <kml xmlns=\"http://www.opengis.net/kml/2.2\"><Document>\
+        <name>{}</name>{}{}</Document></kml>\n", escape(name),
+        styles.concat(), placemarks.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+
+    #[test]
+    fn to_kml() {
+        let geocode = Geocode::Geohash;
+        let kml = geocode.to_kml("0").unwrap();
+        assert!(kml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(kml.contains("<Style id=\"precision-1\">"));
+        assert!(kml.contains("<Placemark><name>0</name>\
+            <styleUrl>#precision-1</styleUrl>"));
+        assert!(kml.contains("<coordinates>-180,-90,0 -135,-90,0 \
+            -135,-45,0 -180,-45,0 -180,-90,0</coordinates>"));
+        assert_eq!(geocode.to_kml(""), Err(GeocodeError::EmptyCode));
+
+        // styles are defined once for each precision within the set
+        let cell_set = CellSet::from_cells(geocode, vec!["dpc5".to_string(),
+            "dpc6".to_string(), "9xjq8z".to_string()]).unwrap();
+        let kml = cell_set.to_kml("fence <a & b>").unwrap();
+        assert!(kml.contains("<name>fence &lt;a &amp; b&gt;</name>"));
+        assert_eq!(kml.matches("<Style id=").count(), 2);
+        assert_eq!(kml.matches("<Placemark>").count(), 3);
+        assert_eq!(kml.matches("#precision-4").count(), 2);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(super::escape("86HJ7JR9+CV"), "86HJ7JR9+CV");
+        assert_eq!(super::escape("<'a' & \"b\">"),
+            "&lt;&apos;a&apos; &amp; &quot;b&quot;&gt;");
+    }
+}
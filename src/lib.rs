@@ -1,6 +1,231 @@
-use std::error::Error;
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 
-const GEOHASH_BOUNDS: (f64, f64, f64, f64) = (-180.0, 180.0, -90.0, 90.0);
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("the libm feature is required when std is disabled");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "alloc")]
+mod batch;
+mod bbox;
+pub use bbox::BoundingBox;
+#[cfg(feature = "std")]
+mod binner;
+#[cfg(feature = "std")]
+pub use binner::{Aggregate, Binner};
+#[cfg(feature = "roaring")]
+mod bitmap;
+#[cfg(feature = "roaring")]
+pub use bitmap::BitmapCellSet;
+#[cfg(feature = "std")]
+mod bloom;
+#[cfg(feature = "std")]
+pub use bloom::CellBloom;
+#[cfg(feature = "alloc")]
+mod case;
+#[cfg(feature = "alloc")]
+pub use case::Case;
+#[cfg(feature = "alloc")]
+mod cellid;
+#[cfg(feature = "std")]
+mod cellindex;
+#[cfg(feature = "std")]
+pub use cellindex::CellIndex;
+#[cfg(feature = "std")]
+mod cellset;
+#[cfg(feature = "std")]
+pub use cellset::CellSet;
+mod code;
+pub use code::Code;
+#[cfg(feature = "std")]
+mod compress;
+#[cfg(feature = "std")]
+pub use compress::{CellReader, CellWriter};
+#[cfg(feature = "std")]
+mod coordinate;
+#[cfg(feature = "std")]
+pub use coordinate::{Coordinate, LonLat, Mercator};
+#[cfg(feature = "std")]
+mod cover;
+#[cfg(feature = "std")]
+pub use cover::CoverMode;
+#[cfg(feature = "std")]
+mod coverer;
+#[cfg(feature = "std")]
+pub use coverer::CoverOptions;
+#[cfg(feature = "alloc")]
+mod custom;
+#[cfg(feature = "alloc")]
+pub use custom::{AxisOrder, CustomGeocode, CustomGeocodeBuilder};
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+#[cfg(feature = "std")]
+pub mod distance;
+#[cfg(feature = "std")]
+pub use distance::DistanceMethod;
+#[cfg(feature = "std")]
+pub mod elasticsearch;
+mod error;
+pub use error::GeocodeError;
+#[cfg(feature = "std")]
+mod estimate;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "geo")]
+mod geo;
+#[cfg(feature = "std")]
+mod geodetic;
+#[cfg(feature = "std")]
+pub use geodetic::{GeodeticGrid, GeodeticTile};
+#[cfg(feature = "std")]
+mod geofence;
+#[cfg(feature = "std")]
+pub use geofence::Geofence;
+mod geohash36;
+#[cfg(feature = "alloc")]
+mod geohash3d;
+#[cfg(feature = "alloc")]
+pub use geohash3d::Geohash3d;
+#[cfg(feature = "std")]
+mod geojson;
+mod grid;
+#[cfg(feature = "alloc")]
+pub use grid::GridRegistry;
+pub use grid::GridSystem;
+#[cfg(feature = "h3")]
+pub mod h3;
+#[cfg(feature = "alloc")]
+mod hierarchy;
+#[cfg(feature = "alloc")]
+mod key;
+#[cfg(feature = "alloc")]
+pub use key::{KeyBuilder, KeyValue};
+#[cfg(feature = "kml")]
+mod kml;
+mod maidenhead;
+mod math;
+#[cfg(feature = "std")]
+mod measure;
+#[cfg(feature = "std")]
+pub use measure::Distortion;
+#[cfg(feature = "std")]
+mod mmap;
+#[cfg(feature = "std")]
+pub use mmap::{MappedEntries, MappedIndex, MappedIndexWriter};
+pub mod morton;
+mod neighbor;
+pub use neighbor::{Direction, PolePolicy};
+#[cfg(feature = "node")]
+pub mod node;
+#[cfg(feature = "alloc")]
+mod octree;
+#[cfg(feature = "alloc")]
+pub use octree::Octree;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod pluscode;
+#[cfg(feature = "std")]
+mod polygon;
+#[cfg(feature = "std")]
+pub use polygon::Polygon;
+#[cfg(feature = "std")]
+mod predicate;
+#[cfg(feature = "std")]
+pub mod project;
+#[cfg(feature = "alloc")]
+mod quadtree;
+#[cfg(feature = "alloc")]
+pub use quadtree::QuadTree;
+#[cfg(feature = "alloc")]
+mod range;
+#[cfg(feature = "alloc")]
+pub use range::RangePolicy;
+#[cfg(feature = "alloc")]
+pub mod redis;
+#[cfg(feature = "std")]
+mod reencode;
+#[cfg(feature = "proj")]
+mod reproject;
+#[cfg(feature = "proj")]
+pub use reproject::Reprojector;
+#[cfg(feature = "std")]
+mod resolution;
+#[cfg(feature = "std")]
+mod ring;
+#[cfg(feature = "std")]
+pub use ring::Spiral;
+#[cfg(feature = "std")]
+mod rollup;
+#[cfg(feature = "rstar")]
+mod rtree;
+#[cfg(feature = "rstar")]
+pub use rtree::Cell;
+#[cfg(feature = "rand")]
+mod sample;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "std")]
+mod similarity;
+#[cfg(feature = "std")]
+pub use similarity::Similarity;
+#[cfg(feature = "std")]
+mod sketch;
+#[cfg(feature = "std")]
+pub use sketch::CellSketch;
+#[cfg(feature = "alloc")]
+mod snap;
+#[cfg(feature = "std")]
+mod sort;
+#[cfg(feature = "std")]
+pub use sort::SortOptions;
+#[cfg(feature = "std")]
+mod spatiotemporal;
+#[cfg(feature = "std")]
+pub use spatiotemporal::SpatioTemporal;
+#[cfg(feature = "std")]
+pub mod sql;
+#[cfg(feature = "std")]
+mod svg;
+#[cfg(feature = "std")]
+pub use svg::{SvgOptions, SvgProjection};
+#[cfg(feature = "std")]
+mod tile;
+#[cfg(feature = "std")]
+pub use tile::{Tile, TileScheme};
+#[cfg(feature = "std")]
+mod trie;
+#[cfg(feature = "std")]
+pub use trie::CellTrie;
+#[cfg(feature = "std")]
+pub mod utm;
+#[cfg(feature = "alloc")]
+mod validate;
+#[cfg(feature = "alloc")]
+pub use validate::ValidationMode;
+mod vertex;
+pub use vertex::Edge;
+mod volume;
+pub use volume::BoundingVolume;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+mod wkt;
+#[cfg(feature = "std")]
+mod zorder;
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt::{self, Display, Formatter, Write};
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+
+const GEOHASH_BOUNDS: BoundingBox =
+    BoundingBox::new(-180.0, 180.0, -90.0, 90.0);
 static GEOHASH16_CHARS: &[char] = &['0', '1', '2', '3', '4',
     '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f'];
 static GEOHASH32_CHARS: &[char] = &['0', '1', '2', '3', '4',
@@ -8,136 +233,429 @@ static GEOHASH32_CHARS: &[char] = &['0', '1', '2', '3', '4',
     'k', 'm', 'n', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z'
 ];
 
-const QUADTILE_BOUNDS: (f64, f64, f64, f64) = (-20037508.342789248,
+const QUADTILE_BOUNDS: BoundingBox = BoundingBox::new(-20037508.342789248,
     20037508.342789248, -20037508.342789248, 20037508.342789248);
+// ordered by bisection value which yields bing maps quadkey digits
 static QUADTILE_CHARS: &[char] = &['2', '0', '3', '1'];
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Geocode {
     Geohash,
     Geohash16,
+    Geohash36,
+    Maidenhead,
+    PlusCode,
     QuadTile,
 }
 
 impl Geocode {
-    pub fn decode(&self, _value: &str)
-            -> Result<(f64, f64, f64, f64), Box<dyn Error>> {
-        unimplemented!(); // TODO - implement
+    pub const ALL: [Geocode; 6] = [Geocode::Geohash, Geocode::Geohash16,
+        Geocode::Geohash36, Geocode::Maidenhead, Geocode::PlusCode,
+        Geocode::QuadTile];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Geocode::Geohash => "geohash",
+            Geocode::Geohash16 => "geohash16",
+            Geocode::Geohash36 => "geohash36",
+            Geocode::Maidenhead => "maidenhead",
+            Geocode::PlusCode => "pluscode",
+            Geocode::QuadTile => "quadtile",
+        }
+    }
+
+    pub fn decode(&self, value: &str)
+            -> Result<BoundingBox, GeocodeError> {
+        GridSystem::decode(self, value)
+    }
+
+    pub fn decode_center(&self, value: &str)
+            -> Result<(f64, f64, f64, f64), GeocodeError> {
+        let bbox = self.decode(value)?;
+
+        // compute cell midpoint and half-intervals
+        let (x, y) = bbox.center();
+        Ok((x, y, bbox.width() / 2f64, bbox.height() / 2f64))
     }
 
+    #[cfg(feature = "alloc")]
     pub fn encode(&self, x: f64, y: f64, precision: usize)
-            -> Result<String, Box<dyn Error>> {
-        // retreive geocode specific parameters
-        let (mut min_x, mut max_x, mut min_y, mut max_y,
-                char_bits, codes) = match self {
-            Geocode::Geohash => (GEOHASH_BOUNDS.0, GEOHASH_BOUNDS.1,
-                GEOHASH_BOUNDS.2, GEOHASH_BOUNDS.3, 5, GEOHASH32_CHARS),
-            Geocode::Geohash16 => (GEOHASH_BOUNDS.0, GEOHASH_BOUNDS.1,
-                GEOHASH_BOUNDS.2, GEOHASH_BOUNDS.3, 4, GEOHASH16_CHARS),
-            Geocode::QuadTile => (QUADTILE_BOUNDS.0, QUADTILE_BOUNDS.1,
-                QUADTILE_BOUNDS.2, QUADTILE_BOUNDS.3, 2, QUADTILE_CHARS),
+            -> Result<String, GeocodeError> {
+        GridSystem::encode(self, x, y, precision)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn encode_full(&self, x: f64, y: f64, precision: usize)
+            -> Result<(String, BoundingBox), GeocodeError> {
+        // retreive geocode specific parameters, decoding the cell for
+        // geocodes without them
+        let (char_bits, codes) = match self.get_parameters() {
+            Some(parameters) => parameters,
+            None => {
+                let code = self.encode(x, y, precision)?;
+                let bbox = self.decode(&code)?;
+                return Ok((code, bbox));
+            },
         };
+        let bbox = self.get_bounds();
 
-        // check if coordinates are valid
-        if x < min_x || x > max_x || y < min_y || y > max_y {
-            return Err(format!("coordinate ({}, {}) is outside of geocode range ({} - {}, {} - {})", x, y, min_x, max_x, min_y, max_y).into());
+        // check if coordinates and precision are valid
+        if !bbox.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds: bbox });
+        } else if precision == 0 {
+            return Err(GeocodeError::InvalidPrecision { precision });
         }
 
-        // initailize instance variables
-        let mut bits_total: i8 = 0;
-        let mut hash_value: usize = 0;
         let mut out = String::with_capacity(precision);
+        let cell = encode_bits(&bbox, x, y, precision, char_bits, |_| codes,
+            &mut out)?;
+        Ok((out, cell))
+    }
 
-        // compute geocode code
-        while out.len() < precision {
-            for _ in 0..char_bits {
-                if bits_total % 2 == 0 {
-                    // split on x value
-                    let mid = (max_x + min_x) / 2f64;
-                    if x > mid {
-                        hash_value = (hash_value << 1) + 1usize;
-                        min_x = mid;
-                    } else {
-                        hash_value <<= 1;
-                        max_x = mid;
-                    }
-                } else {
-                    // split on y value
-                    let mid = (max_y + min_y) / 2f64;
-                    if y > mid {
-                        hash_value = (hash_value << 1) + 1usize;
-                        min_y = mid;
-                    } else {
-                        hash_value <<= 1;
-                        max_y = mid;
-                    }
-                }
-                bits_total += 1;
-            }
+    pub fn encode_into<W: Write>(&self, x: f64, y: f64, precision: usize,
+            out: &mut W) -> Result<(), GeocodeError> {
+        // retreive geocode specific parameters, where the others write
+        // their codes directly
+        let (char_bits, codes) = match self.get_parameters() {
+            Some(parameters) => parameters,
+            None => return match self {
+                Geocode::Geohash36 => geohash36::encode(x, y, precision, out),
+                Geocode::Maidenhead =>
+                    maidenhead::encode(x, y, precision, out),
+                _ => pluscode::encode(x, y, precision, out),
+            },
+        };
+        let bbox = self.get_bounds();
 
-            // append character to output
-            let code: char = codes[hash_value];
-            out.push(code);
-            hash_value = 0;
+        // check if coordinates and precision are valid
+        if !bbox.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds: bbox });
+        } else if precision == 0 {
+            return Err(GeocodeError::InvalidPrecision { precision });
         }
 
-        Ok(out)
+        encode_bits(&bbox, x, y, precision, char_bits, |_| codes, out)?;
+        Ok(())
     }
 
-    pub fn get_epsg_code(&self) -> u32 {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn format_key(&self, key: &str) -> String {
         match self {
-            Geocode::Geohash => 4326,
-            Geocode::Geohash16 => 4326,
-            Geocode::QuadTile => 3857,
+            Geocode::PlusCode => pluscode::format(key),
+            _ => key.to_string(),
         }
     }
 
+    #[cfg(feature = "alloc")]
+    pub(crate) fn get_alphabet(&self, index: usize) -> &[char] {
+        GridSystem::get_alphabet(self, index)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn get_child_precision(&self, precision: usize)
+            -> Option<usize> {
+        match self {
+            Geocode::Maidenhead => Some(precision + 2),
+            Geocode::PlusCode => pluscode::get_child_precision(precision),
+            _ => Some(precision + 1),
+        }
+    }
+
+    pub fn get_epsg_code(&self) -> u32 {
+        GridSystem::get_epsg_code(self)
+    }
+
     pub fn get_intervals(&self, precision: usize) -> (f64, f64) {
+        GridSystem::get_intervals(self, precision)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn get_parent_precision(&self, precision: usize)
+            -> Option<usize> {
         match self {
-            Geocode::Geohash => {
-                // calculate number of bits for latitude and longitude
-                let lat_bits = (2 * precision) as f64
-                    + (precision as f64 / 2.0).floor();
-                let long_bits = (2 * precision) as f64
-                    + (precision as f64 / 2.0).ceil();
-
-                // calculate deltas
-                let lat_delta = (GEOHASH_BOUNDS.3 - GEOHASH_BOUNDS.2) /
-                    2_u32.pow(lat_bits as u32) as f64;
-                let long_delta = (GEOHASH_BOUNDS.1 - GEOHASH_BOUNDS.0) /
-                    2_u32.pow(long_bits as u32) as f64;
-
-                (long_delta, lat_delta)
-            },
-            Geocode::Geohash16 => {
-                // calculate deltas
-                let lat_delta = (GEOHASH_BOUNDS.3 - GEOHASH_BOUNDS.2) /
-                    2_u32.pow(2 * precision as u32) as f64;
-                let long_delta = (GEOHASH_BOUNDS.1 - GEOHASH_BOUNDS.0) /
-                    2_u32.pow(2 * precision as u32) as f64;
-
-                (long_delta, lat_delta)
-            },
-            Geocode::QuadTile => {
-                // calculate delta
-                let delta = (QUADTILE_BOUNDS.1 - QUADTILE_BOUNDS.0) /
-                    2_u32.pow(precision as u32) as f64;
+            Geocode::Maidenhead if precision > 2 => Some(precision - 2),
+            Geocode::Maidenhead => None,
+            Geocode::PlusCode => pluscode::get_parent_precision(precision),
+            _ if precision > 1 => Some(precision - 1),
+            _ => None,
+        }
+    }
 
-                (delta, delta)
-            },
+    #[cfg(feature = "alloc")]
+    pub fn get_precision(&self, value: &str) -> Result<usize, GeocodeError> {
+        Ok(self.parse_key(value)?.chars().count())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn is_valid_precision(&self, precision: usize) -> bool {
+        match self {
+            Geocode::Maidenhead => maidenhead::is_valid_precision(precision),
+            Geocode::PlusCode => pluscode::is_valid_precision(precision),
+            _ => precision > 0,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn native_to_wgs84(&self, x: f64, y: f64) -> (f64, f64) {
+        match self.get_epsg_code() {
+            3857 => project::mercator_to_wgs84(x, y),
+            _ => (x, y),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn parse_key(&self, value: &str)
+            -> Result<String, GeocodeError> {
+        // keys are codes stripped of formatting where ancestors are
+        // always prefixes of their descendants
+        match self {
+            Geocode::Maidenhead => return maidenhead::parse(value),
+            Geocode::PlusCode => return pluscode::parse(value),
+            _ => {},
+        }
+
+        // check if code is valid
+        if value.is_empty() {
+            return Err(GeocodeError::EmptyCode);
+        }
+
+        for (index, c) in value.chars().enumerate() {
+            if !self.get_alphabet(index).contains(&c) {
+                return Err(GeocodeError::InvalidCharacter { ch: c, index });
+            }
         }
+
+        Ok(value.to_string())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn wgs84_to_native(&self, lon: f64, lat: f64) -> (f64, f64) {
+        match self.get_epsg_code() {
+            3857 => project::wgs84_to_mercator(lon, lat),
+            _ => (lon, lat),
+        }
+    }
+}
+
+impl Display for Geocode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for Geocode {
+    type Err = GeocodeError;
+
+    fn from_str(s: &str) -> Result<Geocode, GeocodeError> {
+        // names match the serde representation, ignoring ascii case
+        Geocode::ALL.iter().find(|x| x.as_str().eq_ignore_ascii_case(s))
+            .copied().ok_or_else(|| GeocodeError::UnknownGeocode {
+                name: s.to_string() })
     }
 }
 
-#[cfg(test)]
+pub(crate) fn decode_bits<'a, F>(bounds: &BoundingBox, char_bits: usize,
+        alphabet: F, value: &str) -> Result<BoundingBox, GeocodeError>
+        where F: Fn(usize) -> &'a [char] {
+    let mut bbox = *bounds;
+
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    }
+
+    // the leading 64 bits are deinterleaved directly into cell indices
+    let mut interleaved = 0u64;
+    let mut prefix_bits = 0;
+    for (index, c) in value.chars().take(64 / char_bits).enumerate() {
+        let hash_value = match alphabet(index).iter()
+                .position(|x| *x == c) {
+            Some(hash_value) => hash_value,
+            None => return Err(GeocodeError::InvalidCharacter {
+                ch: c, index }),
+        };
+
+        interleaved = (interleaved << char_bits) | hash_value as u64;
+        prefix_bits += char_bits;
+    }
+
+    // the first bit splits on x, so x holds the extra bit when
+    // the total number of bits is odd
+    let (x, y) = match prefix_bits % 2 {
+        0 => morton::deinterleave(interleaved),
+        _ => (morton::compact(interleaved),
+            morton::compact(interleaved >> 1)),
+    };
+    bbox = morton::get_cell(&bbox, x, y,
+        prefix_bits.div_ceil(2), prefix_bits / 2);
+
+    // refine bounds by bisection for the remaining characters
+    let mut bits_total = prefix_bits;
+    for (index, c) in value.chars().enumerate()
+            .skip(prefix_bits / char_bits) {
+        let hash_value = match alphabet(index).iter()
+                .position(|x| *x == c) {
+            Some(hash_value) => hash_value,
+            None => return Err(GeocodeError::InvalidCharacter {
+                ch: c, index }),
+        };
+
+        for i in (0..char_bits).rev() {
+            let bit = (hash_value >> i) & 1;
+            if bits_total.is_multiple_of(2) {
+                // split on x value
+                let mid = (bbox.max_x + bbox.min_x) / 2f64;
+                if bit == 1 {
+                    bbox.min_x = mid;
+                } else {
+                    bbox.max_x = mid;
+                }
+            } else {
+                // split on y value
+                let mid = (bbox.max_y + bbox.min_y) / 2f64;
+                if bit == 1 {
+                    bbox.min_y = mid;
+                } else {
+                    bbox.max_y = mid;
+                }
+            }
+            bits_total += 1;
+        }
+    }
+
+    Ok(bbox)
+}
+
+pub(crate) fn encode_bits<'a, W: Write, F>(bounds: &BoundingBox, x: f64,
+        y: f64, precision: usize, char_bits: usize, alphabet: F,
+        out: &mut W) -> Result<BoundingBox, fmt::Error>
+        where F: Fn(usize) -> &'a [char] {
+    let bbox = *bounds;
+
+    // the leading 64 bits are computed from the cell indices and
+    // emitted from the interleaved value
+    let prefix = precision.min(64 / char_bits);
+    let prefix_bits = prefix * char_bits;
+    let (x_bits, y_bits) = (prefix_bits.div_ceil(2), prefix_bits / 2);
+    let x_index = morton::get_index(x, bbox.min_x, bbox.width(), x_bits);
+    let y_index = morton::get_index(y, bbox.min_y, bbox.height(), y_bits);
+    push_interleaved(x_index, y_index, prefix, char_bits, &alphabet, out)?;
+
+    // refine the remaining characters by bisection
+    let BoundingBox { mut min_x, mut max_x, mut min_y, mut max_y } =
+        morton::get_cell(&bbox, x_index, y_index, x_bits, y_bits);
+    let mut bits_total = prefix_bits;
+    let mut hash_value: usize = 0;
+
+    // compute geocode code
+    for index in prefix..precision {
+        for _ in 0..char_bits {
+            if bits_total.is_multiple_of(2) {
+                // split on x value
+                let mid = (max_x + min_x) / 2f64;
+                if x > mid {
+                    hash_value = (hash_value << 1) + 1usize;
+                    min_x = mid;
+                } else {
+                    hash_value <<= 1;
+                    max_x = mid;
+                }
+            } else {
+                // split on y value
+                let mid = (max_y + min_y) / 2f64;
+                if y > mid {
+                    hash_value = (hash_value << 1) + 1usize;
+                    min_y = mid;
+                } else {
+                    hash_value <<= 1;
+                    max_y = mid;
+                }
+            }
+            bits_total += 1;
+        }
+
+        // append character to output
+        let code: char = alphabet(index)[hash_value];
+        out.write_char(code)?;
+        hash_value = 0;
+    }
+
+    // bisection leaves the bounds of the encoded cell
+    Ok(BoundingBox::new(min_x, max_x, min_y, max_y))
+}
+
+pub(crate) fn push_interleaved<'a, W: Write, F>(x_index: u32, y_index: u32,
+        precision: usize, char_bits: usize, alphabet: F,
+        out: &mut W) -> fmt::Result where F: Fn(usize) -> &'a [char] {
+    // the first bit splits on x, so x holds the extra bit when the total
+    // number of bits is odd
+    let bits = precision * char_bits;
+    let interleaved = match bits % 2 {
+        0 => morton::interleave(x_index, y_index),
+        _ => morton::spread(x_index) | (morton::spread(y_index) << 1),
+    };
+
+    let mask = (1u64 << char_bits) - 1;
+    for i in 0..precision {
+        let shift = bits - (i + 1) * char_bits;
+        out.write_char(alphabet(i)[((interleaved >> shift) & mask) as usize])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-    use super::Geocode;
+    use super::{BoundingBox, Geocode, GeocodeError, QUADTILE_BOUNDS};
 
     const APPLETON_LAT_LONG: (f64, f64) = (-88.4, 44.266667);
     const APPLETON_MERCATOR: (f64, f64) = (-9840642.99, 5506802.68);
     const FORT_COLLINS_LAT_LONG: (f64, f64) = (-105.078056, 40.559167);
     const FORT_COLLINS_MERCATOR: (f64, f64) = (-11697235.69, 4947534.74);
 
+    #[test]
+    fn from_str() {
+        for geocode in Geocode::ALL.iter() {
+            assert_eq!(geocode.to_string().parse::<Geocode>(), Ok(*geocode));
+        }
+
+        assert_eq!("geohash16".parse(), Ok(Geocode::Geohash16));
+        assert_eq!("QuadTile".parse(), Ok(Geocode::QuadTile));
+        assert_eq!("s2".parse::<Geocode>(), Err(GeocodeError::UnknownGeocode {
+            name: "s2".to_string() }));
+    }
+
+    #[test]
+    fn geohash_decode() {
+        let geocode = Geocode::Geohash;
+
+        let result = geocode.decode("dpc5u6");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), BoundingBox::new(-88.406982421875,
+            -88.39599609375, 44.263916015625, 44.2694091796875));
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("dpc5a6"),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 4 }));
+    }
+
+    #[test]
+    fn geohash_decode_center() {
+        let geocode = Geocode::Geohash;
+
+        let result = geocode.decode_center("dpc5u6");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), (-88.4014892578125, 44.26666259765625,
+            0.0054931640625, 0.00274658203125));
+
+        assert_eq!(geocode.decode_center(""), Err(GeocodeError::EmptyCode));
+    }
+
     #[test]
     fn geohash_encode() {
         let geocode = Geocode::Geohash;
@@ -151,6 +669,77 @@ mod tests {
             FORT_COLLINS_LAT_LONG.0, FORT_COLLINS_LAT_LONG.1, 8);
         assert!(result.is_ok());
         assert_eq!("9xjq8zs6", &result.unwrap());
+
+        assert_eq!(geocode.encode(-181.0, 0.0, 6),
+            Err(GeocodeError::CoordinateOutOfRange { x: -181.0, y: 0.0,
+                bounds: BoundingBox::new(-180.0, 180.0, -90.0, 90.0) }));
+        assert_eq!(geocode.encode(APPLETON_LAT_LONG.0, APPLETON_LAT_LONG.1, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+    }
+
+    #[test]
+    fn geohash_encode_full() {
+        // bounds match decoding the code, including codes longer than
+        // the leading 64 bits
+        for geocode in Geocode::ALL.iter() {
+            let bounds = geocode.get_bounds();
+            let (x, y) = (bounds.min_x + bounds.width() * 0.3719,
+                bounds.min_y + bounds.height() * 0.6281);
+            for precision in [1, 4, 10, 14].iter() {
+                // unsupported precisions fail as they do when encoding
+                let result = geocode.encode_full(x, y, *precision);
+                let code = match geocode.encode(x, y, *precision) {
+                    Ok(code) => code,
+                    Err(e) => {
+                        assert_eq!(e, GeocodeError::InvalidPrecision {
+                            precision: *precision });
+                        assert_eq!(result, Err(e));
+                        continue;
+                    },
+                };
+
+                let (full_code, bbox) = result.unwrap();
+                assert_eq!(full_code, code);
+                assert_eq!(bbox, geocode.decode(&code).unwrap());
+            }
+        }
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_full(APPLETON_LAT_LONG.0,
+            APPLETON_LAT_LONG.1, 6).unwrap().0, "dpc5u6");
+        assert!(geocode.encode_full(-181.0, 0.0, 6).is_err());
+        assert_eq!(geocode.encode_full(0.0, 0.0, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+    }
+
+    #[test]
+    fn geohash_encode_into() {
+        let geocode = Geocode::Geohash;
+        let mut out = String::from("code: ");
+        geocode.encode_into(APPLETON_LAT_LONG.0, APPLETON_LAT_LONG.1, 6,
+            &mut out).unwrap();
+        assert_eq!(out, "code: dpc5u6");
+
+        assert_eq!(geocode.encode_into(-181.0, 0.0, 6, &mut out),
+            Err(GeocodeError::CoordinateOutOfRange { x: -181.0, y: 0.0,
+                bounds: BoundingBox::new(-180.0, 180.0, -90.0, 90.0) }));
+        assert_eq!(out, "code: dpc5u6");
+
+        // write errors are reported
+        struct Full;
+        impl std::fmt::Write for Full {
+            fn write_str(&mut self, _: &str) -> std::fmt::Result {
+                Err(std::fmt::Error)
+            }
+        }
+
+        assert_eq!(geocode.encode_into(0.0, 0.0, 6, &mut Full),
+            Err(GeocodeError::WriteFailure));
+
+        let mut out = String::new();
+        Geocode::Maidenhead.encode_into(-72.72726, 41.714775, 6, &mut out)
+            .unwrap();
+        assert_eq!(out, "FN31pr");
     }
 
     #[test]
@@ -170,6 +759,20 @@ mod tests {
             (0.010986328125, 0.0054931640625));
     }
 
+    #[test]
+    fn geohash16_decode() {
+        let geocode = Geocode::Geohash16;
+
+        let result = geocode.decode("65565d");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), BoundingBox::new(-88.41796875,
+            -88.330078125, 44.2529296875, 44.296875));
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("6556g5"),
+            Err(GeocodeError::InvalidCharacter { ch: 'g', index: 4 }));
+    }
+
     #[test]
     fn geohash16_encode() {
         let geocode = Geocode::Geohash16;
@@ -196,6 +799,86 @@ mod tests {
         assert_eq!(geocode.get_intervals(6), (0.087890625, 0.0439453125));
     }
 
+    #[test]
+    fn interleaved_bisection() {
+        // codes beyond 64 bits are computed by bisection, so prefixes of
+        // long codes must match the interleaved codes
+        for (geocode, precision) in [(Geocode::Geohash, 13),
+                (Geocode::Geohash16, 17), (Geocode::QuadTile, 33)].iter() {
+            let bounds = geocode.get_bounds();
+            for i in 0..=64 {
+                for j in 0..=64 {
+                    // include points on cell edges
+                    let x = bounds.min_x + bounds.width() * i as f64 / 64.0;
+                    let y = bounds.min_y + bounds.height() * j as f64 / 64.0;
+
+                    let code = geocode.encode(x, y, precision - 1).unwrap();
+                    let long_code = geocode.encode(x, y, *precision).unwrap();
+                    assert_eq!(code, long_code[..precision - 1]);
+
+                    let bbox = geocode.decode(&code).unwrap();
+                    let long_bbox = geocode.decode(&long_code).unwrap();
+                    assert!(bbox.contains(x, y) && long_bbox.contains(x, y));
+                    assert!(bbox.contains(long_bbox.min_x, long_bbox.min_y));
+                    assert!(bbox.contains(long_bbox.max_x, long_bbox.max_y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn quadtile_bing_quadkeys() {
+        // quadtile characters match bing maps quadkey digits where
+        // tile rows are numbered from the north
+        let geocode = Geocode::QuadTile;
+        let tile_center = |x: usize, y: usize, level: i32| {
+            let width = QUADTILE_BOUNDS.width() / 2f64.powi(level);
+            (QUADTILE_BOUNDS.min_x + (x as f64 + 0.5) * width,
+                QUADTILE_BOUNDS.max_y - (y as f64 + 0.5) * width)
+        };
+
+        let (x, y) = tile_center(3, 5, 3);
+        assert_eq!(geocode.encode(x, y, 3).unwrap(), "213");
+        let (x, y) = tile_center(35210, 21493, 16);
+        assert_eq!(geocode.encode(x, y, 16).unwrap(), "1202102332221212");
+
+        for tile_x in 0..16 {
+            for tile_y in 0..16 {
+                let quadkey: String = (0..4).rev().map(|i| {
+                    let digit = ((tile_x >> i) & 1) + 2 * ((tile_y >> i) & 1);
+                    std::char::from_digit(digit as u32, 10).unwrap()
+                }).collect();
+
+                let (x, y) = tile_center(tile_x, tile_y, 4);
+                assert_eq!(geocode.encode(x, y, 4).unwrap(), quadkey);
+            }
+        }
+    }
+
+    #[test]
+    fn quadtile_decode() {
+        let geocode = Geocode::QuadTile;
+
+        let result = geocode.decode("0");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), BoundingBox::new(-20037508.342789248,
+            0.0, 0.0, 20037508.342789248));
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("03042"),
+            Err(GeocodeError::InvalidCharacter { ch: '4', index: 3 }));
+    }
+
+    #[test]
+    fn quadtile_decode_center() {
+        let geocode = Geocode::QuadTile;
+
+        let result = geocode.decode_center("0");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), (-10018754.171394624, 10018754.171394624,
+            10018754.171394624, 10018754.171394624));
+    }
+
     #[test]
     fn quadtile_encode() {
         let geocode = Geocode::QuadTile;
@@ -211,6 +894,36 @@ mod tests {
         assert_eq!("02310101", &result.unwrap());
     }
 
+    #[test]
+    fn round_trip() {
+        let points = [
+            (Geocode::Geohash, APPLETON_LAT_LONG),
+            (Geocode::Geohash, FORT_COLLINS_LAT_LONG),
+            (Geocode::Geohash16, APPLETON_LAT_LONG),
+            (Geocode::Geohash16, FORT_COLLINS_LAT_LONG),
+            (Geocode::Geohash36, APPLETON_LAT_LONG),
+            (Geocode::Geohash36, FORT_COLLINS_LAT_LONG),
+            (Geocode::QuadTile, APPLETON_MERCATOR),
+            (Geocode::QuadTile, FORT_COLLINS_MERCATOR),
+        ];
+
+        for (geocode, (x, y)) in points.iter() {
+            for precision in 1..10 {
+                let code = geocode.encode(*x, *y, precision).unwrap();
+                let bbox = geocode.decode(&code).unwrap();
+
+                // check point is within decoded bounds
+                assert!(bbox.contains(*x, *y));
+
+                // check bounds match intervals
+                let (x_interval, y_interval) =
+                    geocode.get_intervals(precision);
+                assert!((bbox.width() - x_interval).abs() < 1e-6);
+                assert!((bbox.height() - y_interval).abs() < 1e-6);
+            }
+        }
+    }
+
     #[test]
     fn quadtile_intervals() {
         let geocode = Geocode::QuadTile;
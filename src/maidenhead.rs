@@ -0,0 +1,206 @@
+use crate::{math, BoundingBox, GeocodeError, GEOHASH_BOUNDS};
+
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+static FIELD_CHARS: &[char] = &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R'];
+static SQUARE_CHARS: &[char] = &['0', '1', '2', '3', '4',
+    '5', '6', '7', '8', '9'];
+static SUBSQUARE_CHARS: &[char] = &['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h',
+    'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v',
+    'w', 'x'];
+
+#[cfg(feature = "alloc")]
+pub(crate) fn decode(key: &str) -> BoundingBox {
+    // refine bounds by each longitude and latitude character pair
+    let mut bbox = GEOHASH_BOUNDS;
+    let chars: Vec<char> = key.chars().collect();
+    for (i, pair) in chars.chunks(2).enumerate() {
+        let codes = get_alphabet(i * 2);
+        let column = codes.iter().position(|x| *x == pair[0]).unwrap();
+        let row = codes.iter().position(|x| *x == pair[1]).unwrap();
+        bbox = get_cell(&bbox, codes.len(), column, row);
+    }
+
+    bbox
+}
+
+pub(crate) fn encode<W: Write>(x: f64, y: f64, precision: usize,
+        out: &mut W) -> Result<(), GeocodeError> {
+    // check if coordinates and precision are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    } else if !is_valid_precision(precision) {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    // compute geocode code
+    let mut bbox = GEOHASH_BOUNDS;
+    for index in (0..precision).step_by(2) {
+        let codes = get_alphabet(index);
+        let column = get_index(x - bbox.min_x, bbox.width(), codes.len());
+        let row = get_index(y - bbox.min_y, bbox.height(), codes.len());
+
+        out.write_char(codes[column])?;
+        out.write_char(codes[row])?;
+        bbox = get_cell(&bbox, codes.len(), column, row);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn get_alphabet(index: usize) -> &'static [char] {
+    // pairs alternate between squares and subsquares after the field
+    match index / 2 {
+        0 => FIELD_CHARS,
+        x if x % 2 == 1 => SQUARE_CHARS,
+        _ => SUBSQUARE_CHARS,
+    }
+}
+
+fn get_cell(bbox: &BoundingBox, divisions: usize,
+        column: usize, row: usize) -> BoundingBox {
+    let width = bbox.width() / divisions as f64;
+    let height = bbox.height() / divisions as f64;
+    BoundingBox::new(bbox.min_x + column as f64 * width,
+        bbox.min_x + (column + 1) as f64 * width,
+        bbox.min_y + row as f64 * height,
+        bbox.min_y + (row + 1) as f64 * height)
+}
+
+fn get_index(offset: f64, length: f64, divisions: usize) -> usize {
+    // coordinates on cell edges belong to the east or north cell, except
+    // at the maximum longitude and latitude
+    let index = math::floor(offset / length * divisions as f64) as usize;
+    index.min(divisions - 1)
+}
+
+pub(crate) fn get_intervals(precision: usize) -> (f64, f64) {
+    let divisions: f64 = (0..precision / 2)
+        .map(|x| get_alphabet(x * 2).len() as f64).product();
+    (GEOHASH_BOUNDS.width() / divisions, GEOHASH_BOUNDS.height() / divisions)
+}
+
+pub(crate) fn is_valid_precision(precision: usize) -> bool {
+    precision > 0 && precision.is_multiple_of(2)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse(value: &str) -> Result<String, GeocodeError> {
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    } else if !value.chars().count().is_multiple_of(2) {
+        return Err(GeocodeError::InvalidFormat {
+            reason: format!("odd code length in '{}'", value) });
+    }
+
+    // normalize case where fields are upper and subsquares are lower
+    let mut key = String::with_capacity(value.len());
+    for (index, c) in value.chars().enumerate() {
+        let codes = get_alphabet(index);
+        let normalized = match codes == FIELD_CHARS {
+            true => c.to_ascii_uppercase(),
+            false => c.to_ascii_lowercase(),
+        };
+
+        if !codes.contains(&normalized) {
+            return Err(GeocodeError::InvalidCharacter { ch: c, index });
+        }
+
+        key.push(normalized);
+    }
+
+    Ok(key)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn decode() {
+        let geocode = Geocode::Maidenhead;
+        let bbox = geocode.decode("EN54").unwrap();
+        assert_eq!((bbox.min_x, bbox.max_x), (-90.0, -88.0));
+        assert_eq!((bbox.min_y, bbox.max_y), (44.0, 45.0));
+
+        let bbox = geocode.decode("EN54cq").unwrap();
+        assert!((bbox.min_x - -89.833333).abs() < 1e-6);
+        assert!((bbox.max_y - 44.708333).abs() < 1e-6);
+
+        // codes are case insensitive
+        assert_eq!(geocode.decode("en54CQ").unwrap(), bbox);
+
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("SN"),
+            Err(GeocodeError::InvalidCharacter { ch: 'S', index: 0 }));
+        assert_eq!(geocode.decode("EN5a"),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 3 }));
+        assert_eq!(geocode.decode("EN54cz"),
+            Err(GeocodeError::InvalidCharacter { ch: 'z', index: 5 }));
+        assert!(geocode.decode("EN5").is_err());
+    }
+
+    #[test]
+    fn encode() {
+        // reference test vectors
+        let geocode = Geocode::Maidenhead;
+        assert_eq!(geocode.encode(-72.72726, 41.714775, 6).unwrap(),
+            "FN31pr");
+        assert_eq!(geocode.encode(-72.72726, 41.714775, 8).unwrap(),
+            "FN31pr21");
+        assert_eq!(geocode.encode(-88.4, 44.266667, 4).unwrap(), "EN54");
+        assert_eq!(geocode.encode(-180.0, -90.0, 4).unwrap(), "AA00");
+        assert_eq!(geocode.encode(180.0, 90.0, 6).unwrap(), "RR99xx");
+
+        // points on field, square, and subsquare edges belong to the east
+        // and north cells
+        assert_eq!(geocode.encode(0.0, 0.0, 6).unwrap(), "JJ00aa");
+        assert_eq!(geocode.encode(-180.0, -90.0, 6).unwrap(), "AA00aa");
+        assert_eq!(geocode.encode(-72.0, 41.0, 6).unwrap(), "FN41aa");
+        assert_eq!(geocode.encode(-90.0, 44.0, 4).unwrap(), "EN54");
+        assert_eq!(geocode.encode(-88.0, 44.5, 6).unwrap(), "EN64am");
+        assert_eq!(geocode.encode(180.0, -90.0, 4).unwrap(), "RA90");
+
+        assert_eq!(geocode.encode(0.0, 0.0, 3),
+            Err(GeocodeError::InvalidPrecision { precision: 3 }));
+        assert!(geocode.encode(0.0, 91.0, 2).is_err());
+    }
+
+    #[test]
+    fn hierarchy() {
+        let geocode = Geocode::Maidenhead;
+        assert_eq!(geocode.parent("EN54cq").unwrap(), "EN54");
+        assert!(geocode.parent("EN").is_err());
+        assert_eq!(geocode.children("EN").unwrap().count(), 100);
+        assert_eq!(geocode.siblings("EN54cq").unwrap().len(), 575);
+
+        let cells: Vec<String> = geocode.children("EN54").unwrap().collect();
+        assert_eq!(cells.len(), 576);
+        assert!(cells.contains(&"EN54cq".to_string()));
+        assert_eq!(geocode.compact(cells).unwrap(), vec!["EN54"]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let geocode = Geocode::Maidenhead;
+        for precision in (2..=12).step_by(2) {
+            let code = geocode.encode(-105.078056, 40.559167, precision)
+                .unwrap();
+            let bbox = geocode.decode(&code).unwrap();
+            assert!(bbox.contains(-105.078056, 40.559167));
+
+            let (x_interval, y_interval) = geocode.get_intervals(precision);
+            assert!((bbox.width() - x_interval).abs() < 1e-9);
+            assert!((bbox.height() - y_interval).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// floating point functions missing from core are provided by libm when
+// building without std
+
+#[cfg(feature = "std")]
+pub(crate) fn ceil(x: f64) -> f64 {
+    x.ceil()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn ceil(x: f64) -> f64 {
+    libm::ceil(x)
+}
+
+// powers of two are exact, so they may be computed in const contexts
+pub(crate) const fn exp2(n: usize) -> f64 {
+    let (mut value, mut i) = (1f64, 0);
+    while i < n {
+        value *= 2f64;
+        i += 1;
+    }
+
+    value
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn floor(x: f64) -> f64 {
+    x.floor()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn floor(x: f64) -> f64 {
+    libm::floor(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn powi(x: f64, n: i32) -> f64 {
+    x.powi(n)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn powi(x: f64, n: i32) -> f64 {
+    libm::pow(x, n as f64)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn rem_euclid(x: f64, y: f64) -> f64 {
+    let r = x % y;
+    if r < 0f64 { r + y.abs() } else { r }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn round(x: f64) -> f64 {
+    x.round()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn round(x: f64) -> f64 {
+    libm::round(x)
+}
@@ -0,0 +1,232 @@
+use crate::{CellSet, Geocode, GeocodeError};
+use crate::distance::EARTH_MEAN_RADIUS;
+
+use std::collections::HashSet;
+
+// bound the cells measured within wide latitude bands
+const DISTORTION_SAMPLES: usize = 1024;
+
+// cell shape statistics over a latitude band, measured on the sphere where
+// aspect ratios compare east-west to north-south extents at the center of
+// each cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distortion {
+    pub min_aspect_ratio: f64,
+    pub max_aspect_ratio: f64,
+    pub min_edge: f64,
+    pub max_edge: f64,
+    pub min_area: f64,
+    pub max_area: f64,
+    pub area_variation: f64,
+}
+
+impl Geocode {
+    pub fn cell_area(&self, value: &str) -> Result<f64, GeocodeError> {
+        // compute the spherical area between two parallels and meridians
+        let bbox = self.decode_lonlat(value)?;
+        let lon_delta = (bbox.max_x - bbox.min_x).to_radians();
+        let lat_delta = bbox.max_y.to_radians().sin()
+            - bbox.min_y.to_radians().sin();
+
+        Ok(EARTH_MEAN_RADIUS.powi(2) * lon_delta * lat_delta)
+    }
+
+    pub fn cell_perimeter(&self, value: &str) -> Result<f64, GeocodeError> {
+        // sum meridian edges and the parallel edges along each latitude
+        let bbox = self.decode_lonlat(value)?;
+        let lon_delta = (bbox.max_x - bbox.min_x).to_radians();
+        let lat_delta = (bbox.max_y - bbox.min_y).to_radians();
+
+        Ok(EARTH_MEAN_RADIUS * (2f64 * lat_delta + lon_delta
+            * (bbox.min_y.to_radians().cos() + bbox.max_y.to_radians().cos())))
+    }
+
+    pub fn distortion(&self, precision: usize, min_lat: f64, max_lat: f64)
+            -> Result<Distortion, GeocodeError> {
+        if min_lat > max_lat {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "invalid latitude band [{}, {}]", min_lat, max_lat) });
+        }
+
+        // cells share a shape along each parallel, so sample a column of
+        // cells including the band edges and equator where extremes lie
+        let mut lats = vec![min_lat, max_lat];
+        if min_lat < 0f64 && max_lat > 0f64 {
+            lats.push(0f64);
+        }
+
+        lats.extend((1..DISTORTION_SAMPLES).map(|i| min_lat
+            + (max_lat - min_lat) * i as f64 / DISTORTION_SAMPLES as f64));
+
+        let mut distortion = Distortion {
+            min_aspect_ratio: f64::INFINITY, max_aspect_ratio: 0f64,
+            min_edge: f64::INFINITY, max_edge: 0f64,
+            min_area: f64::INFINITY, max_area: 0f64, area_variation: 0f64,
+        };
+
+        let mut visited = HashSet::new();
+        for lat in lats {
+            let code = self.encode_lonlat(0f64, lat, precision)?;
+            if !visited.insert(code.clone()) {
+                continue;
+            }
+
+            let bbox = self.decode_lonlat(&code)?;
+            let lon_delta = (bbox.max_x - bbox.min_x).to_radians();
+            let height = (bbox.max_y - bbox.min_y).to_radians()
+                * EARTH_MEAN_RADIUS;
+            let width = |lat: f64| lon_delta * EARTH_MEAN_RADIUS
+                * lat.to_radians().cos();
+            let (_, center_lat) = bbox.center();
+            let aspect_ratio = width(center_lat) / height;
+            let edges = [width(bbox.min_y), width(bbox.max_y), height];
+            let area = self.cell_area(&code)?;
+
+            distortion.min_aspect_ratio =
+                distortion.min_aspect_ratio.min(aspect_ratio);
+            distortion.max_aspect_ratio =
+                distortion.max_aspect_ratio.max(aspect_ratio);
+            for edge in edges.iter() {
+                distortion.min_edge = distortion.min_edge.min(*edge);
+                distortion.max_edge = distortion.max_edge.max(*edge);
+            }
+
+            distortion.min_area = distortion.min_area.min(area);
+            distortion.max_area = distortion.max_area.max(area);
+        }
+
+        distortion.area_variation = distortion.max_area / distortion.min_area;
+        Ok(distortion)
+    }
+
+    pub fn cells_area(&self, cells: &[String]) -> Result<f64, GeocodeError> {
+        // cells within other cells are dropped so nested coverage is
+        // only counted once
+        let mut keys = Vec::with_capacity(cells.len());
+        for cell in cells.iter() {
+            keys.push(self.parse_key(cell)?);
+        }
+
+        let mut area = 0f64;
+        for key in self.compact_keys(keys) {
+            area += self.cell_area(&self.format_key(&key))?;
+        }
+
+        Ok(area)
+    }
+}
+
+impl CellSet {
+    pub fn area(&self) -> Result<f64, GeocodeError> {
+        // compacted sets never hold overlapping cells
+        let geocode = self.get_geocode();
+        let mut area = 0f64;
+        for cell in self.iter() {
+            area += geocode.cell_area(&cell)?;
+        }
+
+        Ok(area)
+    }
+
+    pub fn union_area(&self, other: &CellSet)
+            -> Result<f64, GeocodeError> {
+        self.union(other)?.area()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+    use crate::distance::EARTH_MEAN_RADIUS;
+
+    use std::f64::consts::PI;
+
+    #[test]
+    fn cell_area() {
+        // cells at the top level cover the sphere
+        let geocode = Geocode::Geohash;
+        let area: f64 = geocode.children("d").unwrap()
+            .map(|x| geocode.cell_area(&x).unwrap()).sum();
+        assert!((area - geocode.cell_area("d").unwrap()).abs() < 1.0);
+
+        let area: f64 = Geocode::Geohash16.children("0").unwrap()
+            .chain(Geocode::Geohash16.siblings("0").unwrap())
+            .map(|x| Geocode::Geohash16.cell_area(&x).unwrap()).sum();
+        assert!((area - 4f64 * PI * EARTH_MEAN_RADIUS.powi(2)).abs()
+            / area < 1e-9);
+
+        // cells shrink toward the poles
+        let equator = Geocode::Maidenhead.cell_area("JJ00").unwrap();
+        let north = Geocode::Maidenhead.cell_area("JQ00").unwrap();
+        assert!((equator / 1e6 - 24727.0).abs() < 1.0);
+        assert!(north < equator / 2f64);
+
+        assert!(geocode.cell_area("dpca").is_err());
+    }
+
+    #[test]
+    fn cells_area() {
+        // nested cells do not add to the area of their ancestors
+        let geocode = Geocode::Geohash;
+        let cells: Vec<String> = ["dp", "dpc5", "dpc5u6", "9q"].iter()
+            .map(|x| x.to_string()).collect();
+        let expected = geocode.cell_area("dp").unwrap()
+            + geocode.cell_area("9q").unwrap();
+        assert!((geocode.cells_area(&cells).unwrap() - expected).abs() < 1.0);
+
+        let cell_set = CellSet::from_cells(geocode, cells).unwrap();
+        assert!((cell_set.area().unwrap() - expected).abs() < 1.0);
+        assert_eq!(CellSet::new(geocode).area().unwrap(), 0.0);
+        assert!(geocode.cells_area(&["dpa".to_string()]).is_err());
+
+        // overlapping sets are counted once across the union
+        let other = CellSet::from_cells(geocode,
+            vec!["dpc".to_string(), "dr".to_string()]).unwrap();
+        let union_area = cell_set.union_area(&other).unwrap();
+        assert!((union_area - expected - geocode.cell_area("dr").unwrap())
+            .abs() < 1.0);
+        assert!(matches!(cell_set.union_area(&CellSet::new(
+            Geocode::QuadTile)), Err(GeocodeError::GeocodeMismatch { .. })));
+    }
+
+    #[test]
+    fn distortion() {
+        // geohash cells with odd precisions are square at the equator
+        let distortion = Geocode::Geohash.distortion(5, -1.0, 1.0).unwrap();
+        assert!((distortion.max_aspect_ratio - 1f64).abs() < 1e-3);
+        assert!((distortion.max_edge / 4886.50 - 1f64).abs() < 1e-3);
+        assert!(distortion.area_variation < 1.001);
+
+        // cells narrow toward the poles
+        let distortion = Geocode::Geohash.distortion(6, 0.0, 60.0).unwrap();
+        assert!((distortion.max_aspect_ratio / distortion.min_aspect_ratio
+            - 2f64).abs() < 0.01);
+        assert!(distortion.area_variation > 1.99);
+        assert!(distortion.min_edge < distortion.max_edge / 1.99);
+
+        // mercator cells remain square though their areas vary
+        let distortion = Geocode::QuadTile.distortion(12, 0.0, 80.0).unwrap();
+        assert!((distortion.min_aspect_ratio - 1f64).abs() < 0.01);
+        assert!((distortion.max_aspect_ratio - 1f64).abs() < 0.01);
+        assert!(distortion.area_variation > 30.0);
+
+        assert!(Geocode::QuadTile.distortion(12, 0.0, 91.0).is_err());
+        assert!(Geocode::Geohash.distortion(6, 10.0, 0.0).is_err());
+        assert!(Geocode::Geohash.distortion(0, 0.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn cell_perimeter() {
+        // a one degree cell at the equator is roughly 111km per side
+        let geocode = Geocode::PlusCode;
+        let perimeter = geocode.cell_perimeter("6FG20000+").unwrap();
+        assert!((perimeter / 1000f64 - 444.76).abs() < 0.01);
+
+        // mercator cells are measured on the sphere
+        let perimeter = Geocode::QuadTile.cell_perimeter("0").unwrap();
+        let expected = EARTH_MEAN_RADIUS * (2f64
+            * 85.05112877980659f64.to_radians() + PI * (1f64
+            + 85.05112877980659f64.to_radians().cos()));
+        assert!((perimeter - expected).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{Geocode, GeocodeError};
+use crate::error::io_error;
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::io::Write;
+
+const MAGIC: &[u8; 2] = b"GI";
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 16;
+
+// indexes hold a header (magic, version, geocode, and cell count), the
+// sorted little-endian cell ids, and count + 1 offsets delimiting each
+// cell payload, so lookups search the mapped bytes in place
+pub struct MappedIndexWriter {
+    geocode: Geocode,
+    cells: BTreeMap<u64, Vec<u8>>,
+}
+
+impl MappedIndexWriter {
+    pub fn new(geocode: Geocode) -> Result<MappedIndexWriter, GeocodeError> {
+        geocode.get_id_parameters()?;
+        Ok(MappedIndexWriter { geocode, cells: BTreeMap::new() })
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn insert(&mut self, value: &str, payload: &[u8])
+            -> Result<(), GeocodeError> {
+        // payloads of duplicate cells are replaced
+        let id = self.geocode.code_to_u64(value)?;
+        self.cells.insert(id, payload.to_vec());
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<W, GeocodeError> {
+        let index = Geocode::ALL.iter()
+            .position(|x| *x == self.geocode).unwrap();
+        let mut header = [0u8; HEADER_LENGTH];
+        header[..2].copy_from_slice(MAGIC);
+        header[2] = VERSION;
+        header[3] = index as u8;
+        header[8..].copy_from_slice(&(self.cells.len() as u64).to_le_bytes());
+        writer.write_all(&header).map_err(io_error)?;
+
+        for id in self.cells.keys() {
+            writer.write_all(&id.to_le_bytes()).map_err(io_error)?;
+        }
+
+        let mut offset = 0u64;
+        writer.write_all(&offset.to_le_bytes()).map_err(io_error)?;
+        for payload in self.cells.values() {
+            offset += payload.len() as u64;
+            writer.write_all(&offset.to_le_bytes()).map_err(io_error)?;
+        }
+
+        for payload in self.cells.values() {
+            writer.write_all(payload).map_err(io_error)?;
+        }
+
+        writer.flush().map_err(io_error)?;
+        Ok(writer)
+    }
+}
+
+// readers borrow any byte buffer, including a memory map of the file, and
+// only touch the pages a lookup visits
+pub struct MappedIndex<B: AsRef<[u8]>> {
+    bytes: B,
+    geocode: Geocode,
+    len: usize,
+}
+
+impl<B: AsRef<[u8]>> MappedIndex<B> {
+    pub fn new(bytes: B) -> Result<MappedIndex<B>, GeocodeError> {
+        let data = bytes.as_ref();
+        if data.len() < HEADER_LENGTH || &data[..2] != MAGIC
+                || data[2] != VERSION {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "unrecognized cell index header".to_string() });
+        }
+
+        let geocode = *Geocode::ALL.get(data[3] as usize)
+            .ok_or_else(|| GeocodeError::InvalidFormat {
+                reason: format!("unknown geocode index {}", data[3]) })?;
+
+        // check the ids, offsets, and payloads span the buffer
+        let len = read_u64(data, 8) as usize;
+        let payload_start = len.checked_mul(16)
+            .and_then(|x| x.checked_add(HEADER_LENGTH + 8))
+            .filter(|x| *x <= data.len())
+            .ok_or_else(|| GeocodeError::InvalidFormat {
+                reason: format!("cell index of {} cells is truncated", len) })?;
+        let payload_len = read_u64(data, payload_start - 8);
+        if payload_len != (data.len() - payload_start) as u64 {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "cell index payload length mismatch".to_string() });
+        }
+
+        Ok(MappedIndex { bytes, geocode, len })
+    }
+
+    pub fn contains(&self, value: &str) -> Result<bool, GeocodeError> {
+        // check if the cell or any ancestor is indexed
+        self.geocode.code_to_u64(value)?;
+        for precision in 1..=value.chars().count() {
+            let prefix: String = value.chars().take(precision).collect();
+            let id = self.geocode.code_to_u64(&prefix)?;
+            if self.find(id).is_some() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn get(&self, value: &str) -> Result<Option<&[u8]>, GeocodeError> {
+        let id = self.geocode.code_to_u64(value)?;
+        match self.find(id) {
+            Some(index) => self.get_payload(index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> MappedEntries<'_, B> {
+        MappedEntries { index: self, position: 0, end: self.len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn prefix(&self, value: &str)
+            -> Result<MappedEntries<'_, B>, GeocodeError> {
+        // descendants share the leading bits of the cell, so they follow
+        // it up to the id of the next cell at the same precision
+        let id = self.geocode.code_to_u64(value)?;
+        let (char_bits, _) = self.geocode.get_id_parameters()?;
+        let shift = 64 - value.chars().count() * char_bits;
+        let end = match ((id >> shift) + 1).checked_shl(shift as u32) {
+            Some(next) if next >> shift != 0 => self.lower_bound(next),
+            _ => self.len,
+        };
+
+        Ok(MappedEntries { index: self, position: self.lower_bound(id), end })
+    }
+
+    pub fn range(&self, start: &str, end: &str)
+            -> Result<MappedEntries<'_, B>, GeocodeError> {
+        // cells ordered between the bounds, inclusive of both
+        let start = self.geocode.code_to_u64(start)?;
+        let end = self.geocode.code_to_u64(end)?;
+        let position = self.lower_bound(start);
+        let end = match end.checked_add(1) {
+            Some(end) => self.lower_bound(end),
+            None => self.len,
+        };
+
+        Ok(MappedEntries { index: self, position, end: end.max(position) })
+    }
+
+    fn find(&self, id: u64) -> Option<usize> {
+        let index = self.lower_bound(id);
+        match index < self.len && self.get_id(index) == id {
+            true => Some(index),
+            false => None,
+        }
+    }
+
+    fn get_id(&self, index: usize) -> u64 {
+        read_u64(self.bytes.as_ref(), HEADER_LENGTH + index * 8)
+    }
+
+    fn get_payload(&self, index: usize) -> Result<&[u8], GeocodeError> {
+        // offsets are checked on access rather than when opening
+        let data = self.bytes.as_ref();
+        let offsets = HEADER_LENGTH + self.len * 8;
+        let payload_start = offsets + (self.len + 1) * 8;
+        let start = read_u64(data, offsets + index * 8);
+        let end = read_u64(data, offsets + (index + 1) * 8);
+        if start > end || end > (data.len() - payload_start) as u64 {
+            return Err(GeocodeError::InvalidFormat {
+                reason: format!("invalid payload offsets for cell {}",
+                    index) });
+        }
+
+        Ok(&data[payload_start + start as usize
+            ..payload_start + end as usize])
+    }
+
+    fn lower_bound(&self, id: u64) -> usize {
+        // find the first position with an id no less than the target
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.get_id(middle) < id {
+                true => low = middle + 1,
+                false => high = middle,
+            }
+        }
+
+        low
+    }
+}
+
+pub struct MappedEntries<'a, B: AsRef<[u8]>> {
+    index: &'a MappedIndex<B>,
+    position: usize,
+    end: usize,
+}
+
+impl<'a, B: AsRef<[u8]>> Iterator for MappedEntries<'a, B> {
+    type Item = Result<(String, &'a [u8]), GeocodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.end {
+            return None;
+        }
+
+        let index = self.index;
+        let position = self.position;
+        self.position += 1;
+
+        let code = index.geocode.u64_to_code(index.get_id(position));
+        Some(code.and_then(|x| Ok((x, index.get_payload(position)?))))
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::{MappedIndex, MappedIndexWriter};
+
+    fn index() -> MappedIndex<Vec<u8>> {
+        let mut writer = MappedIndexWriter::new(Geocode::Geohash).unwrap();
+        for (code, payload) in [("dpc5", "a"), ("dpc5u6", "b"), ("dpc6", ""),
+                ("9xjq", "c"), ("dpc", "d"), ("zz", "e")].iter() {
+            writer.insert(code, payload.as_bytes()).unwrap();
+        }
+
+        assert_eq!(writer.len(), 6);
+        MappedIndex::new(writer.write(Vec::new()).unwrap()).unwrap()
+    }
+
+    fn codes(entries: super::MappedEntries<'_, Vec<u8>>) -> Vec<String> {
+        entries.map(|x| x.unwrap().0).collect()
+    }
+
+    #[test]
+    fn get() {
+        let index = index();
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.get_geocode(), Geocode::Geohash);
+        assert_eq!(index.get("dpc5u6").unwrap(), Some(&b"b"[..]));
+        assert_eq!(index.get("dpc6").unwrap(), Some(&b""[..]));
+        assert_eq!(index.get("dpc5u").unwrap(), None);
+        assert!(index.get("dpa").is_err());
+
+        assert!(index.contains("dpc5u6gh").unwrap());
+        assert!(index.contains("dpcz").unwrap());
+        assert!(!index.contains("dp").unwrap());
+        assert!(!index.contains("9xjr").unwrap());
+
+        assert_eq!(codes(index.iter()),
+            vec!["9xjq", "dpc", "dpc5", "dpc5u6", "dpc6", "zz"]);
+    }
+
+    #[test]
+    fn new() {
+        let bytes = index().bytes;
+        assert!(MappedIndex::new(&bytes[..bytes.len() - 1]).is_err());
+        assert!(MappedIndex::new(&bytes[..20]).is_err());
+        assert!(MappedIndex::new(&b"XX\x01\x00"[..]).is_err());
+
+        let empty = MappedIndexWriter::new(Geocode::QuadTile).unwrap()
+            .write(Vec::new()).unwrap();
+        let index = MappedIndex::new(&empty[..]).unwrap();
+        assert!(index.is_empty());
+        assert_eq!(index.iter().count(), 0);
+
+        assert!(matches!(MappedIndexWriter::new(Geocode::PlusCode),
+            Err(GeocodeError::UnsupportedGeocode { .. })));
+    }
+
+    #[test]
+    fn prefix() {
+        let index = index();
+        assert_eq!(codes(index.prefix("dpc5").unwrap()),
+            vec!["dpc5", "dpc5u6"]);
+        assert_eq!(codes(index.prefix("dp").unwrap()),
+            vec!["dpc", "dpc5", "dpc5u6", "dpc6"]);
+        assert_eq!(codes(index.prefix("z").unwrap()), vec!["zz"]);
+        assert!(index.prefix("b").unwrap().next().is_none());
+
+        let entries: Vec<(String, &[u8])> = index.prefix("dpc5").unwrap()
+            .map(|x| x.unwrap()).collect();
+        assert_eq!(entries[1].1, b"b");
+    }
+
+    #[test]
+    fn range() {
+        let index = index();
+        assert_eq!(codes(index.range("dpc", "dpc5u6").unwrap()),
+            vec!["dpc", "dpc5", "dpc5u6"]);
+        assert_eq!(codes(index.range("b", "zzzz").unwrap()),
+            vec!["dpc", "dpc5", "dpc5u6", "dpc6", "zz"]);
+        assert!(index.range("zz", "dpc").unwrap().next().is_none());
+    }
+}
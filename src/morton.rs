@@ -0,0 +1,173 @@
+use crate::{math, BoundingBox, Geocode, GeocodeError};
+
+pub fn decode(value: u64, bounds: &BoundingBox, bits: usize)
+        -> Result<BoundingBox, GeocodeError> {
+    // check if bits and value are valid
+    if bits == 0 || bits > 32 {
+        return Err(GeocodeError::InvalidPrecision { precision: bits });
+    } else if bits < 32 && value >> (2 * bits) != 0 {
+        return Err(GeocodeError::InvalidCellId { id: value });
+    }
+
+    // compute cell bounds from the cell indices
+    let (x, y) = deinterleave(value);
+    Ok(get_cell(bounds, x, y, bits, bits))
+}
+
+pub fn deinterleave(value: u64) -> (u32, u32) {
+    (compact(value >> 1), compact(value))
+}
+
+pub fn encode(x: f64, y: f64, bounds: &BoundingBox, bits: usize)
+        -> Result<u64, GeocodeError> {
+    // check if coordinates and bits are valid
+    if !bounds.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: *bounds });
+    } else if bits == 0 || bits > 32 {
+        return Err(GeocodeError::InvalidPrecision { precision: bits });
+    }
+
+    Ok(interleave(get_index(x, bounds.min_x, bounds.width(), bits),
+        get_index(y, bounds.min_y, bounds.height(), bits)))
+}
+
+pub(crate) fn get_cell(bounds: &BoundingBox, x: u32, y: u32,
+        x_bits: usize, y_bits: usize) -> BoundingBox {
+    let width = bounds.width() / math::exp2(x_bits);
+    let height = bounds.height() / math::exp2(y_bits);
+    BoundingBox::new(bounds.min_x + x as f64 * width,
+        bounds.min_x + (x as f64 + 1f64) * width,
+        bounds.min_y + y as f64 * height,
+        bounds.min_y + (y as f64 + 1f64) * height)
+}
+
+pub(crate) const fn correct_index(value: f64, min: f64, range: f64,
+        bits: usize, mut index: u32) -> u32 {
+    // correct rounding error against the edges computed by get_cell
+    let cells = math::exp2(bits);
+    let interval = range / cells;
+    while index > 0 && value <= min + index as f64 * interval {
+        index -= 1;
+    }
+
+    while (index as f64) < cells - 1f64
+            && value > min + (index as f64 + 1f64) * interval {
+        index += 1;
+    }
+
+    index
+}
+
+pub(crate) const fn get_index(value: f64, min: f64, range: f64,
+        bits: usize) -> u32 {
+    // compute cell index, assigning coordinates on a cell edge to the
+    // lower cell to match bisection encoding
+    let cells = math::exp2(bits);
+    let scaled = (value - min) / range * cells;
+    let truncated = scaled as u64 as f64;
+    let index = match truncated < scaled {
+        true => truncated,
+        false => truncated - 1f64,
+    };
+
+    let index = if index < 0f64 {
+        0f64
+    } else if index > cells - 1f64 {
+        cells - 1f64
+    } else {
+        index
+    };
+
+    correct_index(value, min, range, bits, index as u32)
+}
+
+pub const fn interleave(x: u32, y: u32) -> u64 {
+    (spread(x) << 1) | spread(y)
+}
+
+pub(crate) const fn compact(value: u64) -> u32 {
+    let mut value = value & 0x5555555555555555;
+    value = (value | (value >> 1)) & 0x3333333333333333;
+    value = (value | (value >> 2)) & 0x0f0f0f0f0f0f0f0f;
+    value = (value | (value >> 4)) & 0x00ff00ff00ff00ff;
+    value = (value | (value >> 8)) & 0x0000ffff0000ffff;
+    value = (value | (value >> 16)) & 0x00000000ffffffff;
+    value as u32
+}
+
+pub(crate) const fn spread(value: u32) -> u64 {
+    let mut value = value as u64;
+    value = (value | (value << 16)) & 0x0000ffff0000ffff;
+    value = (value | (value << 8)) & 0x00ff00ff00ff00ff;
+    value = (value | (value << 4)) & 0x0f0f0f0f0f0f0f0f;
+    value = (value | (value << 2)) & 0x3333333333333333;
+    (value | (value << 1)) & 0x5555555555555555
+}
+
+impl Geocode {
+    pub fn decode_morton(&self, value: u64, bits: usize)
+            -> Result<BoundingBox, GeocodeError> {
+        let bounds = self.get_bounds();
+        decode(value, &bounds, bits)
+    }
+
+    pub fn encode_morton(&self, x: f64, y: f64, bits: usize)
+            -> Result<u64, GeocodeError> {
+        let bounds = self.get_bounds();
+        encode(x, y, &bounds, bits)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+
+    #[test]
+    fn interleave() {
+        assert_eq!(super::interleave(0b11, 0b00), 0b1010);
+        assert_eq!(super::interleave(0b01, 0b10), 0b0110);
+        assert_eq!(super::interleave(u32::MAX, u32::MAX), u64::MAX);
+        assert_eq!(super::deinterleave(0b0110), (0b01, 0b10));
+
+        let (x, y) = super::deinterleave(
+            super::interleave(0xdeadbeef, 0x01234567));
+        assert_eq!((x, y), (0xdeadbeef, 0x01234567));
+    }
+
+    #[test]
+    fn encode() {
+        let bounds = BoundingBox::new(0.0, 4.0, 0.0, 4.0);
+        assert_eq!(super::encode(0.5, 3.5, &bounds, 2).unwrap(), 0b0101);
+        assert_eq!(super::encode(4.0, 4.0, &bounds, 2).unwrap(), 0b1111);
+        assert_eq!(super::encode(0.0, 0.0, &bounds, 2).unwrap(), 0b0000);
+
+        // coordinates on cell edges belong to the lower cell
+        assert_eq!(super::encode(2.0, 2.0, &bounds, 1).unwrap(), 0b00);
+
+        assert!(super::encode(5.0, 0.0, &bounds, 2).is_err());
+        assert_eq!(super::encode(1.0, 1.0, &bounds, 33),
+            Err(GeocodeError::InvalidPrecision { precision: 33 }));
+    }
+
+    #[test]
+    fn decode() {
+        let bounds = BoundingBox::new(0.0, 4.0, 0.0, 4.0);
+        assert_eq!(super::decode(0b0101, &bounds, 2).unwrap(),
+            BoundingBox::new(0.0, 1.0, 3.0, 4.0));
+        assert_eq!(super::decode(0b10000, &bounds, 2),
+            Err(GeocodeError::InvalidCellId { id: 0b10000 }));
+    }
+
+    #[test]
+    fn geohash_compatibility() {
+        // morton values share bits with geohash codes
+        let geocode = Geocode::Geohash;
+        let value = geocode.encode_morton(-88.4, 44.266667, 15).unwrap();
+        let id = geocode.code_to_u64("dpc5u6").unwrap();
+        assert_eq!(value, id >> 34);
+
+        assert_eq!(geocode.decode_morton(value, 15).unwrap(),
+            geocode.decode("dpc5u6").unwrap());
+    }
+}
@@ -0,0 +1,220 @@
+#[cfg(feature = "alloc")]
+use crate::{Geocode, GeocodeError, RangePolicy};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [Direction::North, Direction::NorthEast,
+        Direction::East, Direction::SouthEast, Direction::South,
+        Direction::SouthWest, Direction::West, Direction::NorthWest];
+
+    pub fn get_offsets(&self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, 1),
+            Direction::NorthEast => (1, 1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, -1),
+            Direction::South => (0, -1),
+            Direction::SouthWest => (-1, -1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, 1),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PolePolicy {
+    Error,
+    Saturate,
+}
+
+#[cfg(feature = "alloc")]
+impl Geocode {
+    pub fn neighbor(&self, value: &str, direction: Direction)
+            -> Result<String, GeocodeError> {
+        self.neighbor_with_policy(value, direction, PolePolicy::Error)
+    }
+
+    pub fn neighbor_with_policy(&self, value: &str, direction: Direction,
+            policy: PolePolicy) -> Result<String, GeocodeError> {
+        // compute center of the neighboring cell
+        let bbox = self.decode(value)?;
+        let (x, y) = bbox.center();
+        let (x_offset, y_offset) = direction.get_offsets();
+
+        let x = x + (x_offset as f64 * bbox.width());
+        let mut y = y + (y_offset as f64 * bbox.height());
+
+        // cells touching a pole either have no neighbor beyond it or
+        // saturate to the polar row
+        let bounds = self.get_bounds();
+        if y < bounds.min_y || y > bounds.max_y {
+            match policy {
+                PolePolicy::Error =>
+                    return Err(GeocodeError::PoleCrossing { direction }),
+                PolePolicy::Saturate => y = y.clamp(bounds.min_y,
+                    bounds.max_y),
+            }
+        }
+
+        // east and west neighbors wrap across the antimeridian
+        self.encode_with_policy(x, y,
+            self.get_precision(value)?, RangePolicy::Wrap)
+    }
+
+    pub fn neighbors(&self, value: &str)
+            -> Result<Vec<String>, GeocodeError> {
+        let mut neighbors = Vec::with_capacity(Direction::ALL.len());
+        for direction in Direction::ALL.iter() {
+            // skip neighbors which fall outside of the geocode range and
+            // duplicates where wrapped neighbors coincide
+            match self.neighbor(value, *direction) {
+                Ok(neighbor) if neighbor == value
+                    || neighbors.contains(&neighbor) => {},
+                Ok(neighbor) => neighbors.push(neighbor),
+                Err(GeocodeError::PoleCrossing { .. }) => {},
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(neighbors)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::{Direction, PolePolicy};
+
+    #[test]
+    fn geohash_neighbor() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::North).unwrap(),
+            "dpc5u7");
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::East).unwrap(),
+            "dpc5ud");
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::South).unwrap(),
+            "dpc5u3");
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::West).unwrap(),
+            "dpc5u4");
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::NorthEast).unwrap(),
+            "dpc5ue");
+        assert_eq!(geocode.neighbor("dpc5u6", Direction::SouthWest).unwrap(),
+            "dpc5u1");
+
+        // check neighbors across parent cell boundaries
+        assert_eq!(geocode.neighbor("dpc5u", Direction::East).unwrap(),
+            "dpc5v");
+        assert_eq!(geocode.neighbor("dpc5u", Direction::North).unwrap(),
+            "dpchh");
+
+        assert_eq!(geocode.neighbor("", Direction::North),
+            Err(GeocodeError::EmptyCode));
+    }
+
+    #[test]
+    fn neighbors() {
+        for geocode in [Geocode::Geohash, Geocode::Geohash16,
+                Geocode::Geohash36, Geocode::Maidenhead,
+                Geocode::PlusCode, Geocode::QuadTile].iter() {
+            let code = match geocode {
+                Geocode::Geohash => "dpc5",
+                Geocode::Geohash16 => "6556",
+                Geocode::Geohash36 => "bdrd",
+                Geocode::Maidenhead => "EN54cq",
+                Geocode::PlusCode => "86HJ7JR9+",
+                Geocode::QuadTile => "0302",
+            };
+
+            let bbox = geocode.decode(code).unwrap();
+            let neighbors = geocode.neighbors(code).unwrap();
+            assert_eq!(neighbors.len(), 8);
+
+            // check each neighbor touches the original cell
+            for neighbor in neighbors.iter() {
+                assert_eq!(neighbor.len(), code.len());
+                assert_ne!(neighbor, code);
+
+                let neighbor_bbox = geocode.decode(neighbor).unwrap();
+                assert!(bbox.intersects(&neighbor_bbox));
+            }
+        }
+    }
+
+    #[test]
+    fn neighbors_edge() {
+        // corner cells only have three neighbors
+        let geocode = Geocode::QuadTile;
+        let neighbors = geocode.neighbors("0").unwrap();
+        assert_eq!(neighbors, vec!["1", "3", "2"]);
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.neighbors("0").unwrap().len(), 5);
+        assert_eq!(geocode.neighbor("0", Direction::South),
+            Err(GeocodeError::PoleCrossing { direction: Direction::South }));
+    }
+
+    #[test]
+    fn neighbors_pole() {
+        let geocode = Geocode::Geohash;
+        let code = geocode.encode(10.0, 89.99, 5).unwrap();
+        let east = geocode.neighbor(&code, Direction::East).unwrap();
+        assert_eq!(geocode.neighbor(&code, Direction::North),
+            Err(GeocodeError::PoleCrossing { direction: Direction::North }));
+        assert_eq!(geocode.neighbor(&code, Direction::NorthEast),
+            Err(GeocodeError::PoleCrossing {
+                direction: Direction::NorthEast }));
+        assert_eq!(geocode.neighbors(&code).unwrap().len(), 5);
+
+        // saturated neighbors remain within the polar row
+        assert_eq!(geocode.neighbor_with_policy(&code, Direction::North,
+            PolePolicy::Saturate).unwrap(), code);
+        assert_eq!(geocode.neighbor_with_policy(&code, Direction::NorthEast,
+            PolePolicy::Saturate).unwrap(), east);
+
+        let code = geocode.encode(10.0, -89.99, 5).unwrap();
+        assert_eq!(geocode.neighbor_with_policy(&code, Direction::South,
+            PolePolicy::Saturate).unwrap(), code);
+        assert_eq!(geocode.neighbor_with_policy(&code, Direction::North,
+            PolePolicy::Saturate), geocode.neighbor(&code, Direction::North));
+
+        // quadtile cells saturate at the mercator latitude limit
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.neighbor_with_policy("0", Direction::North,
+            PolePolicy::Saturate).unwrap(), "0");
+        assert!(geocode.neighbor("0", Direction::North).is_err());
+    }
+
+    #[test]
+    fn neighbors_antimeridian() {
+        // east and west neighbors wrap across the antimeridian
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.neighbor("0", Direction::West).unwrap(), "p");
+        assert_eq!(geocode.neighbor("p", Direction::East).unwrap(), "0");
+        assert_eq!(geocode.neighbor("0", Direction::NorthWest).unwrap(), "r");
+
+        let code = geocode.encode(179.99, 10.0, 6).unwrap();
+        let west = geocode.encode(-179.99, 10.0, 6).unwrap();
+        assert_eq!(geocode.neighbor(&code, Direction::East).unwrap(), west);
+        assert_eq!(geocode.neighbor(&west, Direction::West).unwrap(), code);
+        assert_eq!(geocode.neighbors(&code).unwrap().len(), 8);
+
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.neighbor("1", Direction::East).unwrap(), "0");
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use napi::{Error, Result};
+use napi_derive::napi;
+
+// napi only exports c-style enums, so geocodes are mirrored here
+#[napi]
+#[derive(Debug, Eq, PartialEq)]
+pub enum GeocodeType {
+    Geohash,
+    Geohash16,
+    Geohash36,
+    Maidenhead,
+    PlusCode,
+    QuadTile,
+}
+
+impl From<GeocodeType> for Geocode {
+    fn from(geocode_type: GeocodeType) -> Geocode {
+        match geocode_type {
+            GeocodeType::Geohash => Geocode::Geohash,
+            GeocodeType::Geohash16 => Geocode::Geohash16,
+            GeocodeType::Geohash36 => Geocode::Geohash36,
+            GeocodeType::Maidenhead => Geocode::Maidenhead,
+            GeocodeType::PlusCode => Geocode::PlusCode,
+            GeocodeType::QuadTile => Geocode::QuadTile,
+        }
+    }
+}
+
+// exported as { minX, maxX, minY, maxY } javascript objects
+#[napi(object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+}
+
+impl From<BoundingBox> for Bounds {
+    fn from(bbox: BoundingBox) -> Bounds {
+        Bounds { min_x: bbox.min_x, max_x: bbox.max_x,
+            min_y: bbox.min_y, max_y: bbox.max_y }
+    }
+}
+
+#[napi]
+pub fn cover_bbox(geocode: GeocodeType, bounds: Bounds, precision: u32)
+        -> Result<Vec<String>> {
+    let bbox = BoundingBox::new(bounds.min_x, bounds.max_x,
+        bounds.min_y, bounds.max_y);
+    Geocode::from(geocode).cover_bbox(&bbox, precision as usize)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn cover_radius(geocode: GeocodeType, x: f64, y: f64, radius: f64,
+        precision: u32) -> Result<Vec<String>> {
+    Geocode::from(geocode).cover_radius(x, y, radius, precision as usize)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn decode(geocode: GeocodeType, value: String) -> Result<Bounds> {
+    Geocode::from(geocode).decode(&value).map(Bounds::from)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn encode(geocode: GeocodeType, x: f64, y: f64, precision: u32)
+        -> Result<String> {
+    Geocode::from(geocode).encode(x, y, precision as usize)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn neighbors(geocode: GeocodeType, value: String)
+        -> Result<Vec<String>> {
+    Geocode::from(geocode).neighbors(&value).map_err(to_napi_error)
+}
+
+fn to_napi_error(e: GeocodeError) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode};
+    use super::{Bounds, GeocodeType};
+
+    #[test]
+    fn encode() {
+        assert_eq!(super::encode(GeocodeType::Geohash, -88.4, 44.266667, 6)
+            .unwrap(), "dpc5u6");
+        assert_eq!(super::decode(GeocodeType::Geohash, "dpc5u6".to_string())
+            .unwrap(), Bounds::from(Geocode::Geohash.decode("dpc5u6")
+                .unwrap()));
+
+        // errors carry the geocode error message
+        let e = super::decode(GeocodeType::Geohash, "dpca".to_string())
+            .unwrap_err();
+        assert_eq!(e.reason, "invalid character 'a' at index 3");
+    }
+
+    #[test]
+    fn cover() {
+        assert_eq!(super::neighbors(GeocodeType::QuadTile, "0302".to_string())
+            .unwrap(), Geocode::QuadTile.neighbors("0302").unwrap());
+
+        let bbox = BoundingBox::new(-89.0, -88.0, 44.0, 45.0);
+        assert_eq!(super::cover_bbox(GeocodeType::Geohash,
+            Bounds::from(bbox), 4).unwrap(),
+            Geocode::Geohash.cover_bbox(&bbox, 4).unwrap());
+        assert_eq!(super::cover_radius(GeocodeType::Geohash, -88.4,
+            44.266667, 1000.0, 6).unwrap(),
+            Geocode::Geohash.cover_radius(-88.4, 44.266667, 1000.0, 6)
+                .unwrap());
+    }
+}
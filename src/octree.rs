@@ -0,0 +1,224 @@
+use crate::{BoundingVolume, GeocodeError};
+use crate::volume::{decode_volume, encode_volume};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const MAX_DEPTH: usize = 32;
+// ordered by bisection value where each digit holds an x, y, and z bit
+static OCTREE_CHARS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7'];
+
+// an octree over a projected extent, addressing volumes by one octal digit
+// per level in the manner of quadtiles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Octree {
+    bounds: BoundingVolume,
+}
+
+impl Octree {
+    pub fn new(bounds: BoundingVolume) -> Result<Octree, GeocodeError> {
+        // check if the extent is valid
+        if !(bounds.width() > 0f64 && bounds.height() > 0f64
+                && bounds.depth() > 0f64) {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "octree extent must have positive size".to_string() });
+        }
+
+        Ok(Octree { bounds })
+    }
+
+    pub fn children(&self, value: &str) -> Result<Vec<String>, GeocodeError> {
+        let (_, precision) = self.get_indices(value)?;
+        if precision >= MAX_DEPTH {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: precision + 1 });
+        }
+
+        Ok(OCTREE_CHARS.iter().map(|c| {
+            let mut child = value.to_string();
+            child.push(*c);
+            child
+        }).collect())
+    }
+
+    pub fn decode(&self, value: &str)
+            -> Result<BoundingVolume, GeocodeError> {
+        self.get_indices(value)?;
+        decode_volume(&self.bounds, 3, OCTREE_CHARS, value)
+    }
+
+    pub fn encode(&self, x: f64, y: f64, z: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        if precision > MAX_DEPTH {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        encode_volume(&self.bounds, [x, y, z], precision, 3, OCTREE_CHARS)
+    }
+
+    pub fn get_bounds(&self) -> BoundingVolume {
+        self.bounds
+    }
+
+    pub fn neighbor(&self, value: &str, offset: (i64, i64, i64))
+            -> Result<Option<String>, GeocodeError> {
+        // offset the cell indices, where cells beyond the extent are absent
+        let (indices, precision) = self.get_indices(value)?;
+        let count = 1i64 << precision;
+        let mut neighbor = [0u32; 3];
+        for (i, delta) in [offset.0, offset.1, offset.2].iter().enumerate() {
+            let index = indices[i] as i64 + delta;
+            if index < 0 || index >= count {
+                return Ok(None);
+            }
+
+            neighbor[i] = index as u32;
+        }
+
+        Ok(Some(format_indices(&neighbor, precision)))
+    }
+
+    pub fn neighbors(&self, value: &str)
+            -> Result<Vec<String>, GeocodeError> {
+        // face, edge, and corner neighbors within the extent
+        let mut neighbors = Vec::with_capacity(26);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if (dx, dy, dz) == (0, 0, 0) {
+                        continue;
+                    }
+
+                    if let Some(neighbor) =
+                            self.neighbor(value, (dx, dy, dz))? {
+                        neighbors.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        Ok(neighbors)
+    }
+
+    pub fn parent(&self, value: &str) -> Result<String, GeocodeError> {
+        let (_, precision) = self.get_indices(value)?;
+        match precision {
+            1 => Err(GeocodeError::InvalidPrecision { precision: 0 }),
+            _ => Ok(value[..precision - 1].to_string()),
+        }
+    }
+
+    fn get_indices(&self, value: &str)
+            -> Result<([u32; 3], usize), GeocodeError> {
+        // check if code is valid
+        let precision = value.chars().count();
+        if precision == 0 {
+            return Err(GeocodeError::EmptyCode);
+        } else if precision > MAX_DEPTH {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // each digit contributes one bit to the x, y, and z indices
+        let mut indices = [0u32; 3];
+        for (index, c) in value.chars().enumerate() {
+            let digit = match c.to_digit(8) {
+                Some(digit) => digit,
+                None => return Err(GeocodeError::InvalidCharacter {
+                    ch: c, index }),
+            };
+
+            for (i, value) in indices.iter_mut().enumerate() {
+                *value = (*value << 1) | ((digit >> (2 - i)) & 1);
+            }
+        }
+
+        Ok((indices, precision))
+    }
+}
+
+fn format_indices(indices: &[u32; 3], precision: usize) -> String {
+    (0..precision).rev().map(|shift| {
+        let digit = indices.iter().fold(0, |digit, x|
+            (digit << 1) | ((x >> shift) & 1));
+        OCTREE_CHARS[digit as usize]
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingVolume, GeocodeError};
+    use super::Octree;
+
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn get_octree() -> Octree {
+        Octree::new(BoundingVolume::new(0.0, 1024.0, 0.0, 1024.0,
+            -512.0, 512.0)).unwrap()
+    }
+
+    #[test]
+    fn encode_decode() {
+        let octree = get_octree();
+        assert_eq!(octree.encode(900.0, 100.0, 256.0, 1).unwrap(), "5");
+        assert_eq!(octree.decode("5").unwrap(), BoundingVolume::new(
+            512.0, 1024.0, 0.0, 512.0, 0.0, 512.0));
+
+        let code = octree.encode(123.4, 567.8, -91.0, 10).unwrap();
+        let volume = octree.decode(&code).unwrap();
+        assert!(volume.contains(123.4, 567.8, -91.0));
+        assert_eq!((volume.width(), volume.height(), volume.depth()),
+            (1.0, 1.0, 1.0));
+
+        assert!(octree.encode(123.4, 567.8, -91.0, 33).is_err());
+        assert!(octree.encode(123.4, 567.8, 600.0, 4).is_err());
+        assert_eq!(octree.decode("58"), Err(GeocodeError::InvalidCharacter {
+            ch: '8', index: 1 }));
+        assert!(Octree::new(BoundingVolume::new(0.0, 1.0, 0.0, 1.0,
+            0.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn hierarchy() {
+        let octree = get_octree();
+        let children = octree.children("52").unwrap();
+        assert_eq!(children.len(), 8);
+        assert_eq!(children[0], "520");
+        assert_eq!(children[7], "527");
+        for child in children.iter() {
+            assert_eq!(octree.parent(child).unwrap(), "52");
+        }
+
+        assert_eq!(octree.parent("5"),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+        assert!(octree.children(&"0".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn neighbors() {
+        let octree = get_octree();
+
+        // neighbors share a face, edge, or corner with the cell
+        let volume = octree.decode("5252").unwrap();
+        let neighbors = octree.neighbors("5252").unwrap();
+        assert_eq!(neighbors.len(), 26);
+        for neighbor in neighbors.iter() {
+            let other = octree.decode(neighbor).unwrap();
+            assert!(other.min_x <= volume.max_x && other.max_x >= volume.min_x
+                && other.min_y <= volume.max_y && other.max_y >= volume.min_y
+                && other.min_z <= volume.max_z
+                && other.max_z >= volume.min_z);
+        }
+
+        assert_eq!(octree.neighbor("5", (-1, 0, 0)).unwrap(),
+            Some("1".to_string()));
+        assert_eq!(octree.neighbor("1", (0, 1, -1)).unwrap(),
+            Some("2".to_string()));
+        assert_eq!(octree.neighbor("5", (1, 0, 0)).unwrap(), None);
+
+        // corner cells only have neighbors within the extent
+        let corners: Vec<String> = octree.neighbors("000").unwrap();
+        assert_eq!(corners.len(), 7);
+        assert!(octree.neighbors("").is_err());
+    }
+}
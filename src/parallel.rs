@@ -0,0 +1,55 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use rayon::prelude::*;
+
+// points are split into chunks to amortize per task overhead
+const CHUNK_SIZE: usize = 4096;
+
+impl Geocode {
+    pub fn par_decode_batch<T: AsRef<str> + Sync>(&self, values: &[T])
+            -> Result<Vec<BoundingBox>, GeocodeError> {
+        values.par_iter().map(|x| self.decode(x.as_ref())).collect()
+    }
+
+    pub fn par_encode_batch(&self, points: &[(f64, f64)], precision: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        let chunks = points.par_chunks(CHUNK_SIZE)
+            .map(|x| self.encode_batch(x, precision))
+            .collect::<Result<Vec<Vec<String>>, GeocodeError>>()?;
+
+        Ok(chunks.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn par_decode_batch() {
+        let geocode = Geocode::Geohash;
+        let codes: Vec<String> = (0..10000)
+            .map(|i| geocode.encode(-180.0 + i as f64 * 0.036, 0.0, 8)
+                .unwrap()).collect();
+        assert_eq!(geocode.par_decode_batch(&codes).unwrap(),
+            geocode.decode_batch(&codes).unwrap());
+
+        assert_eq!(geocode.par_decode_batch(&["dpc5u6", "dpca"]),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 3 }));
+    }
+
+    #[test]
+    fn par_encode_batch() {
+        let points: Vec<(f64, f64)> = (0..10000)
+            .map(|i| (-180.0 + i as f64 * 0.036, -90.0 + i as f64 * 0.018))
+            .collect();
+        for geocode in [Geocode::Geohash, Geocode::Maidenhead].iter() {
+            assert_eq!(geocode.par_encode_batch(&points, 8).unwrap(),
+                geocode.encode_batch(&points, 8).unwrap());
+        }
+
+        let mut points = points;
+        points[9000] = (0.0, 91.0);
+        assert!(Geocode::Geohash.par_encode_batch(&points, 8).is_err());
+    }
+}
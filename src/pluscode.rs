@@ -0,0 +1,459 @@
+use crate::{math, GeocodeError, GEOHASH_BOUNDS};
+#[cfg(feature = "alloc")]
+use crate::BoundingBox;
+
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+pub(crate) static PLUSCODE_CHARS: &[char] = &['2', '3', '4', '5', '6', '7',
+    '8', '9', 'C', 'F', 'G', 'H', 'J', 'M', 'P', 'Q', 'R', 'V', 'W', 'X'];
+
+const PAIR_LENGTH: usize = 10;
+const MAX_LENGTH: usize = 15;
+const SEPARATOR: char = '+';
+const SEPARATOR_POSITION: usize = 8;
+const PADDING: char = '0';
+
+// number of lat/lon units in the smallest (precision 15) cell per degree
+const LAT_UNITS: i64 = 8000 * 3125;
+const LON_UNITS: i64 = 8000 * 1024;
+
+#[cfg(feature = "alloc")]
+pub(crate) fn decode(key: &str) -> BoundingBox {
+    // accumulate lat/lon cell indices for pair and grid digits
+    let (mut lat, mut lon) = (0i64, 0i64);
+    for (i, c) in key.chars().enumerate() {
+        let value = get_value(c) as i64;
+        if i >= PAIR_LENGTH {
+            lat = lat * 5 + value / 4;
+            lon = lon * 4 + value % 4;
+        } else if i % 2 == 0 {
+            lat = lat * 20 + value;
+        } else {
+            lon = lon * 20 + value;
+        }
+    }
+
+    // scale indices to degrees
+    let (lat_scale, lon_scale) = get_scales(key.len());
+    BoundingBox::new((lon * lon_scale) as f64 / LON_UNITS as f64 - 180.0,
+        ((lon + 1) * lon_scale) as f64 / LON_UNITS as f64 - 180.0,
+        (lat * lat_scale) as f64 / LAT_UNITS as f64 - 90.0,
+        ((lat + 1) * lat_scale) as f64 / LAT_UNITS as f64 - 90.0)
+}
+
+pub(crate) fn encode<W: Write>(x: f64, y: f64, precision: usize,
+        out: &mut W) -> Result<(), GeocodeError> {
+    // check if coordinates and precision are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    } else if !is_valid_precision(precision) {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    // insert separator and pad codes shorter than the separator position
+    let digits = get_digits(x, y);
+    for (i, digit) in digits.iter().enumerate().take(precision) {
+        if i == SEPARATOR_POSITION {
+            out.write_char(SEPARATOR)?;
+        }
+
+        out.write_char(PLUSCODE_CHARS[*digit])?;
+    }
+
+    for _ in precision..SEPARATOR_POSITION {
+        out.write_char(PADDING)?;
+    }
+
+    if precision <= SEPARATOR_POSITION {
+        out.write_char(SEPARATOR)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_key(x: f64, y: f64, precision: usize) -> String {
+    get_digits(x, y).iter().take(precision)
+        .map(|x| PLUSCODE_CHARS[*x]).collect()
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn format(key: &str) -> String {
+    // insert separator and pad codes shorter than the separator position
+    let mut code = String::with_capacity(MAX_LENGTH + 1);
+    if key.len() < SEPARATOR_POSITION {
+        code.push_str(key);
+        code.extend(core::iter::repeat_n(PADDING,
+            SEPARATOR_POSITION - key.len()));
+        code.push(SEPARATOR);
+    } else {
+        code.push_str(&key[..SEPARATOR_POSITION]);
+        code.push(SEPARATOR);
+        code.push_str(&key[SEPARATOR_POSITION..]);
+    }
+
+    code
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn get_child_precision(precision: usize) -> Option<usize> {
+    match precision {
+        x if x < PAIR_LENGTH => Some(x + 2),
+        x if x < MAX_LENGTH => Some(x + 1),
+        _ => None,
+    }
+}
+
+fn get_digits(x: f64, y: f64) -> [usize; MAX_LENGTH] {
+    // convert coordinates to integer units, rounding away floating point
+    // error before truncating
+    let to_units = |value: f64, units: i64|
+        math::floor(math::round(value * units as f64 * 1e6) / 1e6) as i64;
+    let mut lat = to_units(y + 90.0, LAT_UNITS)
+        .clamp(0, 180 * LAT_UNITS - 1);
+    let mut lon = to_units(x + 180.0, LON_UNITS)
+        .rem_euclid(360 * LON_UNITS);
+
+    // compute digits from least to most significant
+    let mut digits = [0usize; MAX_LENGTH];
+    for i in (PAIR_LENGTH..MAX_LENGTH).rev() {
+        digits[i] = (lat % 5 * 4 + lon % 4) as usize;
+        lat /= 5;
+        lon /= 4;
+    }
+
+    for i in (0..PAIR_LENGTH / 2).rev() {
+        digits[i * 2] = (lat % 20) as usize;
+        digits[i * 2 + 1] = (lon % 20) as usize;
+        lat /= 20;
+        lon /= 20;
+    }
+
+    digits
+}
+
+pub(crate) fn get_intervals(precision: usize) -> (f64, f64) {
+    let (lat_scale, lon_scale) = get_scales(precision.min(MAX_LENGTH));
+    (lon_scale as f64 / LON_UNITS as f64, lat_scale as f64 / LAT_UNITS as f64)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn get_parent_precision(precision: usize) -> Option<usize> {
+    match precision {
+        x if x <= 2 => None,
+        x if x <= PAIR_LENGTH => Some(x - 2),
+        x => Some(x - 1),
+    }
+}
+
+fn get_scales(precision: usize) -> (i64, i64) {
+    // compute size of a cell at the given precision in integer units
+    let pairs = (precision.min(PAIR_LENGTH) / 2) as u32;
+    let grids = precision.saturating_sub(PAIR_LENGTH) as u32;
+    (20i64.pow(5 - pairs) * 5i64.pow(5 - grids),
+        20i64.pow(5 - pairs) * 4i64.pow(5 - grids))
+}
+
+#[cfg(feature = "alloc")]
+fn get_value(c: char) -> usize {
+    PLUSCODE_CHARS.iter().position(|x| *x == c).unwrap()
+}
+
+pub(crate) fn is_valid_precision(precision: usize) -> bool {
+    (2..=MAX_LENGTH).contains(&precision)
+        && (precision >= PAIR_LENGTH || precision.is_multiple_of(2))
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn parse(value: &str) -> Result<String, GeocodeError> {
+    let (key, separator) = parse_short(value)?;
+    if separator != SEPARATOR_POSITION {
+        return Err(GeocodeError::InvalidFormat { reason:
+            "short codes must be recovered from a reference location"
+                .to_string() });
+    }
+
+    // check if the first pair is within range
+    let lat = get_value(key.chars().next().unwrap());
+    let lon = get_value(key.chars().nth(1).unwrap());
+    if lat >= 9 {
+        return Err(GeocodeError::InvalidCharacter {
+            ch: value.chars().next().unwrap(), index: 0 });
+    } else if lon >= 18 {
+        return Err(GeocodeError::InvalidCharacter {
+            ch: value.chars().nth(1).unwrap(), index: 1 });
+    }
+
+    Ok(key)
+}
+
+#[cfg(feature = "alloc")]
+fn parse_short(value: &str) -> Result<(String, usize), GeocodeError> {
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    }
+
+    let separator = match value.find(SEPARATOR) {
+        Some(separator) => separator,
+        None => return Err(GeocodeError::InvalidFormat {
+            reason: "missing separator".to_string() }),
+    };
+
+    if separator > SEPARATOR_POSITION || separator % 2 == 1 {
+        return Err(GeocodeError::InvalidCharacter {
+            ch: SEPARATOR, index: separator });
+    }
+
+    let mut key = String::with_capacity(MAX_LENGTH);
+    let mut padding = None;
+    for (index, c) in value.chars().enumerate() {
+        let c = c.to_ascii_uppercase();
+        if index == separator {
+            continue;
+        } else if c == PADDING && index < separator && index > 0
+                && (padding.is_some() || index % 2 == 0) {
+            // padding must start on a pair boundary and continue to the
+            // separator
+            padding.get_or_insert(index);
+        } else if padding.is_some() || !PLUSCODE_CHARS.contains(&c) {
+            return Err(GeocodeError::InvalidCharacter {
+                ch: value.chars().nth(index).unwrap(), index });
+        } else {
+            key.push(c);
+        }
+    }
+
+    // padded codes must end with the separator
+    let suffix = value.len() - separator - 1;
+    if (padding.is_some() && suffix != 0) || suffix == 1
+            || key.len() > MAX_LENGTH
+            || (padding.is_some() && separator != SEPARATOR_POSITION) {
+        return Err(GeocodeError::InvalidFormat {
+            reason: format!("invalid code length in '{}'", value) });
+    }
+
+    Ok((key, separator))
+}
+
+#[cfg(feature = "alloc")]
+pub fn recover_nearest(value: &str, x: f64, y: f64)
+        -> Result<String, GeocodeError> {
+    // check if reference location and code are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    }
+
+    let (key, separator) = parse_short(value)?;
+    if separator == SEPARATOR_POSITION {
+        parse(value)?;
+        return Ok(format(&key));
+    }
+
+    // prefix the short code with digits from the reference location
+    let padding = SEPARATOR_POSITION - separator;
+    let prefix = encode_key(x, y, MAX_LENGTH);
+    let full_key = format!("{}{}", &prefix[..padding], key);
+    let (lon_resolution, lat_resolution) = get_intervals(padding);
+
+    // move the recovered cell to the one nearest the reference location
+    let (mut lon, mut lat) = decode(&full_key).center();
+    if y + lat_resolution / 2.0 < lat && lat - lat_resolution >= -90.0 {
+        lat -= lat_resolution;
+    } else if y - lat_resolution / 2.0 > lat
+            && lat + lat_resolution <= 90.0 {
+        lat += lat_resolution;
+    }
+
+    if x + lon_resolution / 2.0 < lon {
+        lon -= lon_resolution;
+    } else if x - lon_resolution / 2.0 > lon {
+        lon += lon_resolution;
+    }
+
+    // normalize longitude
+    let lon = math::rem_euclid(lon + 180.0, 360.0) - 180.0;
+    Ok(format(&encode_key(lon, lat, full_key.len())))
+}
+
+#[cfg(feature = "alloc")]
+pub fn shorten(value: &str, x: f64, y: f64)
+        -> Result<String, GeocodeError> {
+    // check if reference location and code are valid
+    if !GEOHASH_BOUNDS.contains(x, y) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x, y, bounds: GEOHASH_BOUNDS });
+    }
+
+    let key = parse(value)?;
+    if key.len() < SEPARATOR_POSITION {
+        return Err(GeocodeError::InvalidFormat {
+            reason: "padded codes cannot be shortened".to_string() });
+    }
+
+    // remove as many leading pairs as the reference location allows
+    let (lon, lat) = decode(&key).center();
+    let range = (lat - y).abs().max((lon - x).abs());
+    let code = format(&key);
+    for pairs in (2..=4).rev() {
+        let (_, resolution) = get_intervals(pairs * 2);
+        if range < resolution * 0.3 {
+            return Ok(code[pairs * 2..].to_string());
+        }
+    }
+
+    Ok(code)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+
+    #[test]
+    fn decode() {
+        let geocode = Geocode::PlusCode;
+        let bbox = geocode.decode("7FG49Q00+").unwrap();
+        assert!((bbox.min_x - 2.75).abs() < 1e-9);
+        assert!((bbox.max_x - 2.8).abs() < 1e-9);
+        assert!((bbox.min_y - 20.35).abs() < 1e-9);
+        assert!((bbox.max_y - 20.4).abs() < 1e-9);
+
+        assert_eq!(geocode.decode("CFX30000+").unwrap(),
+            BoundingBox::new(1.0, 2.0, 89.0, 90.0));
+        let bbox = geocode.decode("7FG49QCJ+2V").unwrap();
+        assert!(bbox.contains(2.7821875, 20.3700625));
+        assert!((bbox.width() - 0.000125).abs() < 1e-12);
+
+        // codes are case insensitive
+        assert_eq!(geocode.decode("7fg49qcj+2v").unwrap(), bbox);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.decode(""), Err(GeocodeError::EmptyCode));
+        assert_eq!(geocode.decode("7FG49QCJ+2A"),
+            Err(GeocodeError::InvalidCharacter { ch: 'A', index: 10 }));
+        assert_eq!(geocode.decode("XFG49QCJ+2V"),
+            Err(GeocodeError::InvalidCharacter { ch: 'X', index: 0 }));
+        assert!(geocode.decode("7FG49QCJ2V").is_err());
+        assert!(geocode.decode("7FG49QCJ+2").is_err());
+        assert!(geocode.decode("7FG49Q+CJ").is_err());
+        assert!(geocode.decode("7FG00000+").is_err());
+        assert!(geocode.decode("7F000000+2V").is_err());
+        assert!(geocode.decode("7F00G000+").is_err());
+        assert!(geocode.decode("7FG49QCJ+2V+").is_err());
+        assert!(geocode.decode("9QCJ+2VX").is_err());
+    }
+
+    #[test]
+    fn encode() {
+        // reference test vectors
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.encode(2.775, 20.375, 6).unwrap(), "7FG49Q00+");
+        assert_eq!(geocode.encode(2.7821875, 20.3700625, 10).unwrap(),
+            "7FG49QCJ+2V");
+        assert_eq!(geocode.encode(2.782234375, 20.3701125, 11).unwrap(),
+            "7FG49QCJ+2VX");
+        assert_eq!(geocode.encode(8.0000625, 47.0000625, 10).unwrap(),
+            "8FVC2222+22");
+        assert_eq!(geocode.encode(174.7859375, -41.2730625, 10).unwrap(),
+            "4VCPPQGP+Q9");
+        assert_eq!(geocode.encode(-179.5, 0.5, 4).unwrap(), "62G20000+");
+        assert_eq!(geocode.encode(-179.5, -89.5, 4).unwrap(), "22220000+");
+        assert_eq!(geocode.encode(179.5, 0.5, 4).unwrap(), "6VGX0000+");
+        assert_eq!(geocode.encode(1.0, 90.0, 4).unwrap(), "CFX30000+");
+        assert_eq!(geocode.encode(180.0, 0.5, 4).unwrap(), "62G20000+");
+
+        assert_eq!(geocode.encode(0.0, 0.0, 3),
+            Err(GeocodeError::InvalidPrecision { precision: 3 }));
+        assert_eq!(geocode.encode(0.0, 0.0, 16),
+            Err(GeocodeError::InvalidPrecision { precision: 16 }));
+        assert!(geocode.encode(0.0, 91.0, 10).is_err());
+    }
+
+    #[test]
+    fn hierarchy() {
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.parent("86HJ7JR9+CV").unwrap(), "86HJ7JR9+");
+        assert_eq!(geocode.parent("86HJ7JR9+CVX").unwrap(), "86HJ7JR9+CV");
+        assert_eq!(geocode.parent("86HJ0000+").unwrap(), "86000000+");
+        assert!(geocode.parent("86000000+").is_err());
+
+        let children: Vec<String> =
+            geocode.children("86HJ7JR9+").unwrap().collect();
+        assert_eq!(children.len(), 400);
+        assert!(children.contains(&"86HJ7JR9+CV".to_string()));
+        assert_eq!(geocode.children("86HJ7JR9+CV").unwrap().count(), 20);
+
+        assert_eq!(geocode.siblings("86000000+").unwrap().len(), 161);
+        assert_eq!(geocode.compact(children).unwrap(), vec!["86HJ7JR9+"]);
+    }
+
+    #[test]
+    fn intervals() {
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.get_intervals(2), (20.0, 20.0));
+        assert_eq!(geocode.get_intervals(4), (1.0, 1.0));
+        assert_eq!(geocode.get_intervals(10), (0.000125, 0.000125));
+        assert_eq!(geocode.get_intervals(11), (0.00003125, 0.000025));
+    }
+
+    #[test]
+    fn recover_nearest() {
+        assert_eq!(super::recover_nearest("+2VX", -1.217765625, 51.3701125)
+            .unwrap(), "9C3W9QCJ+2VX");
+        assert_eq!(super::recover_nearest("9G8F+6W", 8.6, 47.4).unwrap(),
+            "8FVC9G8F+6W");
+        assert_eq!(super::recover_nearest("8FVC9G8F+6W", 0.0, 0.0)
+            .unwrap(), "8FVC9G8F+6W");
+
+        // recovered cells are nearest the reference across boundaries
+        assert_eq!(super::recover_nearest("2222+22", 8.0, 47.99)
+            .unwrap(), "8FWC2222+22");
+        assert_eq!(super::recover_nearest("XXXX+XX", 8.0, 47.01)
+            .unwrap(), "8FR9XXXX+XX");
+
+        for (x, y) in [(179.9999, 0.0), (-179.9999, 0.0), (0.0, 0.02),
+                (0.0, -0.02), (10.0, 10.0)].iter() {
+            for short in ["22+22", "XX+XX", "2X+X2"].iter() {
+                let code = super::recover_nearest(short, *x, *y).unwrap();
+                assert!(code.ends_with(short));
+
+                // check recovered cell is within half a resolution
+                let (lon, lat) = super::decode(&super::parse(&code)
+                    .unwrap()).center();
+                let lon_delta = (lon - x).abs().min(360.0 - (lon - x).abs());
+                assert!(lon_delta <= 0.025 && (lat - y).abs() <= 0.025);
+            }
+        }
+        assert!(super::recover_nearest("9G8F6W", 8.6, 47.4).is_err());
+    }
+
+    #[test]
+    fn shorten() {
+        // reference test vectors
+        let code = "9C3W9QCJ+2VX";
+        assert_eq!(super::shorten(code, -1.217765625, 51.3701125).unwrap(),
+            "+2VX");
+        assert_eq!(super::shorten(code, -1.217765625, 51.3708675).unwrap(),
+            "CJ+2VX");
+        assert_eq!(super::shorten(code, -1.217765625, 51.3693575).unwrap(),
+            "CJ+2VX");
+        assert_eq!(super::shorten("8FVC9G8F+6W", 8.6, 47.4).unwrap(),
+            "9G8F+6W");
+        assert_eq!(super::shorten(code, 0.0, 0.0).unwrap(), code);
+        assert!(super::shorten("9C3W0000+", -1.2, 51.4).is_err());
+
+        // check shortened codes are recovered
+        let short = super::shorten(code, -1.2, 51.4).unwrap();
+        assert_eq!(super::recover_nearest(&short, -1.2, 51.4).unwrap(),
+            code);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{BoundingBox, GeocodeError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub exterior: Vec<(f64, f64)>,
+    pub interiors: Vec<Vec<(f64, f64)>>,
+}
+
+impl Polygon {
+    pub fn new(exterior: Vec<(f64, f64)>,
+            interiors: Vec<Vec<(f64, f64)>>) -> Polygon {
+        Polygon { exterior, interiors }
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut bbox = BoundingBox::new(f64::INFINITY,
+            f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+        for (x, y) in self.exterior.iter() {
+            bbox.min_x = bbox.min_x.min(*x);
+            bbox.max_x = bbox.max_x.max(*x);
+            bbox.min_y = bbox.min_y.min(*y);
+            bbox.max_y = bbox.max_y.max(*y);
+        }
+
+        bbox
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        // even-odd rule over all rings handles holes
+        let mut inside = false;
+        for (start, end) in self.edges() {
+            if (start.1 > y) != (end.1 > y) && x < start.0
+                    + (y - start.1) / (end.1 - start.1) * (end.0 - start.0) {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+
+    pub(crate) fn crosses_interior(&self, bbox: &BoundingBox) -> bool {
+        self.edges().any(|(start, end)| segment_crosses(start, end, bbox))
+    }
+
+    pub(crate) fn edges(&self)
+            -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+        std::iter::once(&self.exterior)
+            .chain(self.interiors.iter())
+            .flat_map(|ring| ring_edges(ring))
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), GeocodeError> {
+        for ring in std::iter::once(&self.exterior)
+                .chain(self.interiors.iter()) {
+            if ring_edges(ring).count() < 3 {
+                return Err(GeocodeError::InvalidGeometry {
+                    reason: "polygon rings require at least three points"
+                        .to_string() });
+            } else if ring.iter().any(|(x, y)| !x.is_finite()
+                    || !y.is_finite()) {
+                return Err(GeocodeError::InvalidGeometry {
+                    reason: "polygon coordinates must be finite"
+                        .to_string() });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn ring_edges(ring: &[(f64, f64)])
+        -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    // rings may be explicitly closed or not
+    let len = match (ring.first(), ring.last()) {
+        (Some(first), Some(last)) if ring.len() > 1 && first == last
+            => ring.len() - 1,
+        _ => ring.len(),
+    };
+
+    (0..len).map(move |i| (ring[i], ring[(i + 1) % len]))
+}
+
+pub(crate) fn segment_crosses(start: (f64, f64), end: (f64, f64),
+        bbox: &BoundingBox) -> bool {
+    // clip segment to the bounding box (liang-barsky)
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let (mut t0, mut t1) = (0f64, 1f64);
+    for (p, q) in [(-dx, start.0 - bbox.min_x), (dx, bbox.max_x - start.0),
+            (-dy, start.1 - bbox.min_y), (dy, bbox.max_y - start.1)].iter() {
+        if *p == 0.0 {
+            if *q < 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if *p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return false;
+    }
+
+    // segment crosses the interior if the midpoint of the clipped
+    // segment lies strictly within the bounding box
+    let t = (t0 + t1) / 2f64;
+    let (x, y) = (start.0 + t * dx, start.1 + t * dy);
+    x > bbox.min_x && x < bbox.max_x && y > bbox.min_y && y < bbox.max_y
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BoundingBox;
+    use super::Polygon;
+
+    fn square_with_hole() -> Polygon {
+        Polygon::new(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)],
+            vec![vec![(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0),
+                (4.0, 4.0)]])
+    }
+
+    #[test]
+    fn contains() {
+        let polygon = square_with_hole();
+        assert!(polygon.contains(1.0, 1.0));
+        assert!(polygon.contains(9.0, 5.0));
+        assert!(!polygon.contains(5.0, 5.0));
+        assert!(!polygon.contains(11.0, 5.0));
+        assert!(!polygon.contains(-1.0, -1.0));
+    }
+
+    #[test]
+    fn crosses_interior() {
+        let polygon = square_with_hole();
+        assert!(polygon.crosses_interior(
+            &BoundingBox::new(9.0, 11.0, 1.0, 2.0)));
+        assert!(polygon.crosses_interior(
+            &BoundingBox::new(3.0, 7.0, 3.0, 7.0)));
+        assert!(!polygon.crosses_interior(
+            &BoundingBox::new(1.0, 2.0, 1.0, 2.0)));
+
+        // edges along the bounding box boundary do not cross
+        assert!(!polygon.crosses_interior(
+            &BoundingBox::new(0.0, 2.0, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn validate() {
+        assert!(square_with_hole().validate().is_ok());
+        assert!(Polygon::new(vec![(0.0, 0.0), (1.0, 1.0), (0.0, 0.0)],
+            Vec::new()).validate().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+impl Geocode {
+    pub fn contains(&self, value: &str, x: f64, y: f64)
+            -> Result<bool, GeocodeError> {
+        // encode the point to match edge handling of encode
+        let key = self.parse_key(value)?;
+        if !self.get_bounds().contains(x, y) {
+            return Ok(false);
+        }
+
+        let code = self.encode(x, y, key.chars().count())?;
+        Ok(self.parse_key(&code)? == key)
+    }
+
+    pub fn intersects_bbox(&self, value: &str, bbox: &BoundingBox)
+            -> Result<bool, GeocodeError> {
+        Ok(self.decode(value)?.intersects(bbox))
+    }
+
+    pub fn is_adjacent(&self, a: &str, b: &str)
+            -> Result<bool, GeocodeError> {
+        // nested cells overlap rather than touch
+        let (key_a, key_b) = (self.parse_key(a)?, self.parse_key(b)?);
+        if key_a.starts_with(&key_b) || key_b.starts_with(&key_a) {
+            return Ok(false);
+        }
+
+        // allow for floating point error along shared edges
+        let (bbox_a, bbox_b) = (self.decode(a)?, self.decode(b)?);
+        let epsilon = bbox_a.width().min(bbox_b.width())
+            .min(bbox_a.height()).min(bbox_b.height()) * 1e-9;
+        Ok(bbox_a.intersects(&BoundingBox::new(bbox_b.min_x - epsilon,
+            bbox_b.max_x + epsilon, bbox_b.min_y - epsilon,
+            bbox_b.max_y + epsilon)))
+    }
+
+    pub fn is_ancestor(&self, a: &str, b: &str)
+            -> Result<bool, GeocodeError> {
+        let (key_a, key_b) = (self.parse_key(a)?, self.parse_key(b)?);
+        Ok(key_a.len() < key_b.len() && key_b.starts_with(&key_a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Direction, Geocode, GeocodeError};
+
+    #[test]
+    fn contains() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.contains("dpc5", -88.4, 44.266667), Ok(true));
+        assert_eq!(geocode.contains("dpc4", -88.4, 44.266667), Ok(false));
+        assert_eq!(geocode.contains("dpc5", 0.0, 91.0), Ok(false));
+
+        // points on shared edges belong to the lower cell
+        assert_eq!(geocode.contains("7", 0.0, 0.0), Ok(true));
+        assert_eq!(geocode.contains("k", 0.0, 0.0), Ok(false));
+
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.contains("86PH0000+", -88.4, 44.266667),
+            Ok(true));
+        assert!(geocode.contains("86HJ", -88.4, 44.266667).is_err());
+    }
+
+    #[test]
+    fn intersects_bbox() {
+        let geocode = Geocode::Geohash;
+        let bbox = BoundingBox::new(-90.0, -88.0, 44.0, 45.0);
+        assert_eq!(geocode.intersects_bbox("dpc5", &bbox), Ok(true));
+        assert_eq!(geocode.intersects_bbox("9xjq", &bbox), Ok(false));
+        assert_eq!(geocode.intersects_bbox("", &bbox),
+            Err(GeocodeError::EmptyCode));
+    }
+
+    #[test]
+    fn is_adjacent() {
+        let geocode = Geocode::Geohash;
+        for neighbor in geocode.neighbors("dpc5").unwrap() {
+            assert_eq!(geocode.is_adjacent("dpc5", &neighbor), Ok(true));
+        }
+
+        // cells of differing precision may be adjacent
+        let east = geocode.neighbor("dpc5", Direction::East).unwrap();
+        let child = geocode.children(&east).unwrap().next().unwrap();
+        assert_eq!(geocode.is_adjacent("dpc5", &child), Ok(true));
+
+        assert_eq!(geocode.is_adjacent("dpc5", "dpc5"), Ok(false));
+        assert_eq!(geocode.is_adjacent("dpc5", "dpc5u"), Ok(false));
+        assert_eq!(geocode.is_adjacent("dpc5", "9xjq"), Ok(false));
+
+        let geocode = Geocode::Geohash36;
+        for neighbor in geocode.neighbors("bdrdC").unwrap() {
+            assert_eq!(geocode.is_adjacent(&neighbor, "bdrdC"), Ok(true));
+        }
+    }
+
+    #[test]
+    fn is_ancestor() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.is_ancestor("dpc", "dpc5u6"), Ok(true));
+        assert_eq!(geocode.is_ancestor("dpc5u6", "dpc"), Ok(false));
+        assert_eq!(geocode.is_ancestor("dpc", "dpc"), Ok(false));
+        assert_eq!(geocode.is_ancestor("dpb", "dpc5u6"), Ok(false));
+
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.is_ancestor("86HJ0000+", "86HJ7JR9+CV"),
+            Ok(true));
+        assert!(geocode.is_ancestor("86HJ", "86HJ7JR9+CV").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{BoundingBox, Geocode, GeocodeError, GEOHASH_BOUNDS};
+
+use std::f64::consts::PI;
+
+pub const EARTH_RADIUS: f64 = 6378137.0;
+pub const MERCATOR_MAX_LATITUDE: f64 = 85.05112877980659;
+
+// wgs84 ellipsoid parameters
+pub const WGS84_FLATTENING: f64 = 1.0 / 298.257223563;
+pub const WGS84_SEMI_MAJOR_AXIS: f64 = 6378137.0;
+
+impl Geocode {
+    pub fn decode_lonlat(&self, value: &str)
+            -> Result<BoundingBox, GeocodeError> {
+        // project native cell corners to wgs84
+        let bbox = self.decode(value)?;
+        let (min_x, min_y) = self.native_to_wgs84(bbox.min_x, bbox.min_y);
+        let (max_x, max_y) = self.native_to_wgs84(bbox.max_x, bbox.max_y);
+        Ok(BoundingBox::new(min_x, max_x, min_y, max_y))
+    }
+
+    pub fn encode_lonlat(&self, lon: f64, lat: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        // check if coordinates are valid before projecting
+        if !GEOHASH_BOUNDS.contains(lon, lat) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x: lon, y: lat, bounds: GEOHASH_BOUNDS });
+        }
+
+        let (x, y) = self.wgs84_to_native(lon, lat);
+        self.encode(x, y, precision)
+    }
+}
+
+pub fn mercator_to_wgs84(x: f64, y: f64) -> (f64, f64) {
+    let lon = (x / EARTH_RADIUS).to_degrees();
+    let lat = (2f64 * (y / EARTH_RADIUS).exp().atan() - PI / 2f64)
+        .to_degrees();
+
+    (lon, lat)
+}
+
+pub fn wgs84_to_mercator(lon: f64, lat: f64) -> (f64, f64) {
+    // latitudes beyond the mercator limit are clamped
+    let lat = lat.clamp(-MERCATOR_MAX_LATITUDE, MERCATOR_MAX_LATITUDE);
+
+    let x = EARTH_RADIUS * lon.to_radians();
+    let y = EARTH_RADIUS * (PI / 4f64 + lat.to_radians() / 2f64).tan().ln();
+
+    (x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Geocode;
+
+    #[test]
+    fn decode_lonlat() {
+        let bbox = Geocode::QuadTile.decode_lonlat("0").unwrap();
+        assert!((bbox.min_x - -180.0).abs() < 1e-9 && bbox.max_x.abs() < 1e-9);
+        assert!(bbox.min_y.abs() < 1e-9);
+        assert!((bbox.max_y - super::MERCATOR_MAX_LATITUDE).abs() < 1e-9);
+
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.decode_lonlat("dpc5").unwrap(),
+            geocode.decode("dpc5").unwrap());
+    }
+
+    #[test]
+    fn encode_lonlat() {
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.encode_lonlat(-88.4, 44.266667, 6).unwrap(),
+            "030222");
+        assert_eq!(geocode.encode_lonlat(-88.4, 44.266667, 12).unwrap(),
+            geocode.encode(-9840642.99, 5506802.68, 12).unwrap());
+
+        // latitudes beyond the mercator limit resolve to edge cells
+        assert_eq!(geocode.encode_lonlat(0.0, 90.0, 2).unwrap(), "01");
+        assert!(geocode.encode_lonlat(-88.4, 91.0, 6).is_err());
+
+        // geographic geocodes are unchanged
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_lonlat(-88.4, 44.266667, 6).unwrap(),
+            geocode.encode(-88.4, 44.266667, 6).unwrap());
+    }
+
+    #[test]
+    fn mercator() {
+        let (x, y) = super::wgs84_to_mercator(-88.4, 44.266667);
+        assert!((x - -9840642.99).abs() < 0.01);
+        assert!((y - 5506802.68).abs() < 0.01);
+
+        let (lon, lat) = super::mercator_to_wgs84(x, y);
+        assert!((lon - -88.4).abs() < 1e-9);
+        assert!((lat - 44.266667).abs() < 1e-9);
+
+        let (_, y) = super::wgs84_to_mercator(0.0,
+            super::MERCATOR_MAX_LATITUDE);
+        assert!((y - 20037508.342789248).abs() < 1e-6);
+        assert_eq!(super::wgs84_to_mercator(0.0, 90.0), (0.0, y));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{BoundingBox, Geocode, GeocodeError, QUADTILE_BOUNDS};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    // quadtile key at the maximum depth, so each entry follows the
+    // path its code describes
+    key: String,
+    x: f64,
+    y: f64,
+    value: T,
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    entries: Vec<Entry<T>>,
+    // leaves have no children, otherwise one child per quadtile digit
+    children: Vec<Node<T>>,
+    len: usize,
+}
+
+impl<T> Node<T> {
+    fn new() -> Node<T> {
+        Node { entries: Vec::new(), children: Vec::new(), len: 0 }
+    }
+
+    fn insert(&mut self, entry: Entry<T>, depth: usize, capacity: usize) {
+        self.len += 1;
+        if !self.children.is_empty() {
+            let index = get_index(&entry.key, depth);
+            self.children[index].insert(entry, depth + 1, capacity);
+            return;
+        }
+
+        // leaves split once full, unless they are at the maximum depth
+        self.entries.push(entry);
+        let max_depth = self.entries[0].key.len();
+        if self.entries.len() > capacity && depth < max_depth {
+            self.children = (0..4).map(|_| Node::new()).collect();
+            for entry in core::mem::take(&mut self.entries) {
+                let index = get_index(&entry.key, depth);
+                self.children[index].insert(entry, depth + 1, capacity);
+            }
+        }
+    }
+
+    fn leaves(&self, code: &mut String, leaves: &mut Vec<(String, usize)>) {
+        if self.children.is_empty() {
+            leaves.push((code.clone(), self.len));
+            return;
+        }
+
+        for (i, child) in self.children.iter().enumerate() {
+            code.push(get_digit(i));
+            child.leaves(code, leaves);
+            code.pop();
+        }
+    }
+
+    fn query<'a>(&'a self, code: &mut String, bbox: &BoundingBox,
+            values: &mut Vec<&'a T>) {
+        let bounds = match code.is_empty() {
+            true => QUADTILE_BOUNDS,
+            false => Geocode::QuadTile.decode(code).unwrap(),
+        };
+
+        if self.len == 0 || !bounds.intersects(bbox) {
+            return;
+        }
+
+        values.extend(self.entries.iter()
+            .filter(|x| bbox.contains(x.x, x.y)).map(|x| &x.value));
+        for (i, child) in self.children.iter().enumerate() {
+            code.push(get_digit(i));
+            child.query(code, bbox, values);
+            code.pop();
+        }
+    }
+
+    fn values<'a>(&'a self, prefix: &str, values: &mut Vec<&'a T>) {
+        values.extend(self.entries.iter()
+            .filter(|x| x.key.starts_with(prefix)).map(|x| &x.value));
+        for child in self.children.iter() {
+            child.values(prefix, values);
+        }
+    }
+}
+
+// a point quadtree whose nodes are quadtile cells, so each node path is
+// the sequence of digits of its code
+#[derive(Clone, Debug)]
+pub struct QuadTree<T> {
+    capacity: usize,
+    max_depth: usize,
+    root: Node<T>,
+}
+
+impl<T> QuadTree<T> {
+    pub fn new(capacity: usize, max_depth: usize)
+            -> Result<QuadTree<T>, GeocodeError> {
+        // check if capacity and depth are valid
+        if capacity == 0 {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "quadtree node capacity must be positive"
+                    .to_string() });
+        }
+
+        let (x, y) = QUADTILE_BOUNDS.center();
+        Geocode::QuadTile.encode(x, y, max_depth)?;
+
+        Ok(QuadTree { capacity, max_depth, root: Node::new() })
+    }
+
+    pub fn code_to_path(value: &str) -> Result<Vec<u8>, GeocodeError> {
+        // paths index children by quadtile digit
+        let key = Geocode::QuadTile.parse_key(value)?;
+        Ok(key.chars().map(|x| x.to_digit(4).unwrap() as u8).collect())
+    }
+
+    pub fn get(&self, value: &str) -> Result<Vec<&T>, GeocodeError> {
+        // descend to the node, where codes deeper than a leaf match
+        // entries by their key
+        let path = QuadTree::<T>::code_to_path(value)?;
+        let mut node = &self.root;
+        for index in path.iter() {
+            if node.children.is_empty() {
+                break;
+            }
+
+            node = &node.children[*index as usize];
+        }
+
+        let key = Geocode::QuadTile.parse_key(value)?;
+        let mut values = Vec::new();
+        node.values(&key, &mut values);
+        Ok(values)
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn insert(&mut self, x: f64, y: f64, value: T)
+            -> Result<(), GeocodeError> {
+        let code = Geocode::QuadTile.encode(x, y, self.max_depth)?;
+        let key = Geocode::QuadTile.parse_key(&code)?;
+        self.root.insert(Entry { key, x, y, value }, 0, self.capacity);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.len == 0
+    }
+
+    pub fn leaves(&self) -> Vec<(String, usize)> {
+        // leaf codes with the number of values stored beneath them
+        let mut leaves = Vec::new();
+        self.root.leaves(&mut String::new(), &mut leaves);
+        leaves
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.len
+    }
+
+    pub fn path_to_code(path: &[u8]) -> Result<String, GeocodeError> {
+        let mut code = String::with_capacity(path.len());
+        for (index, digit) in path.iter().enumerate() {
+            if *digit > 3 {
+                return Err(GeocodeError::InvalidFormat { reason:
+                    format!("invalid child {} at depth {}",
+                        digit, index) });
+            }
+
+            code.push(get_digit(*digit as usize));
+        }
+
+        Ok(code)
+    }
+
+    pub fn query(&self, bbox: &BoundingBox)
+            -> Result<Vec<&T>, GeocodeError> {
+        // check if bounding box is valid
+        if !(bbox.min_x <= bbox.max_x && bbox.min_y <= bbox.max_y) {
+            return Err(GeocodeError::InvalidBoundingBox { bounds: *bbox });
+        }
+
+        let mut values = Vec::new();
+        self.root.query(&mut String::new(), bbox, &mut values);
+        Ok(values)
+    }
+}
+
+fn get_digit(index: usize) -> char {
+    core::char::from_digit(index as u32, 4).unwrap()
+}
+
+fn get_index(key: &str, depth: usize) -> usize {
+    key.as_bytes()[depth] as usize - '0' as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::QuadTree;
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn quadtree() -> QuadTree<usize> {
+        let mut quadtree = QuadTree::new(2, 8).unwrap();
+        for (i, (x, y)) in [(-9840642.99, 5506802.68),
+                (-9850000.0, 5500000.0), (-11681000.0, 4948000.0),
+                (1000.0, -1000.0), (-9840642.99, 5506802.68)].iter()
+                .enumerate() {
+            quadtree.insert(*x, *y, i).unwrap();
+        }
+
+        quadtree
+    }
+
+    #[test]
+    fn insert() {
+        let quadtree = quadtree();
+        assert_eq!(quadtree.len(), 5);
+
+        // full nodes split until values separate or reach maximum depth
+        let leaves = quadtree.leaves();
+        assert_eq!(leaves.iter().map(|x| x.1).sum::<usize>(), 5);
+        assert!(leaves.iter().all(|x| x.1 <= 2 || x.0.len() == 8));
+        assert!(leaves.iter().any(|x| x.0.len() > 1));
+
+        let code = Geocode::QuadTile.encode(-9840642.99, 5506802.68, 8)
+            .unwrap();
+        assert_eq!(quadtree.get(&code).unwrap(), vec![&0, &1, &4]);
+        assert_eq!(quadtree.get(&code[..2]).unwrap().len(), 3);
+        assert_eq!(quadtree.get("3").unwrap(), vec![&3]);
+        assert!(quadtree.get("4").is_err());
+
+        let mut quadtree = quadtree;
+        assert!(quadtree.insert(0.0, 30000000.0, 5).is_err());
+        assert!(QuadTree::<usize>::new(0, 8).is_err());
+        assert!(QuadTree::<usize>::new(2, 0).is_err());
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(QuadTree::<usize>::code_to_path("0321").unwrap(),
+            vec![0, 3, 2, 1]);
+        assert_eq!(QuadTree::<usize>::path_to_code(&[0, 3, 2, 1]).unwrap(),
+            "0321");
+        assert!(QuadTree::<usize>::code_to_path("04").is_err());
+        assert!(QuadTree::<usize>::path_to_code(&[4]).is_err());
+
+        // leaf paths address the cells holding their values
+        let quadtree = quadtree();
+        for (code, _) in quadtree.leaves() {
+            let path = QuadTree::<usize>::code_to_path(&code).unwrap();
+            assert_eq!(QuadTree::<usize>::path_to_code(&path).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn query() {
+        let quadtree = quadtree();
+        let bbox = BoundingBox::new(-10000000.0, -9800000.0,
+            5400000.0, 5600000.0);
+        let mut values: Vec<&usize> = quadtree.query(&bbox).unwrap();
+        values.sort();
+        assert_eq!(values, vec![&0, &1, &4]);
+
+        assert_eq!(quadtree.query(&BoundingBox::new(0.0, 2000.0,
+            -2000.0, 0.0)).unwrap(), vec![&3]);
+        assert!(quadtree.query(&BoundingBox::new(5000.0, 6000.0,
+            5000.0, 6000.0)).unwrap().is_empty());
+        assert!(matches!(quadtree.query(&BoundingBox::new(1.0, 0.0,
+            0.0, 1.0)), Err(GeocodeError::InvalidBoundingBox { .. })));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{math, Geocode, GeocodeError};
+
+use alloc::string::String;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RangePolicy {
+    Clamp,
+    Error,
+    Wrap,
+}
+
+impl Geocode {
+    pub fn apply_range_policy(&self, x: f64, y: f64, policy: RangePolicy)
+            -> Result<(f64, f64), GeocodeError> {
+        let bounds = self.get_bounds();
+        let (x, y) = match policy {
+            RangePolicy::Clamp if !x.is_nan() && !y.is_nan() =>
+                (x.clamp(bounds.min_x, bounds.max_x),
+                    y.clamp(bounds.min_y, bounds.max_y)),
+            RangePolicy::Wrap if x.is_finite()
+                    && (x < bounds.min_x || x > bounds.max_x) => {
+                // only the x axis wraps, y values are still checked
+                let offset = math::rem_euclid(x - bounds.min_x, bounds.width());
+                (bounds.min_x + offset, y)
+            },
+            _ => (x, y),
+        };
+
+        // check if coordinates are valid
+        match bounds.contains(x, y) {
+            true => Ok((x, y)),
+            false => Err(GeocodeError::CoordinateOutOfRange { x, y, bounds }),
+        }
+    }
+
+    pub fn encode_with_policy(&self, x: f64, y: f64, precision: usize,
+            policy: RangePolicy) -> Result<String, GeocodeError> {
+        let (x, y) = self.apply_range_policy(x, y, policy)?;
+        self.encode(x, y, precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::RangePolicy;
+
+    #[test]
+    fn clamp() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.apply_range_policy(180.000001, -91.0,
+            RangePolicy::Clamp).unwrap(), (180.0, -90.0));
+        assert_eq!(geocode.apply_range_policy(-88.4, 44.266667,
+            RangePolicy::Clamp).unwrap(), (-88.4, 44.266667));
+        assert!(geocode.apply_range_policy(f64::NAN, 0.0,
+            RangePolicy::Clamp).is_err());
+
+        assert_eq!(geocode.encode_with_policy(181.0, 0.0, 4,
+            RangePolicy::Clamp).unwrap(), geocode.encode(180.0, 0.0, 4)
+            .unwrap());
+    }
+
+    #[test]
+    fn error() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.encode_with_policy(181.0, 0.0, 4,
+            RangePolicy::Error), geocode.encode(181.0, 0.0, 4));
+        assert_eq!(geocode.apply_range_policy(10.0, 20.0, RangePolicy::Error)
+            .unwrap(), (10.0, 20.0));
+    }
+
+    #[test]
+    fn wrap() {
+        let geocode = Geocode::Geohash;
+        let (x, y) = geocode.apply_range_policy(180.5, 10.0,
+            RangePolicy::Wrap).unwrap();
+        assert!((x - -179.5).abs() < 1e-9 && y == 10.0);
+        let (x, _) = geocode.apply_range_policy(-540.25, 10.0,
+            RangePolicy::Wrap).unwrap();
+        assert!((x - 179.75).abs() < 1e-9);
+
+        // coordinates within bounds are unchanged
+        assert_eq!(geocode.apply_range_policy(180.0, 0.0, RangePolicy::Wrap)
+            .unwrap(), (180.0, 0.0));
+
+        // latitudes do not wrap
+        assert!(matches!(geocode.apply_range_policy(0.0, 91.0,
+            RangePolicy::Wrap),
+            Err(GeocodeError::CoordinateOutOfRange { .. })));
+
+        let geocode = Geocode::QuadTile;
+        let bounds = geocode.get_bounds();
+        let (x, _) = geocode.apply_range_policy(bounds.max_x + 1000.0, 0.0,
+            RangePolicy::Wrap).unwrap();
+        assert!((x - (bounds.min_x + 1000.0)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{morton, BoundingBox, GeocodeError, GEOHASH32_CHARS,
+    GEOHASH_BOUNDS};
+
+use alloc::string::String;
+
+// redis limits latitudes to the web mercator extent
+pub const REDIS_BOUNDS: BoundingBox =
+    BoundingBox::new(-180.0, 180.0, -85.05112878, 85.05112878);
+
+const STEP: usize = 26;
+
+pub fn decode(score: u64) -> Result<BoundingBox, GeocodeError> {
+    if score >> (2 * STEP) != 0 {
+        return Err(GeocodeError::InvalidCellId { id: score });
+    }
+
+    // mirror the redis computation to reproduce its rounding
+    let (x, y) = morton::deinterleave(score);
+    let cells = (1u64 << STEP) as f64;
+    let (x_range, y_range) = (REDIS_BOUNDS.width(), REDIS_BOUNDS.height());
+    Ok(BoundingBox::new(
+        REDIS_BOUNDS.min_x + (x as f64 / cells) * x_range,
+        REDIS_BOUNDS.min_x + ((x + 1) as f64 / cells) * x_range,
+        REDIS_BOUNDS.min_y + (y as f64 / cells) * y_range,
+        REDIS_BOUNDS.min_y + ((y + 1) as f64 / cells) * y_range))
+}
+
+pub fn encode(lon: f64, lat: f64) -> Result<u64, GeocodeError> {
+    // check if coordinates are valid
+    if !REDIS_BOUNDS.contains(lon, lat) {
+        return Err(GeocodeError::CoordinateOutOfRange {
+            x: lon, y: lat, bounds: REDIS_BOUNDS });
+    }
+
+    Ok(encode_within(lon, lat, &REDIS_BOUNDS))
+}
+
+pub fn get_position(score: u64) -> Result<(f64, f64), GeocodeError> {
+    // positions are cell centers clamped to the valid range, as
+    // returned by GEOPOS
+    let bbox = decode(score)?;
+    let (lon, lat) = ((bbox.min_x + bbox.max_x) / 2f64,
+        (bbox.min_y + bbox.max_y) / 2f64);
+    Ok((lon.clamp(REDIS_BOUNDS.min_x, REDIS_BOUNDS.max_x),
+        lat.clamp(REDIS_BOUNDS.min_y, REDIS_BOUNDS.max_y)))
+}
+
+pub fn to_geohash(score: u64) -> Result<String, GeocodeError> {
+    // GEOHASH re-encodes the position over standard geohash bounds,
+    // and the final character of its 11 is always zero
+    let (lon, lat) = get_position(score)?;
+    let bits = encode_within(lon, lat, &GEOHASH_BOUNDS);
+
+    let mut geohash = String::with_capacity(11);
+    for i in 0..10 {
+        let index = (bits >> (2 * STEP - (i + 1) * 5)) & 0x1f;
+        geohash.push(GEOHASH32_CHARS[index as usize]);
+    }
+
+    geohash.push(GEOHASH32_CHARS[0]);
+    Ok(geohash)
+}
+
+fn encode_within(lon: f64, lat: f64, bounds: &BoundingBox) -> u64 {
+    // offsets are truncated, so coordinates on a cell edge belong to the
+    // upper cell and the maximum bound to the final cell
+    let cells = (1u64 << STEP) as f64;
+    let max = (1u32 << STEP) - 1;
+    let x = (((lon - bounds.min_x) / bounds.width()) * cells) as u32;
+    let y = (((lat - bounds.min_y) / bounds.height()) * cells) as u32;
+    morton::interleave(x.min(max), y.min(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GeocodeError;
+    use super::REDIS_BOUNDS;
+
+    #[test]
+    fn decode() {
+        let bbox = super::decode(3479099956230698).unwrap();
+        assert!(bbox.contains(13.361389, 38.115556));
+        assert_eq!(super::get_position(3479099956230698).unwrap(),
+            (13.361389338970184, 38.1155563954963));
+
+        assert_eq!(super::decode(0).unwrap().min_y, REDIS_BOUNDS.min_y);
+        assert_eq!(super::decode(1 << 52),
+            Err(GeocodeError::InvalidCellId { id: 1 << 52 }));
+    }
+
+    #[test]
+    fn encode() {
+        // scores of the sicily example within the redis documentation
+        assert_eq!(super::encode(13.361389, 38.115556),
+            Ok(3479099956230698));
+        assert_eq!(super::encode(15.087269, 37.502669),
+            Ok(3479447370796909));
+
+        assert_eq!(super::encode(180.0, 85.05112878), Ok((1 << 52) - 1));
+        assert_eq!(super::encode(0.0, 86.0),
+            Err(GeocodeError::CoordinateOutOfRange { x: 0.0, y: 86.0,
+                bounds: REDIS_BOUNDS }));
+    }
+
+    #[test]
+    fn to_geohash() {
+        assert_eq!(super::to_geohash(3479099956230698).unwrap(),
+            "sqc8b49rny0");
+        assert_eq!(super::to_geohash(3479447370796909).unwrap(),
+            "sqdtr74hyu0");
+        assert!(super::to_geohash(u64::MAX).is_err());
+    }
+}
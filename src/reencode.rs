@@ -0,0 +1,77 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+impl Geocode {
+    pub fn reencode(&self, value: &str, to: Geocode)
+            -> Result<String, GeocodeError> {
+        // encode the projected native center at the nearest resolution
+        let precision = self.get_equivalent_precision(
+            self.get_precision(value)?, to)?;
+        let (x, y) = self.decode(value)?.center();
+        let (lon, lat) = self.native_to_wgs84(x, y);
+        to.encode_lonlat(lon, lat, precision)
+    }
+
+    pub fn reencode_cover(&self, value: &str, to: Geocode)
+            -> Result<Vec<String>, GeocodeError> {
+        let precision = self.get_equivalent_precision(
+            self.get_precision(value)?, to)?;
+
+        // projections between geocodes preserve axis aligned bounds, where
+        // the cell interior leaves out cells only sharing its edges
+        let bbox = self.decode_lonlat(value)?;
+        let (x, y) = (bbox.width() * 1e-6, bbox.height() * 1e-6);
+        let (min_x, min_y) = to.wgs84_to_native(bbox.min_x + x,
+            bbox.min_y + y);
+        let (max_x, max_y) = to.wgs84_to_native(bbox.max_x - x,
+            bbox.max_y - y);
+        to.cover_bbox(&BoundingBox::new(min_x, max_x, min_y, max_y),
+            precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Geocode;
+
+    #[test]
+    fn reencode() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.reencode("dpc5u6", geocode).unwrap(), "dpc5u6");
+        assert_eq!(geocode.reencode("dpc5u6", Geocode::Geohash16).unwrap(),
+            "65565d1");
+
+        // re-encoded cells contain the original center
+        for to in Geocode::ALL.iter() {
+            let code = geocode.reencode("dpc5u6", *to).unwrap();
+            let bbox = to.decode_lonlat(&code).unwrap();
+            let (x, y) = geocode.decode("dpc5u6").unwrap().center();
+            assert!(bbox.contains(x, y), "{:?} {}", to, code);
+        }
+
+        assert_eq!(Geocode::QuadTile.reencode("030222", geocode).unwrap(),
+            "dp");
+        assert!(geocode.reencode("dpc5u6a", Geocode::QuadTile).is_err());
+    }
+
+    #[test]
+    fn reencode_cover() {
+        // cells of equal resolution cover themselves
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.reencode_cover("dpc5u6", geocode).unwrap(),
+            vec!["dpc5u6".to_string()]);
+
+        // the covering spans the original cell
+        for to in Geocode::ALL.iter() {
+            let cells = geocode.reencode_cover("dpc5u6", *to).unwrap();
+            let bbox = geocode.decode("dpc5u6").unwrap();
+            let precision = to.get_precision(&cells[0]).unwrap();
+            for (x, y) in [(bbox.min_x + 1e-9, bbox.min_y + 1e-9),
+                    (bbox.max_x - 1e-9, bbox.max_y - 1e-9)].iter() {
+                let code = to.encode_lonlat(*x, *y, precision).unwrap();
+                assert!(cells.contains(&code), "{:?} {}", to, code);
+            }
+        }
+
+        assert!(Geocode::QuadTile.reencode_cover("4", geocode).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{Geocode, GeocodeError};
+
+use proj::Proj;
+
+pub struct Reprojector {
+    geocode: Geocode,
+    proj: Proj,
+}
+
+impl Reprojector {
+    pub fn new(geocode: Geocode, epsg_code: u32)
+            -> Result<Reprojector, GeocodeError> {
+        // transform from the source crs to the geocode's native crs
+        let source = format!("EPSG:{}", epsg_code);
+        let target = format!("EPSG:{}", geocode.get_epsg_code());
+        let proj = Proj::new_known_crs(&source, &target, None)
+            .map_err(|e| GeocodeError::InvalidProjection {
+                reason: e.to_string() })?;
+
+        Ok(Reprojector { geocode, proj })
+    }
+
+    pub fn encode(&self, x: f64, y: f64, precision: usize)
+            -> Result<String, GeocodeError> {
+        let (x, y) = self.reproject(x, y)?;
+        self.geocode.encode(x, y, precision)
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn reproject(&self, x: f64, y: f64)
+            -> Result<(f64, f64), GeocodeError> {
+        self.proj.convert((x, y))
+            .map_err(|e| GeocodeError::InvalidProjection {
+                reason: e.to_string() })
+    }
+}
+
+impl Geocode {
+    pub fn encode_epsg(&self, x: f64, y: f64, epsg_code: u32,
+            precision: usize) -> Result<String, GeocodeError> {
+        Reprojector::new(*self, epsg_code)?.encode(x, y, precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::Reprojector;
+
+    #[test]
+    fn encode() {
+        // wgs84 to web mercator matches the built in projection
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.encode_epsg(-88.4, 44.266667, 4326, 12).unwrap(),
+            geocode.encode_lonlat(-88.4, 44.266667, 12).unwrap());
+
+        // colorado state plane north (us survey feet)
+        let reprojector = Reprojector::new(Geocode::Geohash, 2231).unwrap();
+        let (x, y) = reprojector.reproject(3117200.0, 1446800.0).unwrap();
+        assert!((x - -105.078).abs() < 0.01);
+        assert!((y - 40.559).abs() < 0.01);
+        assert_eq!(reprojector.encode(3117200.0, 1446800.0, 4).unwrap(),
+            Geocode::Geohash.encode(x, y, 4).unwrap());
+        assert_eq!(reprojector.get_geocode(), Geocode::Geohash);
+    }
+
+    #[test]
+    fn new() {
+        assert!(matches!(Reprojector::new(Geocode::Geohash, 0),
+            Err(GeocodeError::InvalidProjection { .. })));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Geocode, GeocodeError, GEOHASH_BOUNDS};
+use crate::distance::EARTH_MEAN_RADIUS;
+
+// bound the search for geocodes without a maximum precision
+const MAX_SEARCH_PRECISION: usize = 64;
+
+impl Geocode {
+    pub fn get_equivalent_precision(&self, precision: usize, to: Geocode)
+            -> Result<usize, GeocodeError> {
+        // check if precision is valid by encoding the geocode center
+        let (x, y) = self.get_bounds().center();
+        self.encode(x, y, precision)?;
+
+        // compare cell sizes at the equator by their geometric mean
+        let (width, height) = self.get_intervals_meters(precision, 0f64);
+        let size = (width * height).sqrt();
+
+        // find the target precision with the nearest logarithmic size,
+        // stopping once cells become a worse match
+        let mut best: Option<(usize, f64)> = None;
+        let mut target = to.get_child_precision(0);
+        while let Some(value) = target {
+            if value > MAX_SEARCH_PRECISION {
+                break;
+            }
+
+            let (width, height) = to.get_intervals_meters(value, 0f64);
+            let difference = ((width * height).sqrt() / size).ln().abs();
+            match best {
+                Some((_, best_difference)) if best_difference <= difference
+                    => break,
+                _ => best = Some((value, difference)),
+            }
+
+            target = to.get_child_precision(value);
+        }
+
+        best.map(|x| x.0).ok_or(GeocodeError::InvalidPrecision { precision })
+    }
+
+    pub fn get_intervals_meters(&self, precision: usize, lat: f64)
+            -> (f64, f64) {
+        let (x_interval, y_interval) = self.get_intervals(precision);
+        match self.get_epsg_code() {
+            // mercator distances are scaled by the secant of latitude
+            3857 => (x_interval * lat.to_radians().cos(),
+                y_interval * lat.to_radians().cos()),
+            _ => (x_interval.to_radians() * EARTH_MEAN_RADIUS
+                    * lat.to_radians().cos(),
+                y_interval.to_radians() * EARTH_MEAN_RADIUS),
+        }
+    }
+
+    pub fn get_adaptive_precision(&self, lat: f64, resolution: f64)
+            -> Result<usize, GeocodeError> {
+        // size cells by their geometric mean, so narrowing cells away from
+        // the equator permit coarser precisions of equivalent area
+        self.find_precision(resolution, lat,
+            |width, height| (width * height).sqrt())
+    }
+
+    pub fn get_precision_for_resolution(&self, resolution: f64,
+            lat: Option<f64>) -> Result<usize, GeocodeError> {
+        // cells are largest at the equator
+        self.find_precision(resolution, lat.unwrap_or(0f64),
+            |width, height| width.max(height))
+    }
+
+    fn find_precision<F>(&self, resolution: f64, lat: f64, size: F)
+            -> Result<usize, GeocodeError> where F: Fn(f64, f64) -> f64 {
+        if !GEOHASH_BOUNDS.contains(0f64, lat) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x: 0f64, y: lat, bounds: GEOHASH_BOUNDS });
+        } else if resolution.is_nan() || resolution <= 0f64 {
+            return Err(GeocodeError::InvalidResolution { resolution });
+        }
+
+        // find the coarsest precision with cells no larger than resolution
+        let mut precision = self.get_child_precision(0);
+        while let Some(value) = precision {
+            if value > MAX_SEARCH_PRECISION {
+                break;
+            }
+
+            let (width, height) = self.get_intervals_meters(value, lat);
+            if size(width, height) <= resolution {
+                return Ok(value);
+            }
+
+            precision = self.get_child_precision(value);
+        }
+
+        Err(GeocodeError::InvalidResolution { resolution })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn adaptive_precision() {
+        // geohash precision 5 cells are roughly 4.9km x 4.9km at the
+        // equator, but narrow to 2.4km x 4.9km at 60 degrees
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.get_adaptive_precision(0.0, 160.0), Ok(7));
+        assert_eq!(geocode.get_adaptive_precision(0.0, 150.0), Ok(8));
+        assert_eq!(geocode.get_adaptive_precision(0.0, 4000.0), Ok(6));
+        assert_eq!(geocode.get_adaptive_precision(60.0, 4000.0), Ok(5));
+        assert_eq!(geocode.get_precision_for_resolution(4000.0, Some(60.0)),
+            Ok(6));
+
+        // mercator cells shrink in both dimensions
+        let geocode = Geocode::QuadTile;
+        assert_eq!(geocode.get_adaptive_precision(0.0, 1000.0), Ok(16));
+        assert_eq!(geocode.get_adaptive_precision(60.0, 1000.0), Ok(15));
+        assert_eq!(geocode.get_adaptive_precision(80.0, 1000.0), Ok(13));
+
+        assert!(geocode.get_adaptive_precision(91.0, 1000.0).is_err());
+        assert_eq!(geocode.get_adaptive_precision(0.0, -1.0),
+            Err(GeocodeError::InvalidResolution { resolution: -1.0 }));
+    }
+
+    #[test]
+    fn equivalent_precision() {
+        // a geohash precision maps to itself and its binary refinements
+        let geocode = Geocode::Geohash;
+        for precision in 1..=12 {
+            assert_eq!(geocode.get_equivalent_precision(precision, geocode),
+                Ok(precision));
+        }
+
+        assert_eq!(geocode.get_equivalent_precision(4, Geocode::Geohash16),
+            Ok(5));
+        assert_eq!(Geocode::Geohash16.get_equivalent_precision(5, geocode),
+            Ok(4));
+
+        // geohash precision 6 cells are roughly 1.2km by 0.6km, near zoom
+        // 16 tiles at the equator
+        assert_eq!(geocode.get_equivalent_precision(6, Geocode::QuadTile),
+            Ok(16));
+        assert_eq!(geocode.get_equivalent_precision(6, Geocode::PlusCode),
+            Ok(8));
+        assert_eq!(Geocode::PlusCode.get_equivalent_precision(10,
+            Geocode::Maidenhead), Ok(10));
+
+        assert!(geocode.get_equivalent_precision(0, Geocode::QuadTile)
+            .is_err());
+        assert!(Geocode::PlusCode.get_equivalent_precision(3, geocode)
+            .is_err());
+    }
+
+    #[test]
+    fn intervals_meters() {
+        let geocode = Geocode::Geohash;
+        let (width, height) = geocode.get_intervals_meters(7, 0.0);
+        assert!((width - 152.71).abs() < 0.01);
+        assert!((height - 152.71).abs() < 0.01);
+
+        // degree based cells narrow with latitude
+        let (width, height) = geocode.get_intervals_meters(7, 60.0);
+        assert!((width - 76.35).abs() < 0.01);
+        assert!((height - 152.71).abs() < 0.01);
+
+        // mercator cells shrink with latitude in both dimensions
+        let geocode = Geocode::QuadTile;
+        let (width, height) = geocode.get_intervals_meters(1, 0.0);
+        assert!((width - 20037508.34).abs() < 0.01);
+        assert_eq!(width, height);
+        let (width, height) = geocode.get_intervals_meters(1, 60.0);
+        assert!((width - 10018754.17).abs() < 0.01);
+        assert_eq!(width, height);
+    }
+
+    #[test]
+    fn precision_for_resolution() {
+        // geohash precision 7 cells are roughly 153m x 153m
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.get_precision_for_resolution(200.0, None), Ok(7));
+        assert_eq!(geocode.get_precision_for_resolution(100.0, None), Ok(8));
+        assert_eq!(geocode.get_precision_for_resolution(1e7, None), Ok(1));
+
+        // cells narrow away from the equator
+        let geocode = Geocode::QuadTile;
+        let equator = geocode.get_precision_for_resolution(1000.0, None)
+            .unwrap();
+        assert_eq!(equator, 16);
+        assert_eq!(geocode.get_precision_for_resolution(1000.0, Some(60.0)),
+            Ok(equator - 1));
+
+        // only valid precisions are returned
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.get_precision_for_resolution(1000.0, None),
+            Ok(8));
+        assert_eq!(geocode.get_precision_for_resolution(1.0, None),
+            Ok(12));
+        assert_eq!(geocode.get_precision_for_resolution(1e-3, None),
+            Err(GeocodeError::InvalidResolution { resolution: 1e-3 }));
+        assert_eq!(Geocode::Maidenhead
+            .get_precision_for_resolution(1e5, None), Ok(6));
+
+        assert!(geocode.get_precision_for_resolution(0.0, None).is_err());
+        assert!(geocode.get_precision_for_resolution(f64::NAN, None)
+            .is_err());
+        assert!(geocode.get_precision_for_resolution(1.0, Some(91.0))
+            .is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{Geocode, GeocodeError, RangePolicy};
+
+use std::collections::HashSet;
+
+pub struct Spiral {
+    geocode: Geocode,
+    precision: usize,
+    center: (f64, f64),
+    intervals: (f64, f64),
+    ring: usize,
+    position: usize,
+    ring_empty: bool,
+    visited: HashSet<String>,
+}
+
+impl Spiral {
+    fn get_offsets(&self) -> (f64, f64) {
+        // walk clockwise from the north-west corner of the ring
+        let r = self.ring as f64;
+        let t = (self.position % (2 * self.ring)) as f64;
+        match self.position / (2 * self.ring) {
+            0 => (-r + t, r),
+            1 => (r, r - t),
+            2 => (r - t, -r),
+            _ => (-r, -r + t),
+        }
+    }
+}
+
+impl Iterator for Spiral {
+    type Item = (usize, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // ring zero contains only the center cell
+            if self.ring == 0 {
+                self.ring = 1;
+                let code = self.geocode.encode(self.center.0, self.center.1,
+                    self.precision).ok()?;
+                self.visited.insert(code.clone());
+                return Some((0, code));
+            }
+
+            // advance to the next ring after each full ring, where rings
+            // without unvisited cells end the spiral
+            if self.position == 8 * self.ring {
+                if self.ring_empty {
+                    return None;
+                }
+
+                self.ring += 1;
+                self.position = 0;
+                self.ring_empty = true;
+            }
+
+            let (x_offset, y_offset) = self.get_offsets();
+            self.position += 1;
+
+            let x = self.center.0 + x_offset * self.intervals.0;
+            let y = self.center.1 + y_offset * self.intervals.1;
+
+            // rings wrap across the antimeridian, skipping cells reached
+            // from the other side and those beyond the poles
+            match self.geocode.encode_with_policy(x, y, self.precision,
+                    RangePolicy::Wrap) {
+                Ok(code) if self.visited.insert(code.clone()) => {
+                    self.ring_empty = false;
+                    return Some((self.ring, code));
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+impl Geocode {
+    pub fn k_ring(&self, value: &str, k: usize)
+            -> Result<Vec<String>, GeocodeError> {
+        Ok(self.spiral(value)?
+            .take_while(|(ring, _)| *ring <= k)
+            .map(|(_, code)| code)
+            .collect())
+    }
+
+    pub fn spiral(&self, value: &str) -> Result<Spiral, GeocodeError> {
+        let bbox = self.decode(value)?;
+        Ok(Spiral {
+            geocode: *self,
+            precision: self.get_precision(value)?,
+            center: bbox.center(),
+            intervals: (bbox.width(), bbox.height()),
+            ring: 0,
+            position: 0,
+            ring_empty: true,
+            visited: HashSet::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn k_ring() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.k_ring("dpc5u6", 0).unwrap(), vec!["dpc5u6"]);
+        assert_eq!(geocode.k_ring("dpc5u6", 1).unwrap(), vec!["dpc5u6",
+            "dpc5u5", "dpc5u7", "dpc5ue", "dpc5ud", "dpc5u9",
+            "dpc5u3", "dpc5u1", "dpc5u4"]);
+
+        // check rings contain all cells within k cells
+        let cells = geocode.k_ring("dpc5u6", 2).unwrap();
+        assert_eq!(cells.len(), 25);
+        for neighbor in geocode.neighbors("dpc5u6").unwrap() {
+            assert!(cells[1..9].contains(&neighbor));
+            for neighbor in geocode.neighbors(&neighbor).unwrap() {
+                assert!(cells.contains(&neighbor));
+            }
+        }
+
+        assert_eq!(geocode.k_ring("", 1), Err(GeocodeError::EmptyCode));
+
+        // rings wrap across the antimeridian like neighbors
+        let code = geocode.encode(179.9, 10.0, 4).unwrap();
+        let cells = geocode.k_ring(&code, 1).unwrap();
+        assert_eq!(cells.len(), 9);
+        for neighbor in geocode.neighbors(&code).unwrap() {
+            assert!(cells.contains(&neighbor));
+        }
+
+        let cells = geocode.k_ring(&code, 2).unwrap();
+        assert_eq!(cells.len(), 25);
+        assert!(cells.contains(&geocode.encode(-179.9, 10.0, 4).unwrap()));
+    }
+
+    #[test]
+    fn spiral() {
+        let geocode = Geocode::QuadTile;
+        let cells: Vec<(usize, String)> = geocode.spiral("00").unwrap()
+            .collect();
+
+        // check the spiral visits every cell exactly once
+        assert_eq!(cells.len(), 16);
+        let mut codes: Vec<&String> = cells.iter().map(|x| &x.1).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), 16);
+
+        // check cells are ordered by ring
+        assert_eq!(cells[0], (0, "00".to_string()));
+        assert!(cells.windows(2).all(|x| x[0].0 <= x[1].0));
+        // rings wrap across the antimeridian, so cells on the far side
+        // are reached in fewer rings
+        assert_eq!(cells.iter().filter(|x| x.0 == 1).count(), 5);
+        assert_eq!(cells.iter().filter(|x| x.0 == 2).count(), 6);
+        assert_eq!(cells.iter().filter(|x| x.0 == 3).count(), 4);
+    }
+}
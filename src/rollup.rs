@@ -0,0 +1,93 @@
+use crate::{Binner, GeocodeError};
+
+impl Binner {
+    pub fn pyramid(&self) -> Result<Vec<Binner>, GeocodeError> {
+        // successively coarser rollups, ending at the coarsest precision
+        let mut levels: Vec<Binner> = Vec::new();
+        let geocode = self.get_geocode();
+        while geocode.get_parent_precision(levels.last()
+                .unwrap_or(self).get_precision()).is_some() {
+            let level = levels.last().unwrap_or(self).rollup()?;
+            levels.push(level);
+        }
+
+        Ok(levels)
+    }
+
+    pub fn rollup(&self) -> Result<Binner, GeocodeError> {
+        let geocode = self.get_geocode();
+        let precision = geocode.get_parent_precision(self.get_precision())
+            .ok_or(GeocodeError::InvalidPrecision {
+                precision: self.get_precision() })?;
+
+        // aggregates merge, so parents derive from children alone
+        let mut binner = Binner::new(geocode, precision)?;
+        for (cell, aggregate) in self.iter() {
+            binner.insert_aggregate(&geocode.parent(&cell)?, aggregate)?;
+        }
+
+        Ok(binner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Aggregate, Binner, Geocode, GeocodeError};
+
+    #[test]
+    fn pyramid() {
+        let geocode = Geocode::PlusCode;
+        let mut binner = Binner::new(geocode, 10).unwrap();
+        for (x, y, value) in [(-88.4, 44.26, 1.0), (-88.39, 44.27, 3.0),
+                (-122.4, 37.8, 7.0)].iter() {
+            binner.insert(*x, *y, *value).unwrap();
+        }
+
+        let levels = binner.pyramid().unwrap();
+        let precisions: Vec<usize> =
+            levels.iter().map(|x| x.get_precision()).collect();
+        assert_eq!(precisions, vec![8, 6, 4, 2]);
+
+        // every level preserves the totals of the raw values
+        for level in levels.iter() {
+            let count: u64 = level.iter().map(|x| x.1.count).sum();
+            let sum: f64 = level.iter().map(|x| x.1.sum).sum();
+            assert_eq!((count, sum), (3, 11.0));
+        }
+
+        let cells: Vec<String> = levels[3].iter().map(|x| x.0).collect();
+        assert_eq!(cells, vec!["84000000+".to_string(),
+            "86000000+".to_string()]);
+        assert_eq!(levels[3].get("86000000+"), Some(&Aggregate {
+            count: 2, sum: 4.0, min: 1.0, max: 3.0 }));
+
+        assert!(levels[3].pyramid().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rollup() {
+        let geocode = Geocode::Geohash;
+        let mut binner = Binner::new(geocode, 5).unwrap();
+        for (x, y, value) in [(-88.4, 44.26, 1.0), (-88.39, 44.27, 3.0),
+                (-88.36, 44.22, 8.0), (-122.4, 37.8, 7.0)].iter() {
+            binner.insert(*x, *y, *value).unwrap();
+        }
+
+        let parents = binner.rollup().unwrap();
+        assert_eq!(parents.get_precision(), 4);
+        assert_eq!(parents.len(), 2);
+        assert_eq!(parents.get("dpc5"), Some(&Aggregate { count: 3,
+            sum: 12.0, min: 1.0, max: 8.0 }));
+        assert_eq!(parents.get("dpc5").unwrap().mean(), 4.0);
+
+        // rolling up twice matches binning directly at the coarser level
+        let mut direct = Binner::new(geocode, 3).unwrap();
+        for (cell, aggregate) in binner.iter() {
+            direct.insert_aggregate(&cell[..3], aggregate).unwrap();
+        }
+
+        assert_eq!(parents.rollup().unwrap(), direct);
+        assert_eq!(Binner::new(geocode, 1).unwrap().rollup(),
+            Err(GeocodeError::InvalidPrecision { precision: 1 }));
+    }
+}
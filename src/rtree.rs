@@ -0,0 +1,106 @@
+use crate::{BoundingBox, CellSet, Geocode, GeocodeError};
+
+use rstar::{Envelope, PointDistance, RTree, RTreeObject, AABB};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    code: String,
+    bounds: BoundingBox,
+}
+
+impl Cell {
+    pub fn new(geocode: Geocode, value: &str) -> Result<Cell, GeocodeError> {
+        let bounds = geocode.decode(value)?;
+        Ok(Cell { code: value.to_string(), bounds })
+    }
+
+    pub fn get_bounds(&self) -> &BoundingBox {
+        &self.bounds
+    }
+
+    pub fn get_code(&self) -> &str {
+        &self.code
+    }
+}
+
+impl RTreeObject for Cell {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> AABB<[f64; 2]> {
+        AABB::from_corners([self.bounds.min_x, self.bounds.min_y],
+            [self.bounds.max_x, self.bounds.max_y])
+    }
+}
+
+impl PointDistance for Cell {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        // cells are filled so interior points have zero distance
+        self.envelope().distance_2(point)
+    }
+
+    fn contains_point(&self, point: &[f64; 2]) -> bool {
+        self.envelope().contains_point(point)
+    }
+}
+
+impl Geocode {
+    pub fn to_rtree(&self, cells: &[String])
+            -> Result<RTree<Cell>, GeocodeError> {
+        let mut objects = Vec::new();
+        for cell in cells.iter() {
+            objects.push(Cell::new(*self, cell)?);
+        }
+
+        Ok(RTree::bulk_load(objects))
+    }
+}
+
+impl CellSet {
+    pub fn to_rtree(&self) -> Result<RTree<Cell>, GeocodeError> {
+        let cells: Vec<String> = self.iter().collect();
+        self.get_geocode().to_rtree(&cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+    use super::Cell;
+
+    use rstar::{PointDistance, AABB};
+
+    #[test]
+    fn cell() {
+        let cell = Cell::new(Geocode::Geohash, "dpc5u6").unwrap();
+        assert_eq!(cell.get_code(), "dpc5u6");
+        assert_eq!(cell.get_bounds(),
+            &Geocode::Geohash.decode("dpc5u6").unwrap());
+        assert!(cell.contains_point(&[-88.4, 44.266667]));
+        assert_eq!(cell.distance_2(&[-88.4, 44.266667]), 0.0);
+        assert!(cell.distance_2(&[-88.0, 44.266667]) > 0.0);
+        assert_eq!(Cell::new(Geocode::Geohash, ""),
+            Err(GeocodeError::EmptyCode));
+    }
+
+    #[test]
+    fn to_rtree() {
+        let geocode = Geocode::Geohash;
+        let cells = geocode.cover_radius(-88.4, 44.266667, 2000.0, 6)
+            .unwrap();
+        let rtree = geocode.to_rtree(&cells).unwrap();
+        assert_eq!(rtree.size(), cells.len());
+
+        // locate the cell containing a point and its nearest neighbor
+        let found: Vec<&str> = rtree.locate_all_at_point(&[-88.4, 44.266667])
+            .map(|x| x.get_code()).collect();
+        assert_eq!(found, vec!["dpc5u6"]);
+        assert!(rtree.nearest_neighbor(&[-88.0, 44.266667]).is_some());
+
+        let envelope = AABB::from_corners([-88.41, 44.26], [-88.39, 44.27]);
+        assert!(rtree.locate_in_envelope_intersecting(&envelope).count() > 1);
+
+        // cell sets load their compacted cells
+        let cell_set = CellSet::from_cells(geocode, cells).unwrap();
+        assert_eq!(cell_set.to_rtree().unwrap().size(), cell_set.len());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Geocode, GeocodeError};
+
+use rand::Rng;
+
+impl Geocode {
+    pub fn sample_point<R: Rng + ?Sized>(&self, value: &str, rng: &mut R)
+            -> Result<(f64, f64), GeocodeError> {
+        // sample on the sphere, where area is uniform in longitude and the
+        // sine of latitude, then project into native coordinates
+        let bbox = self.decode(value)?;
+        let lonlat = self.decode_lonlat(value)?;
+
+        // bisection geocodes assign points on cell edges to the west and
+        // south cells, where the others assign them to the east and north
+        // cells, so offsets within [0, 1) start from the included edges
+        let upper = self.has_upper_edges();
+        let mut sample = |min: f64, max: f64| match upper {
+            true => min + (max - min) * rng.random::<f64>(),
+            false => max - (max - min) * rng.random::<f64>(),
+        };
+
+        let lon = sample(lonlat.min_x, lonlat.max_x);
+        let lat = sample(lonlat.min_y.to_radians().sin(),
+            lonlat.max_y.to_radians().sin()).asin().to_degrees();
+
+        let (x, y) = self.wgs84_to_native(lon, lat);
+        Ok((x.clamp(bbox.min_x, bbox.max_x), y.clamp(bbox.min_y, bbox.max_y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Geocode;
+
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn sample_point() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        for (geocode, code) in [(Geocode::Geohash, "dpc5u6"),
+                (Geocode::QuadTile, "030222"), (Geocode::PlusCode, "86HWG8GG+"),
+                (Geocode::Maidenhead, "FN31pr")].iter() {
+            for _ in 0..100 {
+                let (x, y) = geocode.sample_point(code, &mut rng).unwrap();
+                assert_eq!(&geocode.encode(x, y, code.len()
+                    - code.matches('+').count()).unwrap(), code);
+            }
+        }
+
+        // latitudes are weighted by area, so the equatorward half of a
+        // cell reaching the pole holds its larger share of points
+        let geocode = Geocode::Geohash;
+        let bbox = geocode.decode("u").unwrap();
+        let (_, center) = bbox.center();
+        let below = (0..10000).filter(|_| geocode.sample_point("u", &mut rng)
+            .unwrap().1 < center).count();
+        let expected = ((center.to_radians().sin()
+            - bbox.min_y.to_radians().sin())
+            / (bbox.max_y.to_radians().sin() - bbox.min_y.to_radians().sin()))
+            * 10000f64;
+        assert!((below as f64 - expected).abs() < 200.0, "{} vs {}", below,
+            expected);
+
+        assert!(geocode.sample_point("dpa", &mut rng).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Code;
+#[cfg(feature = "std")]
+use crate::{CellSet, Geocode};
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Formatter};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+impl<const N: usize> Serialize for Code<N> {
+    fn serialize<S: Serializer>(&self, serializer: S)
+            -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Code<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+            -> Result<Code<N>, D::Error> {
+        struct CodeVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for CodeVisitor<N> {
+            type Value = Code<N>;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                write!(f, "a code of at most {} bytes", N)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str)
+                    -> Result<Code<N>, E> {
+                Code::try_from(value).map_err(|_| E::invalid_length(
+                    value.len(), &self))
+            }
+        }
+
+        deserializer.deserialize_str(CodeVisitor::<N>)
+    }
+}
+
+// cell sets are serialized as their geocode and compacted cells
+#[cfg(feature = "std")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct CellSetData {
+    geocode: Geocode,
+    cells: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl Serialize for CellSet {
+    fn serialize<S: Serializer>(&self, serializer: S)
+            -> Result<S::Ok, S::Error> {
+        let cells = self.iter().collect();
+        CellSetData { geocode: self.get_geocode(), cells }
+            .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for CellSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+            -> Result<CellSet, D::Error> {
+        // validate and compact cells as they are loaded
+        let data = CellSetData::deserialize(deserializer)?;
+        CellSet::from_cells(data.geocode, data.cells)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Code, Geocode};
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn geocode() {
+        assert_eq!(serde_json::to_string(&Geocode::QuadTile).unwrap(),
+            "\"quadtile\"");
+        assert_eq!(serde_json::from_str::<Vec<Geocode>>(
+            "[\"geohash\", \"geohash16\", \"pluscode\"]").unwrap(),
+            vec![Geocode::Geohash, Geocode::Geohash16, Geocode::PlusCode]);
+        assert!(serde_json::from_str::<Geocode>("\"Geohash\"").is_err());
+
+        let bbox = BoundingBox::new(-1.0, 1.0, -2.0, 2.0);
+        let json = serde_json::to_string(&bbox).unwrap();
+        assert_eq!(json, "{\"min_x\":-1.0,\"max_x\":1.0,\
+            \"min_y\":-2.0,\"max_y\":2.0}");
+        assert_eq!(serde_json::from_str::<BoundingBox>(&json).unwrap(), bbox);
+    }
+
+    #[test]
+    fn code() {
+        let code: Code = Code::try_from("dpc5u6").unwrap();
+        assert_eq!(serde_json::to_string(&code).unwrap(), "\"dpc5u6\"");
+        assert_eq!(serde_json::from_str::<Code>("\"dpc5u6\"").unwrap(), code);
+        assert!(serde_json::from_str::<Code<4>>("\"dpc5u6\"").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn cell_set() {
+        use crate::CellSet;
+
+        let cell_set = CellSet::from_cells(Geocode::QuadTile, vec![
+            "0".to_string(), "12".to_string()]).unwrap();
+        let json = serde_json::to_string(&cell_set).unwrap();
+        assert_eq!(json, "{\"geocode\":\"quadtile\",\"cells\":[\"0\",\"12\"]}");
+        assert_eq!(serde_json::from_str::<CellSet>(&json).unwrap(), cell_set);
+
+        // cells are compacted and validated on load
+        assert_eq!(serde_json::from_str::<CellSet>("{\"geocode\":\
+            \"quadtile\",\"cells\":[\"120\",\"121\",\"122\",\"123\",\"0\"]}")
+            .unwrap(), cell_set);
+        assert!(serde_json::from_str::<CellSet>("{\"geocode\":\"geohash\",\
+            \"cells\":[\"dpca\"]}").is_err());
+    }
+}
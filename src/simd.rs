@@ -0,0 +1,121 @@
+use crate::{morton, Geocode, GeocodeError};
+
+const LANES: usize = 4;
+
+pub(crate) fn encode_batch(geocode: &Geocode, points: &[(f64, f64)],
+        precision: usize, char_bits: usize, codes: &[char],
+        outs: &mut [String]) -> Result<usize, GeocodeError> {
+    // only codes computed entirely from cell indices are vectorized
+    let bits = precision * char_bits;
+    if bits > 64 {
+        return Ok(0);
+    }
+
+    let bounds = geocode.get_bounds();
+    let (x_bits, y_bits) = (bits.div_ceil(2), bits / 2);
+    let mut count = 0;
+    for (chunk, outs) in points.chunks_exact(LANES)
+            .zip(outs.chunks_exact_mut(LANES)) {
+        // check if coordinates are valid
+        let (mut xs, mut ys) = ([0f64; LANES], [0f64; LANES]);
+        for (i, (x, y)) in chunk.iter().enumerate() {
+            if !bounds.contains(*x, *y) {
+                return Err(GeocodeError::CoordinateOutOfRange {
+                    x: *x, y: *y, bounds });
+            }
+
+            xs[i] = *x;
+            ys[i] = *y;
+        }
+
+        let x_indices = get_indices(&xs, bounds.min_x, bounds.width(), x_bits);
+        let y_indices =
+            get_indices(&ys, bounds.min_y, bounds.height(), y_bits);
+        for (i, out) in outs.iter_mut().enumerate() {
+            out.clear();
+            crate::push_interleaved(x_indices[i], y_indices[i],
+                precision, char_bits, |_| codes, out)?;
+        }
+
+        count += LANES;
+    }
+
+    Ok(count)
+}
+
+fn get_indices(values: &[f64; LANES], min: f64, range: f64, bits: usize)
+        -> [u32; LANES] {
+    let mut indices = [0u32; LANES];
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx") {
+            // estimates are corrected individually to match scalar encoding
+            let estimates = unsafe { get_estimates_avx(values, min, range,
+                2f64.powi(bits as i32)) };
+            for (i, index) in indices.iter_mut().enumerate() {
+                *index = morton::correct_index(values[i], min, range,
+                    bits, estimates[i] as u32);
+            }
+
+            return indices;
+        }
+    }
+
+    for (index, value) in indices.iter_mut().zip(values.iter()) {
+        *index = morton::get_index(*value, min, range, bits);
+    }
+
+    indices
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn get_estimates_avx(values: &[f64; LANES], min: f64, range: f64,
+        cells: f64) -> [f64; LANES] {
+    use std::arch::x86_64::*;
+
+    // mirror the scalar arithmetic in morton::get_index
+    let value = _mm256_loadu_pd(values.as_ptr());
+    let scaled = _mm256_mul_pd(_mm256_div_pd(
+        _mm256_sub_pd(value, _mm256_set1_pd(min)),
+        _mm256_set1_pd(range)), _mm256_set1_pd(cells));
+    let index = _mm256_sub_pd(_mm256_ceil_pd(scaled), _mm256_set1_pd(1f64));
+    let index = _mm256_min_pd(_mm256_max_pd(index, _mm256_setzero_pd()),
+        _mm256_set1_pd(cells - 1f64));
+
+    let mut estimates = [0f64; LANES];
+    _mm256_storeu_pd(estimates.as_mut_ptr(), index);
+    estimates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn encode_batch() {
+        let points: Vec<(f64, f64)> = (0..1001)
+            .map(|i| (-180.0 + i as f64 * 0.36, -90.0 + i as f64 * 0.18))
+            .collect();
+        for (geocode, precision) in [(Geocode::Geohash, 12),
+                (Geocode::Geohash, 13), (Geocode::Geohash16, 9),
+                (Geocode::QuadTile, 32)].iter() {
+            let bounds = geocode.get_bounds();
+            let points: Vec<(f64, f64)> = points.iter()
+                .map(|(x, y)| (bounds.min_x + (x + 180.0) / 360.0
+                    * bounds.width(), bounds.min_y + (y + 90.0) / 180.0
+                    * bounds.height())).collect();
+
+            let codes = geocode.encode_batch(&points, *precision).unwrap();
+            for ((x, y), code) in points.iter().zip(codes.iter()) {
+                assert_eq!(&geocode.encode(*x, *y, *precision).unwrap(), code);
+            }
+        }
+
+        let mut points = points;
+        points[2] = (0.0, 91.0);
+        assert!(matches!(Geocode::Geohash.encode_batch(&points, 6),
+            Err(GeocodeError::CoordinateOutOfRange { .. })));
+    }
+}
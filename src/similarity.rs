@@ -0,0 +1,77 @@
+use crate::{CellSet, GeocodeError};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Similarity {
+    pub jaccard: f64,
+    pub overlap: f64,
+}
+
+impl CellSet {
+    pub fn similarity(&self, other: &CellSet)
+            -> Result<Similarity, GeocodeError> {
+        // coefficients weigh cells by area, so coarse cells count for
+        // their descendants without expanding them
+        let intersection = self.intersect(other)?;
+        let intersection_area = intersection.area()?;
+        let (area, other_area) = (self.area()?, other.area()?);
+
+        let union_area = area + other_area - intersection_area;
+        let min_area = area.min(other_area);
+        Ok(Similarity {
+            jaccard: match union_area > 0f64 {
+                true => intersection_area / union_area,
+                false => 0f64,
+            },
+            overlap: match min_area > 0f64 {
+                true => intersection_area / min_area,
+                false => 0f64,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+
+    fn cell_set(geocode: Geocode, cells: &[&str]) -> CellSet {
+        CellSet::from_cells(geocode,
+            cells.iter().map(|x| x.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn similarity() {
+        // mixed precisions match the fully expanded sets
+        let a = cell_set(Geocode::QuadTile, &["0", "10"]);
+        let b = cell_set(Geocode::QuadTile, &["00", "01", "1"]);
+        let similarity = a.similarity(&b).unwrap();
+
+        let expand = |x: &CellSet| -> Vec<String> {
+            Geocode::QuadTile.uncompact(x.iter().collect(), 2).unwrap()
+        };
+        let (a_cells, b_cells) = (expand(&a), expand(&b));
+        let area = |cells: &[&String]| -> f64 { cells.iter()
+            .map(|x| Geocode::QuadTile.cell_area(x).unwrap()).sum() };
+        let shared: Vec<&String> = a_cells.iter()
+            .filter(|x| b_cells.contains(x)).collect();
+        let all: Vec<&String> = a_cells.iter()
+            .chain(b_cells.iter().filter(|x| !a_cells.contains(x))).collect();
+        assert!((similarity.jaccard - area(&shared) / area(&all)).abs()
+            < 1e-9);
+        assert!((similarity.overlap - 1f64).abs() > 1e-3);
+
+        // subsets fully overlap
+        let c = cell_set(Geocode::Geohash, &["dp"]);
+        let d = cell_set(Geocode::Geohash, &["dpc5", "dpc6"]);
+        let similarity = c.similarity(&d).unwrap();
+        assert_eq!(similarity.overlap, 1.0);
+        assert!(similarity.jaccard > 0.0 && similarity.jaccard < 1e-2);
+        assert_eq!(c.similarity(&c).unwrap().jaccard, 1.0);
+
+        let empty = CellSet::new(Geocode::Geohash);
+        assert_eq!(c.similarity(&empty).unwrap().jaccard, 0.0);
+        assert_eq!(empty.similarity(&empty).unwrap().overlap, 0.0);
+        assert!(matches!(a.similarity(&c),
+            Err(GeocodeError::GeocodeMismatch { .. })));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{Geocode, GeocodeError};
+use crate::bloom::hash_key;
+
+const MAGIC: &[u8; 2] = b"GH";
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 6;
+const MIN_REGISTER_BITS: usize = 4;
+const MAX_REGISTER_BITS: usize = 16;
+
+// a hyperloglog sketch counting distinct cells at a fixed precision, with
+// a relative standard error of roughly 1.04 / sqrt(registers)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellSketch {
+    geocode: Geocode,
+    precision: usize,
+    register_bits: usize,
+    registers: Vec<u8>,
+}
+
+impl CellSketch {
+    pub fn new(geocode: Geocode, precision: usize, register_bits: usize)
+            -> Result<CellSketch, GeocodeError> {
+        // check if precision and register count are valid
+        let (x, y) = geocode.get_bounds().center();
+        geocode.encode(x, y, precision)?;
+        if !(MIN_REGISTER_BITS..=MAX_REGISTER_BITS).contains(&register_bits) {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "register bits {} is not within [{}, {}]", register_bits,
+                MIN_REGISTER_BITS, MAX_REGISTER_BITS) });
+        }
+
+        Ok(CellSketch { geocode, precision, register_bits,
+            registers: vec![0; 1 << register_bits] })
+    }
+
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1f64 + 1.079 / m),
+        };
+
+        let sum: f64 = self.registers.iter()
+            .map(|x| 2f64.powi(-(*x as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // small cardinalities are more accurately linear counted, while
+        // 64 bit hashes need no correction for large cardinalities
+        let zeros = self.registers.iter().filter(|x| **x == 0).count();
+        match estimate <= 2.5 * m && zeros > 0 {
+            true => m * (m / zeros as f64).ln(),
+            false => estimate,
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<CellSketch, GeocodeError> {
+        if bytes.len() < HEADER_LENGTH || &bytes[..2] != MAGIC
+                || bytes[2] != VERSION {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "unrecognized cell sketch header".to_string() });
+        }
+
+        let geocode = *Geocode::ALL.get(bytes[3] as usize)
+            .ok_or_else(|| GeocodeError::InvalidFormat {
+                reason: format!("unknown geocode index {}", bytes[3]) })?;
+        let mut sketch = CellSketch::new(geocode, bytes[4] as usize,
+            bytes[5] as usize)?;
+
+        let registers = &bytes[HEADER_LENGTH..];
+        let max_rank = (65 - sketch.register_bits) as u8;
+        if registers.len() != sketch.registers.len()
+                || registers.iter().any(|x| *x > max_rank) {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "invalid cell sketch registers".to_string() });
+        }
+
+        sketch.registers.copy_from_slice(registers);
+        Ok(sketch)
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn get_register_bits(&self) -> usize {
+        self.register_bits
+    }
+
+    pub fn insert(&mut self, x: f64, y: f64) -> Result<(), GeocodeError> {
+        let code = self.geocode.encode(x, y, self.precision)?;
+        self.insert_key(&self.geocode.parse_key(&code)?);
+        Ok(())
+    }
+
+    pub fn insert_cell(&mut self, value: &str) -> Result<(), GeocodeError> {
+        // cells must match the sketch precision to be counted once
+        let key = self.geocode.parse_key(value)?;
+        let precision = key.chars().count();
+        if precision != self.precision {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        self.insert_key(&key);
+        Ok(())
+    }
+
+    pub fn merge(&mut self, other: &CellSketch) -> Result<(), GeocodeError> {
+        // sketches over separate shards combine into a single estimate
+        if self.geocode != other.geocode {
+            return Err(GeocodeError::GeocodeMismatch {
+                expected: self.geocode, found: other.geocode });
+        } else if self.precision != other.precision {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: other.precision });
+        } else if self.register_bits != other.register_bits {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "register bits {} and {} differ", self.register_bits,
+                other.register_bits) });
+        }
+
+        for (register, other) in self.registers.iter_mut()
+                .zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let index = Geocode::ALL.iter()
+            .position(|x| *x == self.geocode).unwrap();
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH
+            + self.registers.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[VERSION, index as u8,
+            self.precision as u8, self.register_bits as u8]);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    fn insert_key(&mut self, key: &str) {
+        // leading hash bits select a register, which records the longest
+        // run of zeros in the remaining bits
+        let hash = hash_key(key);
+        let index = (hash >> (64 - self.register_bits)) as usize;
+        let remaining = (hash << self.register_bits)
+            | (1 << (self.register_bits - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::CellSketch;
+
+    #[test]
+    fn estimate() {
+        let mut sketch = CellSketch::new(Geocode::Geohash, 7, 12).unwrap();
+        assert_eq!(sketch.estimate(), 0.0);
+
+        // revisiting cells does not change the estimate
+        let cells = Geocode::Geohash.cover_bbox(&BoundingBox::new(-88.5,
+            -88.0, 44.0, 44.5), 7).unwrap();
+        for _ in 0..3 {
+            for cell in cells.iter() {
+                sketch.insert_cell(cell).unwrap();
+            }
+        }
+
+        let error = (sketch.estimate() - cells.len() as f64).abs()
+            / cells.len() as f64;
+        assert!(error < 0.05, "{} vs {}", sketch.estimate(), cells.len());
+
+        let mut sketch = CellSketch::new(Geocode::Geohash, 4, 8).unwrap();
+        sketch.insert(-88.4, 44.266667).unwrap();
+        sketch.insert(-88.4001, 44.2667).unwrap();
+        assert_eq!(sketch.estimate().round(), 1.0);
+
+        assert_eq!(sketch.insert_cell("dpc5u6"),
+            Err(GeocodeError::InvalidPrecision { precision: 6 }));
+        assert!(sketch.insert(0.0, 91.0).is_err());
+        assert!(CellSketch::new(Geocode::Geohash, 4, 3).is_err());
+        assert!(CellSketch::new(Geocode::Geohash, 0, 8).is_err());
+    }
+
+    #[test]
+    fn merge() {
+        let cells = Geocode::QuadTile.cover_bbox(&BoundingBox::new(
+            -10000000.0, -9000000.0, 5000000.0, 6000000.0), 12).unwrap();
+        let (mut a, mut b) = (CellSketch::new(Geocode::QuadTile, 12, 10)
+            .unwrap(), CellSketch::new(Geocode::QuadTile, 12, 10).unwrap());
+        let mut all = a.clone();
+        for (i, cell) in cells.iter().enumerate() {
+            match i % 3 {
+                0 => a.insert_cell(cell).unwrap(),
+                1 => b.insert_cell(cell).unwrap(),
+                _ => {
+                    a.insert_cell(cell).unwrap();
+                    b.insert_cell(cell).unwrap();
+                },
+            }
+
+            all.insert_cell(cell).unwrap();
+        }
+
+        // merging matches a single sketch over the union
+        a.merge(&b).unwrap();
+        assert_eq!(a, all);
+
+        assert!(a.merge(&CellSketch::new(Geocode::QuadTile, 11, 10)
+            .unwrap()).is_err());
+        assert!(a.merge(&CellSketch::new(Geocode::QuadTile, 12, 11)
+            .unwrap()).is_err());
+        assert!(matches!(a.merge(&CellSketch::new(Geocode::Geohash, 12, 10)
+            .unwrap()), Err(GeocodeError::GeocodeMismatch { .. })));
+    }
+
+    #[test]
+    fn to_bytes() {
+        let mut sketch = CellSketch::new(Geocode::PlusCode, 8, 6).unwrap();
+        sketch.insert(-88.4, 44.266667).unwrap();
+
+        let bytes = sketch.to_bytes();
+        assert_eq!(bytes.len(), 6 + 64);
+        assert_eq!(CellSketch::from_bytes(&bytes).unwrap(), sketch);
+        assert!(CellSketch::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(CellSketch::from_bytes(b"GH\x01\x00\x04\x20").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+impl Geocode {
+    pub fn snap(&self, x: f64, y: f64, precision: usize)
+            -> Result<(f64, f64), GeocodeError> {
+        // points move to the center of their containing cell
+        let (_, bbox) = self.encode_full(x, y, precision)?;
+        Ok(bbox.center())
+    }
+
+    pub fn snap_bbox(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<BoundingBox, GeocodeError> {
+        // check if bounding box is valid
+        if !(bbox.min_x <= bbox.max_x && bbox.min_y <= bbox.max_y) {
+            return Err(GeocodeError::InvalidBoundingBox { bounds: *bbox });
+        }
+
+        // expand corners outward to the edges of their containing cells
+        let (_, min) = self.encode_full(bbox.min_x, bbox.min_y, precision)?;
+        let (_, max) = self.encode_full(bbox.max_x, bbox.max_y, precision)?;
+
+        // points on cell edges encode to the lower cell for bisection
+        // geocodes and the upper cell otherwise, so grid aligned edges
+        // are kept either way
+        let (min_x, max_x) = align(bbox.min_x, bbox.max_x,
+            (min.min_x, min.max_x), (max.min_x, max.max_x));
+        let (min_y, max_y) = align(bbox.min_y, bbox.max_y,
+            (min.min_y, min.max_y), (max.min_y, max.max_y));
+
+        Ok(BoundingBox::new(min_x, max_x, min_y, max_y))
+    }
+}
+
+fn align(min: f64, max: f64, min_cell: (f64, f64), max_cell: (f64, f64))
+        -> (f64, f64) {
+    // degenerate extents keep the cells containing them
+    if min >= max {
+        return (min_cell.0, max_cell.1);
+    }
+
+    let min = match min >= min_cell.1 {
+        true => min_cell.1,
+        false => min_cell.0,
+    };
+    let max = match max <= max_cell.0 {
+        true => max_cell.0,
+        false => max_cell.1,
+    };
+
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+
+    #[test]
+    fn snap() {
+        let geocode = Geocode::Geohash;
+        let (x, y) = geocode.snap(-88.4, 44.266667, 6).unwrap();
+        let bbox = geocode.decode("dpc5u6").unwrap();
+        assert_eq!((x, y), bbox.center());
+
+        // nearby points share a snapped location
+        assert_eq!(geocode.snap(bbox.min_x + 1e-6, bbox.max_y - 1e-6, 6),
+            Ok((x, y)));
+
+        assert!(geocode.snap(-181.0, 0.0, 6).is_err());
+        assert!(geocode.snap(-88.4, 44.266667, 0).is_err());
+
+        let geocode = Geocode::PlusCode;
+        let code = geocode.encode(-88.4, 44.266667, 10).unwrap();
+        assert_eq!(geocode.snap(-88.4, 44.266667, 10).unwrap(),
+            geocode.decode(&code).unwrap().center());
+    }
+
+    #[test]
+    fn snap_bbox() {
+        let geocode = Geocode::QuadTile;
+        let bbox = BoundingBox::new(-9850000.0, -9840000.0,
+            5500000.0, 5510000.0);
+        let snapped = geocode.snap_bbox(&bbox, 12).unwrap();
+        assert!(snapped.contains(bbox.min_x, bbox.min_y)
+            && snapped.contains(bbox.max_x, bbox.max_y));
+
+        // snapped edges align with the grid
+        let (width, height) = geocode.get_intervals(12);
+        let min = geocode.decode(&geocode.encode(bbox.min_x, bbox.min_y, 12)
+            .unwrap()).unwrap();
+        assert_eq!((snapped.min_x, snapped.min_y), (min.min_x, min.min_y));
+        assert!((snapped.width() / width - 2.0).abs() < 1e-6);
+        assert!((snapped.height() / height - 2.0).abs() < 1e-6);
+        assert_eq!(geocode.snap_bbox(&snapped, 12), Ok(snapped));
+
+        assert!(matches!(geocode.snap_bbox(&BoundingBox::new(1.0, 0.0,
+            0.0, 1.0), 12), Err(GeocodeError::InvalidBoundingBox { .. })));
+        assert!(geocode.snap_bbox(&BoundingBox::new(0.0, 3e7, 0.0, 1.0), 12)
+            .is_err());
+
+        // aligned boxes are kept when edges encode to the upper cell
+        let geocode = Geocode::Maidenhead;
+        let bbox = geocode.decode("EN54").unwrap();
+        assert_eq!(geocode.snap_bbox(&bbox, 4), Ok(bbox));
+        assert_eq!(geocode.snap_bbox(&BoundingBox::new(-89.5, -88.0, 44.5,
+            45.0), 2), Ok(geocode.decode("EN").unwrap()));
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{Geocode, GeocodeError};
+use crate::error::io_error;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// distinguishes sorted runs written concurrently by a single process
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortOptions {
+    pub delimiter: char,
+    pub column: usize,
+    // records held in memory before a sorted run is spilled to disk
+    pub chunk_size: usize,
+    pub temp_dir: PathBuf,
+    // keep only the first record of each cell
+    pub unique: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> SortOptions {
+        SortOptions { delimiter: ',', column: 0, chunk_size: 1_000_000,
+            temp_dir: std::env::temp_dir(), unique: false }
+    }
+}
+
+// a sorted run on disk, removed once merged
+struct Run {
+    path: PathBuf,
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// a sorted record source and the key of its current record
+struct Source<R: BufRead> {
+    lines: Lines<R>,
+    key: Option<String>,
+    line: String,
+}
+
+impl Geocode {
+    pub fn merge_records<R: BufRead, W: Write>(&self, inputs: Vec<R>,
+            output: W, options: &SortOptions) -> Result<usize, GeocodeError> {
+        let mut sources = Vec::new();
+        for input in inputs {
+            sources.push(Source { lines: input.lines(),
+                key: None, line: String::new() });
+        }
+
+        // order by key and then input so equal keys remain stable
+        let mut heap = BinaryHeap::new();
+        for (i, source) in sources.iter_mut().enumerate() {
+            if let Some(key) = self.advance(source, i, options)? {
+                heap.push(Reverse((key, i)));
+            }
+        }
+
+        let mut writer = BufWriter::new(output);
+        let (mut count, mut previous) = (0, None);
+        while let Some(Reverse((key, i))) = heap.pop() {
+            if !options.unique || previous.as_ref() != Some(&key) {
+                writer.write_all(sources[i].line.as_bytes())
+                    .and_then(|_| writer.write_all(b"\n"))
+                    .map_err(io_error)?;
+                count += 1;
+            }
+
+            if let Some(next) = self.advance(&mut sources[i], i, options)? {
+                heap.push(Reverse((next, i)));
+            }
+
+            previous = Some(key);
+        }
+
+        writer.flush().map_err(io_error)?;
+        Ok(count)
+    }
+
+    pub fn sort_records<R: BufRead, W: Write>(&self, input: R, output: W,
+            options: &SortOptions) -> Result<usize, GeocodeError> {
+        if options.chunk_size == 0 {
+            return Err(GeocodeError::InvalidFormat {
+                reason: "chunk size must be positive".to_string() });
+        }
+
+        // sort chunks in memory, spilling each full chunk to a run
+        let (mut runs, mut chunk) = (Vec::new(), Vec::new());
+        for (i, line) in input.lines().enumerate() {
+            let line = line.map_err(io_error)?;
+            if line.is_empty() {
+                continue;
+            }
+
+            chunk.push((self.get_record_key(&line, i + 1, options)?, line));
+            if chunk.len() >= options.chunk_size {
+                runs.push(self.write_run(&mut chunk, options)?);
+            }
+        }
+
+        // inputs fitting in a single chunk never touch the disk
+        if runs.is_empty() {
+            sort_chunk(&mut chunk, options);
+            let mut writer = BufWriter::new(output);
+            for (_, line) in chunk.iter() {
+                writer.write_all(line.as_bytes())
+                    .and_then(|_| writer.write_all(b"\n"))
+                    .map_err(io_error)?;
+            }
+
+            writer.flush().map_err(io_error)?;
+            return Ok(chunk.len());
+        } else if !chunk.is_empty() {
+            runs.push(self.write_run(&mut chunk, options)?);
+        }
+
+        let mut inputs = Vec::new();
+        for run in runs.iter() {
+            inputs.push(BufReader::new(File::open(&run.path)
+                .map_err(io_error)?));
+        }
+
+        self.merge_records(inputs, output, options)
+    }
+
+    fn advance<R: BufRead>(&self, source: &mut Source<R>, index: usize,
+            options: &SortOptions) -> Result<Option<String>, GeocodeError> {
+        loop {
+            let line = match source.lines.next() {
+                Some(line) => line.map_err(io_error)?,
+                None => return Ok(None),
+            };
+
+            if line.is_empty() {
+                continue;
+            }
+
+            // merging requires each input to already be sorted
+            let key = self.get_record_key(&line, 0, options)?;
+            if source.key.as_ref().is_some_and(|x| key < *x) {
+                return Err(GeocodeError::InvalidFormat { reason: format!(
+                    "input {} is not sorted at '{}'", index, line) });
+            }
+
+            source.key = Some(key.clone());
+            source.line = line;
+            return Ok(Some(key));
+        }
+    }
+
+    fn get_record_key(&self, line: &str, line_number: usize,
+            options: &SortOptions) -> Result<String, GeocodeError> {
+        // records are ordered by key so ancestors precede descendants
+        match line.split(options.delimiter).nth(options.column) {
+            Some(value) => self.parse_key(value.trim()),
+            None => Err(GeocodeError::InvalidFormat { reason: match
+                    line_number {
+                0 => format!("record '{}' has no column {}",
+                    line, options.column),
+                _ => format!("line {} has no column {}",
+                    line_number, options.column),
+            }}),
+        }
+    }
+
+    fn write_run(&self, chunk: &mut Vec<(String, String)>,
+            options: &SortOptions) -> Result<Run, GeocodeError> {
+        sort_chunk(chunk, options);
+
+        let run = Run { path: options.temp_dir.join(format!(
+            "geocode-sort-{}-{}.tmp", std::process::id(),
+            RUN_COUNTER.fetch_add(1, Ordering::Relaxed))) };
+        let mut writer = BufWriter::new(File::create(&run.path)
+            .map_err(io_error)?);
+        for (_, line) in chunk.drain(..) {
+            writer.write_all(line.as_bytes())
+                .and_then(|_| writer.write_all(b"\n"))
+                .map_err(io_error)?;
+        }
+
+        writer.flush().map_err(io_error)?;
+        Ok(run)
+    }
+}
+
+fn sort_chunk(chunk: &mut Vec<(String, String)>, options: &SortOptions) {
+    // stable so the first record of each cell is retained
+    chunk.sort_by(|a, b| a.0.cmp(&b.0));
+    if options.unique {
+        chunk.dedup_by(|a, b| a.0 == b.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::SortOptions;
+
+    fn sort(geocode: Geocode, input: &str, options: &SortOptions)
+            -> Result<String, GeocodeError> {
+        let mut output = Vec::new();
+        geocode.sort_records(input.as_bytes(), &mut output, options)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn merge_records() {
+        let geocode = Geocode::Geohash;
+        let inputs = vec!["9xjq,a\ndpc5,b\n".as_bytes(),
+            "dp,c\ndpc5,d\nzz,e\n".as_bytes(), "".as_bytes()];
+        let mut output = Vec::new();
+        assert_eq!(geocode.merge_records(inputs, &mut output,
+            &SortOptions::default()).unwrap(), 5);
+        assert_eq!(String::from_utf8(output).unwrap(),
+            "9xjq,a\ndp,c\ndpc5,b\ndpc5,d\nzz,e\n");
+
+        let options = SortOptions { unique: true, ..SortOptions::default() };
+        let inputs = vec!["dpc5,b\n".as_bytes(), "dpc5,d\n".as_bytes()];
+        let mut output = Vec::new();
+        assert_eq!(geocode.merge_records(inputs, &mut output, &options)
+            .unwrap(), 1);
+        assert_eq!(output, b"dpc5,b\n");
+
+        let inputs = vec!["dpc5,b\n9xjq,a\n".as_bytes()];
+        assert!(geocode.merge_records(inputs, Vec::new(),
+            &SortOptions::default()).is_err());
+    }
+
+    #[test]
+    fn sort_records() {
+        let geocode = Geocode::Geohash;
+        let input = "a\tdpc5u6\nb\t9xjq\n\nc\tdpc5\nd\tdpc5u6\ne\t9xjq\n";
+        let mut options = SortOptions { delimiter: '\t', column: 1,
+            ..SortOptions::default() };
+        let expected = "b\t9xjq\ne\t9xjq\nc\tdpc5\na\tdpc5u6\nd\tdpc5u6\n";
+
+        // spilled runs produce the same stable ordering as memory
+        for chunk_size in [1, 2, 100].iter() {
+            options.chunk_size = *chunk_size;
+            assert_eq!(sort(geocode, input, &options).unwrap(), expected);
+        }
+
+        options.unique = true;
+        for chunk_size in [1, 2, 100].iter() {
+            options.chunk_size = *chunk_size;
+            assert_eq!(sort(geocode, input, &options).unwrap(),
+                "b\t9xjq\nc\tdpc5\na\tdpc5u6\n");
+        }
+
+        // runs are removed once merged
+        let prefix = format!("geocode-sort-{}-", std::process::id());
+        assert!(!std::fs::read_dir(&options.temp_dir).unwrap()
+            .filter_map(|x| x.ok())
+            .any(|x| x.file_name().to_string_lossy().starts_with(&prefix)));
+
+        assert!(sort(geocode, "dpc5\ndpa\n", &SortOptions::default())
+            .is_err());
+        assert_eq!(sort(geocode, "a\tdpc5\nb\n", &options),
+            Err(GeocodeError::InvalidFormat {
+                reason: "line 2 has no column 1".to_string() }));
+        assert!(sort(geocode, "", &SortOptions { chunk_size: 0,
+            ..SortOptions::default() }).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{morton, push_interleaved, BoundingBox, Geocode, GeocodeError};
+use crate::cover::cell_range;
+use crate::zorder::merge_ranges;
+
+const X: u8 = 0;
+const Y: u8 = 1;
+const T: u8 = 2;
+
+// keys interleaving the bits of a geocode cell with a bucketed timestamp,
+// so that nearby places and times share leading bits
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpatioTemporal {
+    geocode: Geocode,
+    precision: usize,
+    start: i64,
+    resolution: i64,
+    // the axis split by each bit of a key, from the most significant
+    order: Vec<u8>,
+    bits: [usize; 3],
+}
+
+impl SpatioTemporal {
+    pub fn new(geocode: Geocode, precision: usize, start: i64, end: i64,
+            resolution: i64) -> Result<SpatioTemporal, GeocodeError> {
+        // check if time range and resolution are valid
+        if end <= start || resolution <= 0 {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "invalid time range {} - {} at resolution {}",
+                start, end, resolution) });
+        }
+
+        // keys hold the spatial and time bits within 64 bits
+        let (char_bits, _) = geocode.get_id_parameters()?;
+        let buckets = end.abs_diff(start).div_ceil(resolution as u64);
+        let time_bits = (u64::BITS - (buckets - 1).leading_zeros()) as usize;
+        let spatial_bits = precision * char_bits;
+        if precision == 0 || spatial_bits + time_bits > 64 {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        // distribute time bits evenly among the spatial bits, where the
+        // first spatial bit splits x
+        let mut order = Vec::with_capacity(spatial_bits + time_bits);
+        let (mut spatial, mut time) = (0, 0);
+        while spatial + time < spatial_bits + time_bits {
+            if spatial < spatial_bits && (time == time_bits
+                    || time * spatial_bits >= spatial * time_bits) {
+                order.push(match spatial % 2 {
+                    0 => X,
+                    _ => Y,
+                });
+                spatial += 1;
+            } else {
+                order.push(T);
+                time += 1;
+            }
+        }
+
+        let bits = [spatial_bits - spatial_bits / 2, spatial_bits / 2,
+            time_bits];
+        Ok(SpatioTemporal { geocode, precision, start, resolution,
+            order, bits })
+    }
+
+    pub fn cover_ranges(&self, bbox: &BoundingBox, start: i64, end: i64)
+            -> Result<Vec<(u64, u64)>, GeocodeError> {
+        // check if bounding box and time range are valid
+        let (bbox, west) = self.geocode.split_bbox(bbox, self.precision)?;
+        if end < start {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "invalid time range {} - {}", start, end) });
+        }
+
+        let mut ranges = self.bbox_ranges(&bbox, start, end)?;
+        if let Some(west) = west {
+            ranges.extend(self.bbox_ranges(&west, start, end)?);
+            ranges = merge_ranges(ranges);
+        }
+
+        Ok(ranges)
+    }
+
+    pub fn decode(&self, key: u64)
+            -> Result<(String, (i64, i64)), GeocodeError> {
+        // check if the key fits the layout
+        let total = self.order.len();
+        if total < 64 && key >> total != 0 {
+            return Err(GeocodeError::InvalidCellId { id: key });
+        }
+
+        // distribute key bits to their axes
+        let mut indices = [0u64; 3];
+        for (i, axis) in self.order.iter().enumerate() {
+            let bit = (key >> (total - 1 - i)) & 1;
+            indices[*axis as usize] = (indices[*axis as usize] << 1) | bit;
+        }
+
+        let bucket = indices[T as usize] as i64;
+        let time = self.start + bucket * self.resolution;
+        if bucket >= 1 << self.bits[T as usize] {
+            return Err(GeocodeError::InvalidCellId { id: key });
+        }
+
+        let (char_bits, codes) = self.geocode.get_id_parameters()?;
+        let mut code = String::with_capacity(self.precision);
+        push_interleaved(indices[0] as u32, indices[1] as u32,
+            self.precision, char_bits, |_| codes, &mut code)?;
+        Ok((code, (time, time + self.resolution)))
+    }
+
+    pub fn encode(&self, x: f64, y: f64, timestamp: i64)
+            -> Result<u64, GeocodeError> {
+        // check if coordinates and timestamp are valid
+        let bounds = self.geocode.get_bounds();
+        let bucket = self.get_bucket(timestamp);
+        if !bounds.contains(x, y) {
+            return Err(GeocodeError::CoordinateOutOfRange {
+                x, y, bounds });
+        } else if bucket < 0 || bucket >= 1 << self.bits[T as usize] {
+            return Err(GeocodeError::InvalidFormat { reason: format!(
+                "timestamp {} is outside of the time range", timestamp) });
+        }
+
+        let indices = [
+            morton::get_index(x, bounds.min_x, bounds.width(), self.bits[0])
+                as u64,
+            morton::get_index(y, bounds.min_y, bounds.height(), self.bits[1])
+                as u64,
+            bucket as u64,
+        ];
+
+        // take the next most significant bit of each axis in turn
+        let mut consumed = [0usize; 3];
+        let mut key = 0u64;
+        for axis in self.order.iter().map(|x| *x as usize) {
+            let shift = self.bits[axis] - 1 - consumed[axis];
+            key = (key << 1) | ((indices[axis] >> shift) & 1);
+            consumed[axis] += 1;
+        }
+
+        Ok(key)
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn get_precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn get_time_bits(&self) -> usize {
+        self.bits[T as usize]
+    }
+
+    fn bbox_ranges(&self, bbox: &BoundingBox, start: i64, end: i64)
+            -> Result<Vec<(u64, u64)>, GeocodeError> {
+        let bounds = self.geocode.get_bounds();
+
+        // compute index ranges along each axis
+        let (x_interval, y_interval) =
+            self.geocode.get_intervals(self.precision);
+        let upper = self.geocode.has_upper_edges();
+        let x_range = cell_range(bbox.min_x, bbox.max_x,
+            bounds.min_x, bounds.max_x, x_interval, upper);
+        let y_range = cell_range(bbox.min_y, bbox.max_y,
+            bounds.min_y, bounds.max_y, y_interval, upper);
+        let time_count = 1i64 << self.bits[T as usize];
+        let t_range = (self.get_bucket(start).max(0),
+            self.get_bucket(end).min(time_count - 1));
+        let query = match (x_range, y_range) {
+            (Some(x), Some(y)) if t_range.0 <= t_range.1 => [
+                (x.0 as u64, x.1 as u64), (y.0 as u64, y.1 as u64),
+                (t_range.0 as u64, t_range.1 as u64)],
+            _ => return Ok(Vec::new()),
+        };
+
+        // split the curve into key ranges fully within the query
+        let mut ranges = Vec::new();
+        let node = [(0, (1u64 << self.bits[0]) - 1),
+            (0, (1u64 << self.bits[1]) - 1), (0, (time_count - 1) as u64)];
+        self.decompose(0, 0, node, &query, &mut ranges);
+        Ok(ranges)
+    }
+
+    fn decompose(&self, prefix: u64, depth: usize, node: [(u64, u64); 3],
+            query: &[(u64, u64); 3], ranges: &mut Vec<(u64, u64)>) {
+        if node.iter().zip(query.iter())
+                .any(|(a, b)| a.1 < b.0 || a.0 > b.1) {
+            return;
+        }
+
+        // blocks within the query are contiguous on the curve, otherwise
+        // split on the axis of the next bit
+        let total = self.order.len();
+        if node.iter().zip(query.iter())
+                .all(|(a, b)| a.0 >= b.0 && a.1 <= b.1) {
+            let shift = (total - depth) as u32;
+            let start = prefix.checked_shl(shift).unwrap_or(0);
+            let end = start | u64::MAX.checked_shr(64 - shift).unwrap_or(0);
+            match ranges.last_mut() {
+                Some(last) if last.1.saturating_add(1) == start => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+
+            return;
+        }
+
+        let axis = self.order[depth] as usize;
+        let (min, max) = node[axis];
+        let mid = min + ((max - min + 1) >> 1);
+        for (i, range) in [(min, mid - 1), (mid, max)].iter().enumerate() {
+            let mut child = node;
+            child[axis] = *range;
+            self.decompose((prefix << 1) | i as u64, depth + 1, child,
+                query, ranges);
+        }
+    }
+
+    fn get_bucket(&self, timestamp: i64) -> i64 {
+        timestamp.saturating_sub(self.start).div_euclid(self.resolution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+    use super::SpatioTemporal;
+
+    const DAY: i64 = 86400;
+    const YEAR_2024: i64 = 1704067200;
+
+    fn get_encoder() -> SpatioTemporal {
+        // hourly buckets through 2024
+        SpatioTemporal::new(Geocode::Geohash, 6, YEAR_2024,
+            YEAR_2024 + 366 * DAY, 3600).unwrap()
+    }
+
+    #[test]
+    fn encode_decode() {
+        let encoder = get_encoder();
+        assert_eq!(encoder.get_time_bits(), 14);
+
+        let timestamp = YEAR_2024 + 100 * DAY + 5000;
+        let key = encoder.encode(-88.4, 44.266667, timestamp).unwrap();
+        assert!(key >> 44 == 0);
+
+        let (code, (start, end)) = encoder.decode(key).unwrap();
+        assert_eq!(code, Geocode::Geohash.encode(-88.4, 44.266667, 6)
+            .unwrap());
+        assert!(start <= timestamp && timestamp < end);
+        assert_eq!(end - start, 3600);
+
+        // keys order by the leading spatial and time bits together
+        let nearby = encoder.encode(-88.4, 44.266667, timestamp + 3600)
+            .unwrap();
+        let later = encoder.encode(-88.4, 44.266667, timestamp + 200 * DAY)
+            .unwrap();
+        assert!((key ^ nearby).leading_zeros() > (key ^ later).leading_zeros());
+
+        assert!(encoder.encode(-88.4, 44.266667, YEAR_2024 - 1).is_err());
+        assert!(encoder.encode(-188.4, 44.266667, timestamp).is_err());
+        assert_eq!(encoder.decode(1 << 44),
+            Err(GeocodeError::InvalidCellId { id: 1 << 44 }));
+    }
+
+    #[test]
+    fn cover_ranges() {
+        let encoder = get_encoder();
+        let bbox = BoundingBox::new(-88.45, -88.35, 44.24, 44.28);
+        let (start, end) = (YEAR_2024 + 10 * DAY, YEAR_2024 + 17 * DAY);
+        let ranges = encoder.cover_ranges(&bbox, start, end).unwrap();
+        assert!(ranges.windows(2).all(|x| x[0].1 + 1 < x[1].0));
+
+        // every cell and hour within the query lies within a range
+        let cells = Geocode::Geohash.cover_bbox(&bbox, 6).unwrap();
+        let mut count = 0;
+        for cell in cells.iter() {
+            let (x, y) = Geocode::Geohash.decode(cell).unwrap().center();
+            for hour in (start..=end).step_by(3600) {
+                let key = encoder.encode(x, y, hour).unwrap();
+                assert!(ranges.iter().any(|r| key >= r.0 && key <= r.1));
+                count += 1;
+            }
+        }
+
+        // and ranges hold nothing else
+        let total: u64 = ranges.iter().map(|r| r.1 - r.0 + 1).sum();
+        assert_eq!(total, count);
+
+        assert!(encoder.cover_ranges(&bbox, end, start).is_err());
+        assert!(encoder.cover_ranges(&bbox, YEAR_2024 - 10 * DAY,
+            YEAR_2024 - DAY).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(SpatioTemporal::new(Geocode::Geohash, 6, 10, 0, 1).is_err());
+        assert!(SpatioTemporal::new(Geocode::Geohash, 6, 0, 10, 0).is_err());
+        assert_eq!(SpatioTemporal::new(Geocode::Geohash, 12, YEAR_2024,
+            YEAR_2024 + 366 * DAY, 3600),
+            Err(GeocodeError::InvalidPrecision { precision: 12 }));
+        assert_eq!(SpatioTemporal::new(Geocode::PlusCode, 6, 0, 10, 1),
+            Err(GeocodeError::UnsupportedGeocode {
+                geocode: Geocode::PlusCode }));
+
+        // bounding boxes are checked as in cover_bbox
+        let encoder = get_encoder();
+        let bbox = BoundingBox::new(-89.0, -88.0, 44.0, f64::NAN);
+        assert!(matches!(encoder.cover_ranges(&bbox, 0, 10),
+            Err(GeocodeError::InvalidBoundingBox { .. })));
+    }
+}
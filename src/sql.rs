@@ -0,0 +1,165 @@
+use crate::{CellSet, Geocode, GeocodeError};
+
+// envelopes are written in wgs84 degrees regardless of the geocode
+const WGS84_SRID: u32 = 4326;
+
+impl Geocode {
+    pub fn to_sql_between(&self, column: &str, value: &str,
+            precision: usize) -> Result<String, GeocodeError> {
+        // descendants at the stored precision sort between the code padded
+        // with the lowest and highest characters under the "C" collation
+        let key = self.parse_key(value)?;
+        let key_precision = key.chars().count();
+        let (x, y) = self.get_bounds().center();
+        self.encode(x, y, precision)?;
+        if key_precision > precision {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: key_precision });
+        }
+
+        let (mut lower, mut upper) = (key.clone(), key);
+        for index in key_precision..precision {
+            let alphabet = self.get_alphabet(index);
+            lower.push(*alphabet.iter().min().unwrap());
+            upper.push(*alphabet.iter().max().unwrap());
+        }
+
+        Ok(format!("{} BETWEEN {} AND {}", quote_identifier(column),
+            quote_literal(&self.format_key(&lower)),
+            quote_literal(&self.format_key(&upper))))
+    }
+
+    pub fn to_sql_envelope(&self, value: &str)
+            -> Result<String, GeocodeError> {
+        Ok(format!("ST_GeomFromText({}, {})",
+            quote_literal(&self.to_wkt(value)?), WGS84_SRID))
+    }
+
+    pub fn to_sql_in<T: AsRef<str>>(&self, column: &str, values: &[T])
+            -> Result<String, GeocodeError> {
+        // codes are validated and formatted before they are quoted
+        let mut literals = Vec::with_capacity(values.len());
+        for value in values.iter() {
+            let key = self.parse_key(value.as_ref())?;
+            literals.push(quote_literal(&self.format_key(&key)));
+        }
+
+        match literals.is_empty() {
+            true => Ok("FALSE".to_string()),
+            false => Ok(format!("{} IN ({})", quote_identifier(column),
+                literals.join(", "))),
+        }
+    }
+}
+
+impl CellSet {
+    pub fn to_sql_between(&self, column: &str, precision: usize)
+            -> Result<String, GeocodeError> {
+        let mut ranges = Vec::with_capacity(self.len());
+        for cell in self.iter() {
+            ranges.push(self.get_geocode()
+                .to_sql_between(column, &cell, precision)?);
+        }
+
+        match ranges.len() {
+            0 => Ok("FALSE".to_string()),
+            1 => Ok(ranges.remove(0)),
+            _ => Ok(format!("({})", ranges.join(" OR "))),
+        }
+    }
+
+    pub fn to_sql_envelope(&self) -> Result<String, GeocodeError> {
+        Ok(format!("ST_GeomFromText({}, {})",
+            quote_literal(&self.to_wkt()?), WGS84_SRID))
+    }
+
+    pub fn to_sql_in(&self, column: &str, precision: usize)
+            -> Result<String, GeocodeError> {
+        // compacted cells are expanded to the stored precision
+        let geocode = self.get_geocode();
+        let cells = geocode.uncompact(self.iter().collect(), precision)?;
+        geocode.to_sql_in(column, &cells)
+    }
+}
+
+pub fn quote_identifier(value: &str) -> String {
+    // qualified names quote each part, doubling embedded quotes
+    value.split('.').map(|x| format!("\"{}\"", x.replace('"', "\"\"")))
+        .collect::<Vec<String>>().join(".")
+}
+
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+
+    #[test]
+    fn quote() {
+        assert_eq!(super::quote_identifier("cells.geohash"),
+            "\"cells\".\"geohash\"");
+        assert_eq!(super::quote_identifier("a\"b"), "\"a\"\"b\"");
+        assert_eq!(super::quote_literal("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn to_sql_between() {
+        assert_eq!(Geocode::Geohash.to_sql_between("geohash", "dp", 4)
+            .unwrap(), "\"geohash\" BETWEEN 'dp00' AND 'dpzz'");
+        assert_eq!(Geocode::QuadTile.to_sql_between("t.tile", "03", 4)
+            .unwrap(), "\"t\".\"tile\" BETWEEN '0300' AND '0333'");
+        assert_eq!(Geocode::PlusCode.to_sql_between("code", "86HW0000+", 8)
+            .unwrap(), "\"code\" BETWEEN '86HW2222+' AND '86HWXXXX+'");
+        assert_eq!(Geocode::Geohash.to_sql_between("geohash", "dpc5", 4)
+            .unwrap(), "\"geohash\" BETWEEN 'dpc5' AND 'dpc5'");
+
+        assert_eq!(Geocode::Geohash.to_sql_between("geohash", "dpc5", 3),
+            Err(GeocodeError::InvalidPrecision { precision: 4 }));
+        assert!(Geocode::Geohash.to_sql_between("geohash", "dpa", 4)
+            .is_err());
+        assert!(Geocode::PlusCode.to_sql_between("code", "86HW0000+", 9)
+            .is_err());
+
+        let cell_set = CellSet::from_cells(Geocode::QuadTile,
+            vec!["01".to_string(), "2".to_string()]).unwrap();
+        assert_eq!(cell_set.to_sql_between("tile", 3).unwrap(),
+            "(\"tile\" BETWEEN '010' AND '013' OR \
+            \"tile\" BETWEEN '200' AND '233')");
+        assert_eq!(CellSet::new(Geocode::QuadTile).to_sql_between("tile", 3)
+            .unwrap(), "FALSE");
+    }
+
+    #[test]
+    fn to_sql_envelope() {
+        assert_eq!(Geocode::Geohash.to_sql_envelope("0").unwrap(),
+            "ST_GeomFromText('POLYGON((-180 -90,-135 -90,-135 -45,\
+            -180 -45,-180 -90))', 4326)");
+
+        let cell_set = CellSet::from_cells(Geocode::QuadTile,
+            vec!["0".to_string()]).unwrap();
+        assert!(cell_set.to_sql_envelope().unwrap()
+            .starts_with("ST_GeomFromText('MULTIPOLYGON((("));
+        assert!(cell_set.to_sql_envelope().unwrap().ends_with("', 4326)"));
+        assert!(Geocode::QuadTile.to_sql_envelope("0").unwrap()
+            .ends_with("', 4326)"));
+        assert!(Geocode::Geohash.to_sql_envelope("a").is_err());
+    }
+
+    #[test]
+    fn to_sql_in() {
+        assert_eq!(Geocode::Geohash.to_sql_in("geohash",
+            &["dpc5", "9q8z"]).unwrap(),
+            "\"geohash\" IN ('dpc5', '9q8z')");
+        assert_eq!(Geocode::Geohash.to_sql_in::<&str>("geohash", &[])
+            .unwrap(), "FALSE");
+        assert!(Geocode::Geohash.to_sql_in("geohash", &["dp'"]).is_err());
+
+        let cell_set = CellSet::from_cells(Geocode::QuadTile,
+            vec!["01".to_string(), "030".to_string()]).unwrap();
+        assert_eq!(cell_set.to_sql_in("tile", 3).unwrap(),
+            "\"tile\" IN ('010', '011', '012', '013', '030')");
+        assert!(cell_set.to_sql_in("tile", 2).is_err());
+    }
+}
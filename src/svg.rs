@@ -0,0 +1,205 @@
+use crate::{BoundingBox, CellSet, GeocodeError, Polygon, GEOHASH_BOUNDS};
+use crate::project;
+
+// fraction of the content extent added around each side of the viewport
+const PADDING: f64 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgProjection {
+    Equirectangular,
+    Mercator,
+}
+
+impl SvgProjection {
+    fn project(&self, lon: f64, lat: f64) -> (f64, f64) {
+        match self {
+            SvgProjection::Equirectangular => (lon, lat),
+            SvgProjection::Mercator => project::wgs84_to_mercator(lon, lat),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    pub projection: SvgProjection,
+    pub width: f64,
+    pub height: f64,
+    // wgs84 bounds to render, defaulting to the padded content extent
+    pub viewport: Option<BoundingBox>,
+}
+
+impl SvgOptions {
+    pub fn new(projection: SvgProjection, width: f64, height: f64)
+            -> SvgOptions {
+        SvgOptions { projection, width, height, viewport: None }
+    }
+}
+
+struct Transform {
+    scale: f64,
+    offset: (f64, f64),
+    origin: (f64, f64),
+    projection: SvgProjection,
+}
+
+impl Transform {
+    fn apply(&self, lon: f64, lat: f64) -> (f64, f64) {
+        // svg y coordinates increase downward
+        let (x, y) = self.projection.project(lon, lat);
+        (self.offset.0 + (x - self.origin.0) * self.scale,
+            self.offset.1 + (self.origin.1 - y) * self.scale)
+    }
+
+    fn format_path<'a, I>(&self, rings: I) -> String
+            where I: IntoIterator<Item = &'a Vec<(f64, f64)>> {
+        let mut path = String::new();
+        for ring in rings {
+            for (i, (lon, lat)) in ring.iter().enumerate() {
+                let (x, y) = self.apply(*lon, *lat);
+                let command = if i == 0 { 'M' } else { 'L' };
+                path.push_str(&format!("{}{:.3} {:.3}", command, x, y));
+            }
+
+            path.push('Z');
+        }
+
+        path
+    }
+}
+
+impl CellSet {
+    pub fn to_svg(&self, polygon: Option<&Polygon>, options: &SvgOptions)
+            -> Result<String, GeocodeError> {
+        if !(options.width > 0.0 && options.height > 0.0) {
+            return Err(GeocodeError::InvalidGeometry {
+                reason: format!("invalid svg size {}x{}",
+                    options.width, options.height) });
+        }
+
+        // convert cells and polygon rings to wgs84
+        let geocode = self.get_geocode();
+        let mut cells = Vec::new();
+        for cell in self.iter() {
+            let ring = geocode.decode_ring(&cell)?;
+            cells.push((cell, ring));
+        }
+
+        let rings: Vec<Vec<(f64, f64)>> = polygon.iter()
+            .flat_map(|x| std::iter::once(&x.exterior)
+                .chain(x.interiors.iter()))
+            .map(|ring| ring.iter().map(|(x, y)|
+                geocode.native_to_wgs84(*x, *y)).collect())
+            .collect();
+
+        let viewport = match options.viewport {
+            Some(viewport) => viewport,
+            None => get_extent(cells.iter().flat_map(|(_, x)| x.iter())
+                .chain(rings.iter().flatten())),
+        };
+
+        // fit the projected viewport within the image, preserving aspect
+        let (min_x, min_y) = options.projection
+            .project(viewport.min_x, viewport.min_y);
+        let (max_x, max_y) = options.projection
+            .project(viewport.max_x, viewport.max_y);
+        let scale = (options.width / (max_x - min_x))
+            .min(options.height / (max_y - min_y));
+        let transform = Transform {
+            scale,
+            offset: ((options.width - (max_x - min_x) * scale) / 2.0,
+                (options.height - (max_y - min_y) * scale) / 2.0),
+            origin: (min_x, max_y),
+            projection: options.projection,
+        };
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            options.width, options.height);
+        svg.push_str("<g fill=\"#3388ff\" fill-opacity=\"0.4\" \
+            stroke=\"#3388ff\" stroke-width=\"1\">\n");
+        for (cell, ring) in cells.iter() {
+            svg.push_str(&format!("<path d=\"{}\"><title>{}</title></path>\n",
+                transform.format_path(std::iter::once(ring)), cell));
+        }
+
+        svg.push_str("</g>\n");
+        if !rings.is_empty() {
+            svg.push_str(&format!("<path d=\"{}\" fill=\"none\" \
+                fill-rule=\"evenodd\" stroke=\"#e31a1c\" \
+                stroke-width=\"2\"/>\n", transform.format_path(&rings)));
+        }
+
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+}
+
+fn get_extent<'a, I: Iterator<Item = &'a (f64, f64)>>(points: I)
+        -> BoundingBox {
+    let mut bbox = BoundingBox::new(f64::INFINITY,
+        f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+    for (x, y) in points {
+        bbox.min_x = bbox.min_x.min(*x);
+        bbox.max_x = bbox.max_x.max(*x);
+        bbox.min_y = bbox.min_y.min(*y);
+        bbox.max_y = bbox.max_y.max(*y);
+    }
+
+    // render the entire globe when there is nothing to draw
+    if bbox.min_x > bbox.max_x {
+        return GEOHASH_BOUNDS;
+    }
+
+    let (x_padding, y_padding) =
+        (bbox.width() * PADDING, bbox.height() * PADDING);
+    BoundingBox::new((bbox.min_x - x_padding).max(GEOHASH_BOUNDS.min_x),
+        (bbox.max_x + x_padding).min(GEOHASH_BOUNDS.max_x),
+        (bbox.min_y - y_padding).max(GEOHASH_BOUNDS.min_y),
+        (bbox.max_y + y_padding).min(GEOHASH_BOUNDS.max_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CellSet, CoverMode, Geocode, Polygon};
+    use super::{SvgOptions, SvgProjection};
+
+    #[test]
+    fn to_svg() {
+        let geocode = Geocode::Geohash;
+        let cell_set = CellSet::from_cells(geocode,
+            vec!["0".to_string(), "1".to_string()]).unwrap();
+        let mut options = SvgOptions::new(SvgProjection::Equirectangular,
+            200.0, 100.0);
+        options.viewport = Some(BoundingBox::new(-180.0, 0.0, -90.0, 0.0));
+
+        // cells fill the lower left of the viewport
+        let svg = cell_set.to_svg(None, &options).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            width=\"200\" height=\"100\" viewBox=\"0 0 200 100\">"));
+        assert!(svg.contains("<path d=\"M0.000 100.000L50.000 100.000\
+            L50.000 50.000L0.000 50.000L0.000 100.000Z\">\
+            <title>0</title></path>"));
+        assert_eq!(svg.matches("<path").count(), 2);
+
+        // polygons are drawn over the covering
+        let polygon = Polygon::new(vec![(-89.0, 44.0), (-88.0, 44.0),
+            (-88.0, 45.0), (-89.0, 44.0)], Vec::new());
+        let cells = geocode.cover_polygon(&polygon, 4,
+            CoverMode::Intersecting).unwrap();
+        let cell_set = CellSet::from_cells(geocode, cells).unwrap();
+        let options = SvgOptions::new(SvgProjection::Mercator, 400.0, 400.0);
+        let svg = cell_set.to_svg(Some(&polygon), &options).unwrap();
+        assert_eq!(svg.matches("<title>").count(), cell_set.len());
+        assert!(svg.contains("stroke=\"#e31a1c\""));
+
+        // projected coordinates lie within the image
+        for value in svg.split(&['M', 'L', 'Z', '"', ' '][..])
+                .filter_map(|x| x.parse::<f64>().ok()) {
+            assert!((0.0..=400.0).contains(&value));
+        }
+
+        assert!(CellSet::new(geocode).to_svg(None, &options).is_ok());
+        assert!(cell_set.to_svg(None, &SvgOptions::new(
+            SvgProjection::Mercator, 0.0, 400.0)).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{project, BoundingBox, Geocode, GeocodeError, QUADTILE_BOUNDS};
+
+const MAX_ZOOM: usize = 32;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TileScheme {
+    Tms,
+    Xyz,
+}
+
+// rows are stored using the xyz scheme where y increases southward
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Tile {
+    pub z: usize,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Tile {
+    pub fn new(z: usize, x: u32, y: u32) -> Result<Tile, GeocodeError> {
+        // check if zoom and tile indices are valid
+        if z > MAX_ZOOM {
+            return Err(GeocodeError::InvalidPrecision { precision: z });
+        } else if z < MAX_ZOOM && (x >> z != 0 || y >> z != 0) {
+            return Err(GeocodeError::InvalidFormat { reason:
+                format!("tile ({}, {}) is outside of zoom {}", x, y, z) });
+        }
+
+        Ok(Tile { z, x, y })
+    }
+
+    pub fn format_url(&self, template: &str) -> String {
+        // replace xyz, tms ('{-y}'), and quadkey placeholders
+        template.replace("{z}", &self.z.to_string())
+            .replace("{x}", &self.x.to_string())
+            .replace("{y}", &self.y.to_string())
+            .replace("{-y}", &self.get_y(TileScheme::Tms).to_string())
+            .replace("{q}", &self.get_quadtile())
+    }
+
+    pub fn from_quadtile(value: &str) -> Result<Tile, GeocodeError> {
+        // check if code is valid
+        let key = Geocode::QuadTile.parse_key(value)?;
+        if key.len() > MAX_ZOOM {
+            return Err(GeocodeError::InvalidPrecision {
+                precision: key.len() });
+        }
+
+        // each digit contributes one bit to the column and row
+        let (mut x, mut y) = (0u32, 0u32);
+        for c in key.chars() {
+            let digit = c.to_digit(4).unwrap();
+            x = (x << 1) | (digit & 1);
+            y = (y << 1) | (digit >> 1);
+        }
+
+        Ok(Tile { z: key.len(), x, y })
+    }
+
+    pub fn from_scheme(z: usize, x: u32, y: u32, scheme: TileScheme)
+            -> Result<Tile, GeocodeError> {
+        let tile = Tile::new(z, x, y)?;
+        Ok(Tile { z, x, y: tile.get_y(scheme) })
+    }
+
+    pub fn get_bounds(&self) -> BoundingBox {
+        self.get_bounds_within(&QUADTILE_BOUNDS)
+    }
+
+    pub fn get_bounds_within(&self, extent: &BoundingBox) -> BoundingBox {
+        // rows are numbered from the north of the tiled extent
+        let scale = 2f64.powi(self.z as i32);
+        let (width, height) = (extent.width() / scale, extent.height() / scale);
+        BoundingBox::new(extent.min_x + self.x as f64 * width,
+            extent.min_x + (self.x as f64 + 1f64) * width,
+            extent.max_y - (self.y as f64 + 1f64) * height,
+            extent.max_y - self.y as f64 * height)
+    }
+
+    pub fn get_quadtile(&self) -> String {
+        (0..self.z).rev().map(|i| {
+            let digit = ((self.x >> i) & 1) | (((self.y >> i) & 1) << 1);
+            std::char::from_digit(digit, 4).unwrap()
+        }).collect()
+    }
+
+    pub fn get_wgs84_bounds(&self) -> BoundingBox {
+        let bounds = self.get_bounds();
+        let (min_x, min_y) =
+            project::mercator_to_wgs84(bounds.min_x, bounds.min_y);
+        let (max_x, max_y) =
+            project::mercator_to_wgs84(bounds.max_x, bounds.max_y);
+        BoundingBox::new(min_x, max_x, min_y, max_y)
+    }
+
+    pub fn get_y(&self, scheme: TileScheme) -> u32 {
+        // tms rows increase northward
+        match scheme {
+            TileScheme::Tms => (((1u64 << self.z) - 1) as u32) - self.y,
+            TileScheme::Xyz => self.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, CustomGeocode, Geocode, GeocodeError,
+        GridSystem};
+    use super::{Tile, TileScheme};
+
+    #[test]
+    fn bounds() {
+        let tile = Tile::new(1, 0, 0).unwrap();
+        assert_eq!(tile.get_bounds(),
+            Geocode::QuadTile.decode("0").unwrap());
+
+        // bounds match the decoded quadtile
+        let tile = Tile::new(16, 35210, 21493).unwrap();
+        let (bounds, expected) = (tile.get_bounds(),
+            Geocode::QuadTile.decode(&tile.get_quadtile()).unwrap());
+        assert!((bounds.min_x - expected.min_x).abs() < 1e-6);
+        assert!((bounds.max_x - expected.max_x).abs() < 1e-6);
+        assert!((bounds.min_y - expected.min_y).abs() < 1e-6);
+        assert!((bounds.max_y - expected.max_y).abs() < 1e-6);
+
+        let bounds = Tile::new(0, 0, 0).unwrap().get_wgs84_bounds();
+        assert!((bounds.min_x - -180.0).abs() < 1e-9);
+        assert!((bounds.max_y - 85.05112877980659).abs() < 1e-9);
+
+        let bounds = Tile::new(2, 1, 1).unwrap().get_wgs84_bounds();
+        assert!((bounds.min_x - -90.0).abs() < 1e-9);
+        assert!(bounds.max_x.abs() < 1e-9);
+        assert!(bounds.min_y.abs() < 1e-9);
+        assert!((bounds.max_y - 66.51326044311186).abs() < 1e-9);
+
+        // tiles over custom extents match custom quadtile codes
+        let extent = BoundingBox::new(-3850000.0, 3750000.0,
+            -5350000.0, 5850000.0);
+        let geocode = CustomGeocode::quadtile(extent, 3413).unwrap();
+        let code = geocode.encode(-1200000.0, 850000.0, 9).unwrap();
+        assert_eq!(Tile::from_quadtile(&code).unwrap()
+            .get_bounds_within(&extent), geocode.decode(&code).unwrap());
+    }
+
+    #[test]
+    fn format_url() {
+        let tile = Tile::from_quadtile("213").unwrap();
+        assert_eq!(tile.format_url("https://tile.example/{z}/{x}/{y}.png"),
+            "https://tile.example/3/3/5.png");
+        assert_eq!(tile.format_url("https://tms.example/{z}/{x}/{-y}.png"),
+            "https://tms.example/3/3/2.png");
+        assert_eq!(tile.format_url("https://bing.example/a{q}.jpeg?g=1"),
+            "https://bing.example/a213.jpeg?g=1");
+        assert_eq!(tile.format_url("tiles/{z}-{y}-{x}-{z}"),
+            "tiles/3-5-3-3");
+    }
+
+    #[test]
+    fn new() {
+        assert!(Tile::new(0, 0, 0).is_ok());
+        assert!(Tile::new(32, u32::MAX, u32::MAX).is_ok());
+        assert!(Tile::new(2, 4, 0).is_err());
+        assert_eq!(Tile::new(33, 0, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 33 }));
+    }
+
+    #[test]
+    fn quadtile() {
+        assert_eq!(Tile::from_quadtile("213").unwrap(),
+            Tile { z: 3, x: 3, y: 5 });
+        assert_eq!(Tile::new(16, 35210, 21493).unwrap().get_quadtile(),
+            "1202102332221212");
+        assert_eq!(Tile::new(0, 0, 0).unwrap().get_quadtile(), "");
+
+        // check conversion round trips
+        let geocode = Geocode::QuadTile;
+        let code = geocode.encode(-9840642.99, 5506802.68, 12).unwrap();
+        assert_eq!(Tile::from_quadtile(&code).unwrap().get_quadtile(), code);
+
+        assert!(Tile::from_quadtile("0124").is_err());
+        assert!(Tile::from_quadtile(&"0".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn scheme() {
+        let tile = Tile::from_scheme(3, 3, 2, TileScheme::Tms).unwrap();
+        assert_eq!(tile, Tile::new(3, 3, 5).unwrap());
+        assert_eq!(tile.get_y(TileScheme::Tms), 2);
+        assert_eq!(tile.get_y(TileScheme::Xyz), 5);
+
+        // tms row zero is the southernmost row
+        let tile = Tile::from_scheme(1, 0, 0, TileScheme::Tms).unwrap();
+        assert_eq!(tile.get_quadtile(), "2");
+        assert!(tile.get_wgs84_bounds().max_y.abs() < 1e-9);
+
+        let tile = Tile::new(32, 0, 0).unwrap();
+        assert_eq!(tile.get_y(TileScheme::Tms), u32::MAX);
+        assert_eq!(Tile::from_scheme(0, 0, 0, TileScheme::Tms).unwrap(),
+            Tile::new(0, 0, 0).unwrap());
+        assert!(Tile::from_scheme(2, 0, 4, TileScheme::Tms).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{Geocode, GeocodeError};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Node {
+    // children are sorted by character so shared prefixes share nodes
+    children: Vec<(char, Node)>,
+    terminal: bool,
+}
+
+impl Node {
+    fn get(&self, c: char) -> Option<&Node> {
+        self.children.binary_search_by_key(&c, |(x, _)| *x).ok()
+            .map(|i| &self.children[i].1)
+    }
+
+    fn get_or_insert(&mut self, c: char) -> &mut Node {
+        let index = match self.children.binary_search_by_key(&c, |(x, _)| *x) {
+            Ok(index) => index,
+            Err(index) => {
+                self.children.insert(index, (c, Node::default()));
+                index
+            },
+        };
+
+        &mut self.children[index].1
+    }
+
+    fn collect(&self, key: &mut String, keys: &mut Vec<String>) {
+        if self.terminal {
+            keys.push(key.clone());
+        }
+
+        for (c, child) in self.children.iter() {
+            key.push(*c);
+            child.collect(key, keys);
+            key.pop();
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellTrie {
+    geocode: Geocode,
+    root: Node,
+    len: usize,
+    max_precision: usize,
+}
+
+impl CellTrie {
+    pub fn new(geocode: Geocode) -> CellTrie {
+        CellTrie { geocode, root: Node::default(), len: 0, max_precision: 0 }
+    }
+
+    pub fn from_cells(geocode: Geocode, cells: &[String])
+            -> Result<CellTrie, GeocodeError> {
+        let mut trie = CellTrie::new(geocode);
+        for cell in cells.iter() {
+            trie.insert(cell)?;
+        }
+
+        Ok(trie)
+    }
+
+    pub fn ancestors(&self, value: &str) -> Vec<String> {
+        // stored cells along the path to the value, excluding itself
+        let key = match self.geocode.parse_key(value) {
+            Ok(key) => key,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut ancestors = Vec::new();
+        let mut node = &self.root;
+        for (i, c) in key.char_indices() {
+            if node.terminal && i != 0 {
+                ancestors.push(self.geocode.format_key(&key[..i]));
+            }
+
+            node = match node.get(c) {
+                Some(node) => node,
+                None => break,
+            };
+        }
+
+        ancestors
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        match self.geocode.parse_key(value) {
+            Ok(key) => self.get_node(&key).is_some_and(|x| x.terminal),
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64)
+            -> Result<bool, GeocodeError> {
+        if self.len == 0 {
+            return Ok(false);
+        }
+
+        // walk the finest code, stopping at the first stored ancestor
+        let code = self.geocode.encode(x, y, self.max_precision)?;
+        let key = self.geocode.parse_key(&code)?;
+        let mut node = &self.root;
+        for c in key.chars() {
+            node = match node.get(c) {
+                Some(node) => node,
+                None => return Ok(false),
+            };
+
+            if node.terminal {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn contains_prefix(&self, prefix: &str) -> bool {
+        // check if any stored cell starts with the prefix
+        self.get_node(prefix).is_some()
+    }
+
+    pub fn descendants(&self, value: &str) -> Vec<String> {
+        let key = match self.geocode.parse_key(value) {
+            Ok(key) => key,
+            Err(_) => return Vec::new(),
+        };
+
+        let node = match self.get_node(&key) {
+            Some(node) => node,
+            None => return Vec::new(),
+        };
+
+        let (mut prefix, mut keys) = (key.clone(), Vec::new());
+        for (c, child) in node.children.iter() {
+            prefix.push(*c);
+            child.collect(&mut prefix, &mut keys);
+            prefix.pop();
+        }
+
+        keys.iter().map(|x| self.geocode.format_key(x)).collect()
+    }
+
+    pub fn get_geocode(&self) -> Geocode {
+        self.geocode
+    }
+
+    pub fn insert(&mut self, value: &str) -> Result<bool, GeocodeError> {
+        let key = self.geocode.parse_key(value)?;
+        let precision = self.geocode.get_precision(value)?;
+
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.get_or_insert(c);
+        }
+
+        if node.terminal {
+            return Ok(false);
+        }
+
+        node.terminal = true;
+        self.len += 1;
+        self.max_precision = self.max_precision.max(precision);
+        Ok(true)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        let mut keys = Vec::new();
+        self.root.collect(&mut String::new(), &mut keys);
+        keys.into_iter().map(move |x| self.geocode.format_key(&x))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get_node(&self, key: &str) -> Option<&Node> {
+        let mut node = &self.root;
+        for c in key.chars() {
+            node = node.get(c)?;
+        }
+
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::CellTrie;
+
+    fn cells(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn insert() {
+        let mut trie = CellTrie::new(Geocode::Geohash);
+        assert!(trie.is_empty());
+        assert_eq!(trie.insert("dpc5"), Ok(true));
+        assert_eq!(trie.insert("dpc5u6"), Ok(true));
+        assert_eq!(trie.insert("dpc5"), Ok(false));
+        assert_eq!(trie.insert("dpa"), Err(GeocodeError::InvalidCharacter {
+            ch: 'a', index: 2 }));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.iter().collect::<Vec<String>>(),
+            cells(&["dpc5", "dpc5u6"]));
+
+        assert!(trie.contains("dpc5"));
+        assert!(!trie.contains("dpc"));
+        assert!(!trie.contains("dpc5u"));
+    }
+
+    #[test]
+    fn contains_point() {
+        let trie = CellTrie::from_cells(Geocode::Geohash,
+            &cells(&["9xjq", "dpc5u6"])).unwrap();
+        assert_eq!(trie.contains_point(-88.4, 44.266667), Ok(true));
+        assert_eq!(trie.contains_point(-105.078056, 40.559167), Ok(true));
+        assert_eq!(trie.contains_point(-88.0, 44.266667), Ok(false));
+        assert!(trie.contains_point(200.0, 0.0).is_err());
+        assert_eq!(CellTrie::new(Geocode::Geohash)
+            .contains_point(-88.4, 44.266667), Ok(false));
+    }
+
+    #[test]
+    fn prefix_queries() {
+        let trie = CellTrie::from_cells(Geocode::QuadTile,
+            &cells(&["0", "03", "0302", "0310", "1"])).unwrap();
+        assert!(trie.contains_prefix("03"));
+        assert!(trie.contains_prefix("031"));
+        assert!(!trie.contains_prefix("2"));
+
+        assert_eq!(trie.ancestors("03021"), cells(&["0", "03", "0302"]));
+        assert_eq!(trie.ancestors("0302"), cells(&["0", "03"]));
+        assert!(trie.ancestors("2").is_empty());
+
+        assert_eq!(trie.descendants("0"), cells(&["03", "0302", "0310"]));
+        assert_eq!(trie.descendants("031"), cells(&["0310"]));
+        assert!(trie.descendants("1").is_empty());
+        assert!(trie.descendants("4").is_empty());
+    }
+
+    #[test]
+    fn pluscode() {
+        // formatted codes are stored by key and restored on output
+        let trie = CellTrie::from_cells(Geocode::PlusCode,
+            &cells(&["86HJ0000+", "86HJ7JR9+CV"])).unwrap();
+        assert_eq!(trie.descendants("86HJ0000+"), cells(&["86HJ7JR9+CV"]));
+        assert_eq!(trie.ancestors("86HJ7JR9+CV"), cells(&["86HJ0000+"]));
+
+        let (x, y) = Geocode::PlusCode.decode("86HJ2200+").unwrap().center();
+        assert_eq!(trie.contains_point(x, y), Ok(true));
+        assert_eq!(trie.contains_point(-88.4, 44.266667), Ok(false));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{BoundingBox, GeocodeError};
+use crate::project::{WGS84_FLATTENING as FLATTENING,
+    WGS84_SEMI_MAJOR_AXIS as SEMI_MAJOR_AXIS};
+
+pub const UTM_BOUNDS: BoundingBox =
+    BoundingBox::new(-180.0, 180.0, -80.0, 84.0);
+
+static ZONE_LETTERS: &[char] = &['C', 'D', 'E', 'F', 'G', 'H', 'J', 'K',
+    'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X'];
+
+const SCALE_FACTOR: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500000.0;
+const FALSE_NORTHING: f64 = 10000000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UtmCoordinate {
+    pub zone: u8,
+    pub letter: char,
+    pub easting: f64,
+    pub northing: f64,
+}
+
+impl UtmCoordinate {
+    pub fn is_northern(&self) -> bool {
+        self.letter >= 'N'
+    }
+}
+
+pub fn get_zone(lon: f64, lat: f64) -> u8 {
+    // handle norway and svalbard exceptions
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        return 32;
+    } else if (72.0..=84.0).contains(&lat) && (0.0..42.0).contains(&lon) {
+        return match lon {
+            x if x < 9.0 => 31,
+            x if x < 21.0 => 33,
+            x if x < 33.0 => 35,
+            _ => 37,
+        };
+    }
+
+    (((lon + 180.0) / 6.0).floor() as u8 + 1).min(60)
+}
+
+pub fn get_zone_letter(lat: f64) -> Option<char> {
+    // the northernmost band 'X' spans 12 degrees
+    match UTM_BOUNDS.contains(0.0, lat) {
+        true => {
+            let index = ((lat + 80.0) / 8.0).floor() as usize;
+            Some(ZONE_LETTERS[index.min(ZONE_LETTERS.len() - 1)])
+        },
+        false => None,
+    }
+}
+
+fn get_central_meridian(zone: u8) -> f64 {
+    (zone as f64 - 1.0) * 6.0 - 180.0 + 3.0
+}
+
+fn get_conformal_tangent(tau: f64, e: f64) -> f64 {
+    let sigma = (e * (e * tau / (1.0 + tau * tau).sqrt()).atanh()).sinh();
+    tau * (1.0 + sigma * sigma).sqrt() - sigma * (1.0 + tau * tau).sqrt()
+}
+
+fn get_series() -> (f64, [f64; 3], [f64; 3]) {
+    // compute rectifying radius and kruger series coefficients
+    let n = FLATTENING / (2.0 - FLATTENING);
+    let (n2, n3) = (n * n, n * n * n);
+    let radius = SEMI_MAJOR_AXIS / (1.0 + n)
+        * (1.0 + n2 / 4.0 + n2 * n2 / 64.0);
+
+    let alpha = [n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0,
+        13.0 * n2 / 48.0 - 3.0 * n3 / 5.0, 61.0 * n3 / 240.0];
+    let beta = [n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0,
+        n2 / 48.0 + n3 / 15.0, 17.0 * n3 / 480.0];
+
+    (radius, alpha, beta)
+}
+
+pub fn utm_to_wgs84(coordinate: &UtmCoordinate)
+        -> Result<(f64, f64), GeocodeError> {
+    // check if zone and letter are valid
+    if coordinate.zone == 0 || coordinate.zone > 60 {
+        return Err(GeocodeError::InvalidFormat {
+            reason: format!("invalid utm zone {}", coordinate.zone) });
+    } else if !ZONE_LETTERS.contains(&coordinate.letter) {
+        return Err(GeocodeError::InvalidFormat {
+            reason: format!("invalid utm zone letter '{}'",
+                coordinate.letter) });
+    }
+
+    let (radius, _, beta) = get_series();
+    let e = (FLATTENING * (2.0 - FLATTENING)).sqrt();
+
+    // compute normalized transverse mercator coordinates
+    let x = coordinate.easting - FALSE_EASTING;
+    let y = match coordinate.is_northern() {
+        true => coordinate.northing,
+        false => coordinate.northing - FALSE_NORTHING,
+    };
+
+    let xi = y / (SCALE_FACTOR * radius);
+    let eta = x / (SCALE_FACTOR * radius);
+
+    let (mut xi_prime, mut eta_prime) = (xi, eta);
+    for (j, b) in beta.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi_prime -= b * (k * xi).sin() * (k * eta).cosh();
+        eta_prime -= b * (k * xi).cos() * (k * eta).sinh();
+    }
+
+    // iteratively solve for the tangent of latitude
+    let tau_prime = xi_prime.sin()
+        / (eta_prime.sinh().powi(2) + xi_prime.cos().powi(2)).sqrt();
+    let mut tau = tau_prime;
+    for _ in 0..16 {
+        let tau_i = get_conformal_tangent(tau, e);
+        let delta = (tau_prime - tau_i) / (1.0 + tau_i * tau_i).sqrt()
+            * (1.0 + (1.0 - e * e) * tau * tau)
+            / ((1.0 - e * e) * (1.0 + tau * tau).sqrt());
+        tau += delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    let lat = tau.atan().to_degrees();
+    let lon = eta_prime.sinh().atan2(xi_prime.cos()).to_degrees()
+        + get_central_meridian(coordinate.zone);
+
+    Ok(((lon + 180.0).rem_euclid(360.0) - 180.0, lat))
+}
+
+pub fn wgs84_to_utm(lon: f64, lat: f64)
+        -> Result<UtmCoordinate, GeocodeError> {
+    // check if coordinates are valid
+    let letter = match get_zone_letter(lat) {
+        Some(letter) if UTM_BOUNDS.contains(lon, lat) => letter,
+        _ => return Err(GeocodeError::CoordinateOutOfRange {
+            x: lon, y: lat, bounds: UTM_BOUNDS }),
+    };
+
+    let zone = get_zone(lon, lat);
+    let (radius, alpha, _) = get_series();
+    let e = (FLATTENING * (2.0 - FLATTENING)).sqrt();
+
+    // compute conformal latitude and transverse mercator coordinates
+    let phi = lat.to_radians();
+    let lambda = (lon - get_central_meridian(zone)).to_radians();
+    let tau_prime = get_conformal_tangent(phi.tan(), e);
+
+    let xi_prime = tau_prime.atan2(lambda.cos());
+    let eta_prime = (lambda.sin()
+        / (tau_prime * tau_prime + lambda.cos().powi(2)).sqrt()).asinh();
+
+    let (mut xi, mut eta) = (xi_prime, eta_prime);
+    for (j, a) in alpha.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi += a * (k * xi_prime).sin() * (k * eta_prime).cosh();
+        eta += a * (k * xi_prime).cos() * (k * eta_prime).sinh();
+    }
+
+    let easting = SCALE_FACTOR * radius * eta + FALSE_EASTING;
+    let northing = match lat < 0.0 {
+        true => SCALE_FACTOR * radius * xi + FALSE_NORTHING,
+        false => SCALE_FACTOR * radius * xi,
+    };
+
+    Ok(UtmCoordinate { zone, letter, easting, northing })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GeocodeError;
+    use super::UtmCoordinate;
+
+    #[test]
+    fn zone() {
+        assert_eq!(super::get_zone(-88.4, 44.266667), 16);
+        assert_eq!(super::get_zone(-180.0, 0.0), 1);
+        assert_eq!(super::get_zone(180.0, 0.0), 60);
+
+        // norway and svalbard exceptions
+        assert_eq!(super::get_zone(5.3, 60.4), 32);
+        assert_eq!(super::get_zone(5.3, 55.9), 31);
+        assert_eq!(super::get_zone(8.0, 78.0), 31);
+        assert_eq!(super::get_zone(15.6, 78.2), 33);
+        assert_eq!(super::get_zone(41.0, 80.0), 37);
+
+        assert_eq!(super::get_zone_letter(-80.0), Some('C'));
+        assert_eq!(super::get_zone_letter(0.0), Some('N'));
+        assert_eq!(super::get_zone_letter(-0.1), Some('M'));
+        assert_eq!(super::get_zone_letter(84.0), Some('X'));
+        assert_eq!(super::get_zone_letter(84.1), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        for (lon, lat) in [(-88.4, 44.266667), (-105.078056, 40.559167),
+                (151.2093, -33.8688), (5.3, 60.4), (15.6, 78.2),
+                (-179.99, -79.99), (0.0, 0.0)].iter() {
+            let coordinate = super::wgs84_to_utm(*lon, *lat).unwrap();
+            let (x, y) = super::utm_to_wgs84(&coordinate).unwrap();
+            assert!((x - lon).abs() < 1e-9 && (y - lat).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn utm_to_wgs84() {
+        assert!(super::utm_to_wgs84(&UtmCoordinate { zone: 61,
+            letter: 'N', easting: 500000.0, northing: 0.0 }).is_err());
+        assert!(super::utm_to_wgs84(&UtmCoordinate { zone: 31,
+            letter: 'I', easting: 500000.0, northing: 0.0 }).is_err());
+
+        let (lon, lat) = super::utm_to_wgs84(&UtmCoordinate { zone: 31,
+            letter: 'N', easting: 500000.0, northing: 0.0 }).unwrap();
+        assert!((lon - 3.0).abs() < 1e-9 && lat.abs() < 1e-9);
+    }
+
+    #[test]
+    fn wgs84_to_utm() {
+        let coordinate = super::wgs84_to_utm(-88.4, 44.266667).unwrap();
+        assert_eq!((coordinate.zone, coordinate.letter), (16, 'T'));
+        assert!(coordinate.is_northern());
+        assert!((coordinate.easting - 388259.89).abs() < 0.01);
+        assert!((coordinate.northing - 4902444.63).abs() < 0.01);
+
+        let coordinate = super::wgs84_to_utm(151.2093, -33.8688).unwrap();
+        assert_eq!((coordinate.zone, coordinate.letter), (56, 'H'));
+        assert!(!coordinate.is_northern());
+        assert!((coordinate.easting - 334368.63).abs() < 0.01);
+        assert!((coordinate.northing - 6250948.35).abs() < 0.01);
+
+        // norway and svalbard exceptions
+        let coordinate = super::wgs84_to_utm(5.3, 60.4).unwrap();
+        assert_eq!((coordinate.zone, coordinate.letter), (32, 'V'));
+        assert!((coordinate.easting - 296191.55).abs() < 0.01);
+        let coordinate = super::wgs84_to_utm(15.6, 78.2).unwrap();
+        assert_eq!((coordinate.zone, coordinate.letter), (33, 'X'));
+        assert!((coordinate.northing - 8680760.05).abs() < 0.01);
+
+        assert_eq!(super::wgs84_to_utm(0.0, 85.0),
+            Err(GeocodeError::CoordinateOutOfRange { x: 0.0, y: 85.0,
+                bounds: super::UTM_BOUNDS }));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Geocode, GeocodeError};
+
+use alloc::string::String;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ValidationMode {
+    Lenient,
+    Strict,
+}
+
+impl Geocode {
+    pub fn validate(&self, value: &str, mode: ValidationMode)
+            -> Result<String, GeocodeError> {
+        match mode {
+            ValidationMode::Lenient => {
+                // strip whitespace and separators and normalize case
+                let value: String = value.chars()
+                    .filter(|x| !x.is_whitespace() && *x != '-' && *x != '_')
+                    .collect();
+
+                Ok(self.format_key(&self.parse_key(
+                    &self.normalize_case(&value))?))
+            },
+            ValidationMode::Strict => {
+                // codes must already be in canonical form
+                let code = self.format_key(&self.parse_key(value)?);
+                match value.chars().zip(code.chars()).enumerate()
+                        .find(|(_, (a, b))| a != b) {
+                    Some((index, (ch, _))) =>
+                        Err(GeocodeError::InvalidCharacter { ch, index }),
+                    None => Ok(code),
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+    use super::ValidationMode;
+
+    #[test]
+    fn lenient() {
+        let mode = ValidationMode::Lenient;
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.validate(" DPC5-U6 ", mode).unwrap(), "dpc5u6");
+        assert_eq!(geocode.validate("dpca", mode),
+            Err(GeocodeError::InvalidCharacter { ch: 'a', index: 3 }));
+        assert_eq!(geocode.validate(" ", mode), Err(GeocodeError::EmptyCode));
+
+        assert_eq!(Geocode::PlusCode.validate("86hj 7jr9+cv", mode).unwrap(),
+            "86HJ7JR9+CV");
+        assert_eq!(Geocode::Maidenhead.validate("en54CQ", mode).unwrap(),
+            "EN54cq");
+
+        // case sensitive alphabets are not normalized
+        let geocode = Geocode::Geohash36;
+        assert_eq!(geocode.validate("bdrd_C26BqH", mode).unwrap(),
+            "bdrdC26BqH");
+        assert_ne!(geocode.validate("BDRD", mode),
+            geocode.validate("bdrd", mode));
+    }
+
+    #[test]
+    fn strict() {
+        let mode = ValidationMode::Strict;
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.validate("dpc5u6", mode).unwrap(), "dpc5u6");
+        assert_eq!(geocode.validate("dpC5u6", mode),
+            Err(GeocodeError::InvalidCharacter { ch: 'C', index: 2 }));
+        assert!(geocode.validate(" dpc5u6", mode).is_err());
+
+        // codes must use canonical case
+        let geocode = Geocode::PlusCode;
+        assert_eq!(geocode.validate("86HJ7JR9+CV", mode).unwrap(),
+            "86HJ7JR9+CV");
+        assert_eq!(geocode.validate("86HJ7JR9+cV", mode),
+            Err(GeocodeError::InvalidCharacter { ch: 'c', index: 9 }));
+        assert!(geocode.validate("86HJ7JR9", mode).is_err());
+        assert_eq!(Geocode::Maidenhead.validate("EN54CQ", mode),
+            Err(GeocodeError::InvalidCharacter { ch: 'C', index: 4 }));
+    }
+}
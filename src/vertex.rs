@@ -0,0 +1,57 @@
+use crate::{Geocode, GeocodeError};
+
+// start and end vertices of a cell edge
+pub type Edge = ((f64, f64), (f64, f64));
+
+impl Geocode {
+    pub fn edges(&self, value: &str) -> Result<[Edge; 4], GeocodeError> {
+        // edges join consecutive vertices, ending at the first vertex
+        let vertices = self.vertices(value)?;
+        Ok([(vertices[0], vertices[1]), (vertices[1], vertices[2]),
+            (vertices[2], vertices[3]), (vertices[3], vertices[0])])
+    }
+
+    pub fn vertices(&self, value: &str)
+            -> Result<[(f64, f64); 4], GeocodeError> {
+        // native cell corners counter-clockwise from the minimum corner
+        let bbox = self.decode(value)?;
+        Ok([(bbox.min_x, bbox.min_y), (bbox.max_x, bbox.min_y),
+            (bbox.max_x, bbox.max_y), (bbox.min_x, bbox.max_y)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geocode, GeocodeError};
+
+    #[test]
+    fn edges() {
+        let geocode = Geocode::QuadTile;
+        let vertices = geocode.vertices("0302").unwrap();
+        let edges = geocode.edges("0302").unwrap();
+        for (i, (start, end)) in edges.iter().enumerate() {
+            assert_eq!(*start, vertices[i]);
+            assert_eq!(*end, vertices[(i + 1) % 4]);
+        }
+
+        assert!(geocode.edges("04").is_err());
+    }
+
+    #[test]
+    fn vertices() {
+        let geocode = Geocode::Geohash;
+        let bbox = geocode.decode("dpc5u6").unwrap();
+        let vertices = geocode.vertices("dpc5u6").unwrap();
+        assert_eq!(vertices[0], (bbox.min_x, bbox.min_y));
+        assert_eq!(vertices[2], (bbox.max_x, bbox.max_y));
+
+        // the shoelace formula is positive for counter-clockwise rings
+        let area: f64 = (0..4).map(|i| {
+            let ((x_a, y_a), (x_b, y_b)) = (vertices[i], vertices[(i + 1) % 4]);
+            x_a * y_b - x_b * y_a
+        }).sum::<f64>() / 2f64;
+        assert!((area - bbox.width() * bbox.height()).abs() < 1e-12);
+
+        assert_eq!(geocode.vertices(""), Err(GeocodeError::EmptyCode));
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(feature = "alloc")]
+use crate::GeocodeError;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[repr(C)]
+pub struct BoundingVolume {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+}
+
+impl BoundingVolume {
+    pub const fn new(min_x: f64, max_x: f64, min_y: f64, max_y: f64,
+            min_z: f64, max_z: f64) -> BoundingVolume {
+        BoundingVolume { min_x, max_x, min_y, max_y, min_z, max_z }
+    }
+
+    pub fn center(&self) -> (f64, f64, f64) {
+        ((self.min_x + self.max_x) / 2f64, (self.min_y + self.max_y) / 2f64,
+            (self.min_z + self.max_z) / 2f64)
+    }
+
+    pub const fn contains(&self, x: f64, y: f64, z: f64) -> bool {
+        x >= self.min_x && x <= self.max_x
+            && y >= self.min_y && y <= self.max_y
+            && z >= self.min_z && z <= self.max_z
+    }
+
+    pub const fn depth(&self) -> f64 {
+        self.max_z - self.min_z
+    }
+
+    pub const fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    pub const fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_axes(min: &[f64; 3], max: &[f64; 3]) -> BoundingVolume {
+        BoundingVolume::new(min[0], max[0], min[1], max[1], min[2], max[2])
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_axes(self) -> ([f64; 3], [f64; 3]) {
+        ([self.min_x, self.min_y, self.min_z],
+            [self.max_x, self.max_y, self.max_z])
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_volume(bounds: &BoundingVolume, char_bits: usize,
+        codes: &[char], value: &str) -> Result<BoundingVolume, GeocodeError> {
+    // check if code is valid
+    if value.is_empty() {
+        return Err(GeocodeError::EmptyCode);
+    }
+
+    // refine bounds by bisecting x, y, and z in turn
+    let (mut min, mut max) = bounds.to_axes();
+    let mut bits_total = 0;
+    for (index, c) in value.chars().enumerate() {
+        let hash_value = match codes.iter().position(|x| *x == c) {
+            Some(hash_value) => hash_value,
+            None => return Err(GeocodeError::InvalidCharacter {
+                ch: c, index }),
+        };
+
+        for i in (0..char_bits).rev() {
+            let axis = bits_total % 3;
+            let mid = (min[axis] + max[axis]) / 2f64;
+            if (hash_value >> i) & 1 == 1 {
+                min[axis] = mid;
+            } else {
+                max[axis] = mid;
+            }
+            bits_total += 1;
+        }
+    }
+
+    Ok(BoundingVolume::from_axes(&min, &max))
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_volume(bounds: &BoundingVolume, point: [f64; 3],
+        precision: usize, char_bits: usize, codes: &[char])
+        -> Result<String, GeocodeError> {
+    // check if coordinates and precision are valid
+    if !bounds.contains(point[0], point[1], point[2]) {
+        return Err(GeocodeError::CoordinateOutOfVolume { x: point[0],
+            y: point[1], z: point[2], bounds: *bounds });
+    } else if precision == 0 {
+        return Err(GeocodeError::InvalidPrecision { precision });
+    }
+
+    // compute code by bisecting x, y, and z in turn
+    let (mut min, mut max) = bounds.to_axes();
+    let mut out = String::with_capacity(precision);
+    let mut bits_total = 0;
+    for _ in 0..precision {
+        let mut hash_value = 0;
+        for _ in 0..char_bits {
+            let axis = bits_total % 3;
+            let mid = (min[axis] + max[axis]) / 2f64;
+            if point[axis] > mid {
+                hash_value = (hash_value << 1) + 1;
+                min[axis] = mid;
+            } else {
+                hash_value <<= 1;
+                max[axis] = mid;
+            }
+            bits_total += 1;
+        }
+
+        out.push(codes[hash_value]);
+    }
+
+    Ok(out)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{decode_volume, encode_volume, BoundingVolume};
+
+    #[test]
+    fn dimensions() {
+        let volume = BoundingVolume::new(-10.0, 30.0, 5.0, 15.0, 0.0, 100.0);
+        assert_eq!(volume.center(), (10.0, 10.0, 50.0));
+        assert_eq!(volume.width(), 40.0);
+        assert_eq!(volume.height(), 10.0);
+        assert_eq!(volume.depth(), 100.0);
+        assert!(volume.contains(-10.0, 15.0, 100.0));
+        assert!(!volume.contains(0.0, 10.0, -0.5));
+    }
+
+    #[test]
+    fn volume() {
+        let bounds = BoundingVolume::new(0.0, 8.0, 0.0, 8.0, 0.0, 8.0);
+        let codes = &['0', '1', '2', '3', '4', '5', '6', '7'];
+        assert_eq!(encode_volume(&bounds, [5.0, 1.0, 7.0], 2, 3, codes)
+            .unwrap(), "51");
+        assert_eq!(decode_volume(&bounds, 3, codes, "51").unwrap(),
+            BoundingVolume::new(4.0, 6.0, 0.0, 2.0, 6.0, 8.0));
+
+        assert!(encode_volume(&bounds, [9.0, 1.0, 7.0], 2, 3, codes)
+            .is_err());
+        assert!(encode_volume(&bounds, [5.0, 1.0, 7.0], 0, 3, codes)
+            .is_err());
+        assert!(decode_volume(&bounds, 3, codes, "").is_err());
+        assert!(decode_volume(&bounds, 3, codes, "58").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+
+use wasm_bindgen::prelude::*;
+
+// wasm-bindgen only exports c-style enums, so geocodes are mirrored here
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GeocodeType {
+    Geohash,
+    Geohash16,
+    Geohash36,
+    Maidenhead,
+    PlusCode,
+    QuadTile,
+}
+
+impl From<GeocodeType> for Geocode {
+    fn from(geocode_type: GeocodeType) -> Geocode {
+        match geocode_type {
+            GeocodeType::Geohash => Geocode::Geohash,
+            GeocodeType::Geohash16 => Geocode::Geohash16,
+            GeocodeType::Geohash36 => Geocode::Geohash36,
+            GeocodeType::Maidenhead => Geocode::Maidenhead,
+            GeocodeType::PlusCode => Geocode::PlusCode,
+            GeocodeType::QuadTile => Geocode::QuadTile,
+        }
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const BOUNDS_DOCS: &str = r#"
+// bounds are returned as [min_x, max_x, min_y, max_y] and centers as
+// [x, y, x_delta, y_delta] where deltas are half of the cell intervals
+"#;
+
+#[wasm_bindgen(js_name = coverBbox)]
+pub fn cover_bbox(geocode: GeocodeType, min_x: f64, max_x: f64, min_y: f64,
+        max_y: f64, precision: usize) -> Result<Vec<String>, JsError> {
+    let bbox = BoundingBox::new(min_x, max_x, min_y, max_y);
+    Geocode::from(geocode).cover_bbox(&bbox, precision).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = coverRadius)]
+pub fn cover_radius(geocode: GeocodeType, x: f64, y: f64, radius: f64,
+        precision: usize) -> Result<Vec<String>, JsError> {
+    Geocode::from(geocode).cover_radius(x, y, radius, precision)
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn decode(geocode: GeocodeType, value: &str)
+        -> Result<Vec<f64>, JsError> {
+    let bbox = Geocode::from(geocode).decode(value).map_err(to_js_error)?;
+    Ok(vec![bbox.min_x, bbox.max_x, bbox.min_y, bbox.max_y])
+}
+
+#[wasm_bindgen(js_name = decodeCenter)]
+pub fn decode_center(geocode: GeocodeType, value: &str)
+        -> Result<Vec<f64>, JsError> {
+    let (x, y, x_delta, y_delta) = Geocode::from(geocode)
+        .decode_center(value).map_err(to_js_error)?;
+    Ok(vec![x, y, x_delta, y_delta])
+}
+
+#[wasm_bindgen]
+pub fn encode(geocode: GeocodeType, x: f64, y: f64, precision: usize)
+        -> Result<String, JsError> {
+    Geocode::from(geocode).encode(x, y, precision).map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn neighbors(geocode: GeocodeType, value: &str)
+        -> Result<Vec<String>, JsError> {
+    Geocode::from(geocode).neighbors(value).map_err(to_js_error)
+}
+
+fn to_js_error(e: GeocodeError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode};
+    use super::GeocodeType;
+
+    // error paths construct javascript values, which are only available
+    // on wasm targets
+
+    #[test]
+    fn encode() {
+        let code = super::encode(GeocodeType::Geohash, -88.4, 44.266667, 6)
+            .unwrap();
+        assert_eq!(code, "dpc5u6");
+
+        let bbox = Geocode::Geohash.decode("dpc5u6").unwrap();
+        assert_eq!(super::decode(GeocodeType::Geohash, "dpc5u6").unwrap(),
+            vec![bbox.min_x, bbox.max_x, bbox.min_y, bbox.max_y]);
+
+        let (x, y) = bbox.center();
+        assert_eq!(super::decode_center(GeocodeType::Geohash, "dpc5u6")
+            .unwrap(), vec![x, y, bbox.width() / 2.0, bbox.height() / 2.0]);
+    }
+
+    #[test]
+    fn cover() {
+        let geocode = Geocode::QuadTile;
+        assert_eq!(super::neighbors(GeocodeType::QuadTile, "0302").unwrap(),
+            geocode.neighbors("0302").unwrap());
+
+        let bbox = BoundingBox::new(-89.0, -88.0, 44.0, 45.0);
+        assert_eq!(super::cover_bbox(GeocodeType::Geohash, -89.0, -88.0,
+            44.0, 45.0, 4).unwrap(),
+            Geocode::Geohash.cover_bbox(&bbox, 4).unwrap());
+        assert_eq!(super::cover_radius(GeocodeType::Geohash, -88.4,
+            44.266667, 1000.0, 6).unwrap(),
+            Geocode::Geohash.cover_radius(-88.4, 44.266667, 1000.0, 6)
+                .unwrap());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{CellSet, Geocode, GeocodeError};
+
+impl Geocode {
+    pub fn to_wkt(&self, value: &str) -> Result<String, GeocodeError> {
+        let ring = self.decode_ring(value)?;
+        Ok(format!("POLYGON({})", format_ring(&ring)))
+    }
+}
+
+impl CellSet {
+    pub fn to_wkt(&self) -> Result<String, GeocodeError> {
+        if self.is_empty() {
+            return Ok("MULTIPOLYGON EMPTY".to_string());
+        }
+
+        // each cell is a single ring polygon
+        let mut polygons = Vec::new();
+        for cell in self.iter() {
+            let ring = self.get_geocode().decode_ring(&cell)?;
+            polygons.push(format!("({})", format_ring(&ring)));
+        }
+
+        Ok(format!("MULTIPOLYGON({})", polygons.join(",")))
+    }
+}
+
+fn format_ring(ring: &[(f64, f64)]) -> String {
+    let points: Vec<String> = ring.iter()
+        .map(|(x, y)| format!("{} {}", x, y)).collect();
+    format!("({})", points.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CellSet, Geocode, GeocodeError};
+
+    #[test]
+    fn to_wkt() {
+        let geocode = Geocode::Geohash;
+        assert_eq!(geocode.to_wkt("0").unwrap(), "POLYGON((-180 -90,-135 -90,\
+            -135 -45,-180 -45,-180 -90))");
+        assert_eq!(geocode.to_wkt(""), Err(GeocodeError::EmptyCode));
+
+        let cell_set = CellSet::from_cells(geocode,
+            vec!["0".to_string(), "1".to_string()]).unwrap();
+        assert_eq!(cell_set.to_wkt().unwrap(), "MULTIPOLYGON(((-180 -90,\
+            -135 -90,-135 -45,-180 -45,-180 -90)),((-135 -90,-90 -90,\
+            -90 -45,-135 -45,-135 -90)))");
+        assert_eq!(CellSet::new(geocode).to_wkt().unwrap(),
+            "MULTIPOLYGON EMPTY");
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{BoundingBox, Geocode, GeocodeError};
+use crate::cover::cell_range;
+
+impl Geocode {
+    pub fn cover_bbox_ranges(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<Vec<(u64, u64)>, GeocodeError> {
+        let (bbox, west) = self.split_bbox(bbox, precision)?;
+        if precision > self.get_max_id_precision()? {
+            return Err(GeocodeError::InvalidPrecision { precision });
+        }
+
+        let mut ranges = self.bbox_ranges(&bbox, precision)?;
+        if let Some(west) = west {
+            ranges.extend(self.bbox_ranges(&west, precision)?);
+            ranges = merge_ranges(ranges);
+        }
+
+        Ok(ranges)
+    }
+
+    fn bbox_ranges(&self, bbox: &BoundingBox, precision: usize)
+            -> Result<Vec<(u64, u64)>, GeocodeError> {
+        let bounds = self.get_bounds();
+
+        // compute cell index ranges
+        let (x_interval, y_interval) = self.get_intervals(precision);
+        let upper = self.has_upper_edges();
+        let x_range = match cell_range(bbox.min_x, bbox.max_x,
+                bounds.min_x, bounds.max_x, x_interval, upper) {
+            Some((min, max)) => (min as u64, max as u64),
+            None => return Ok(Vec::new()),
+        };
+        let y_range = match cell_range(bbox.min_y, bbox.max_y,
+                bounds.min_y, bounds.max_y, y_interval, upper) {
+            Some((min, max)) => (min as u64, max as u64),
+            None => return Ok(Vec::new()),
+        };
+
+        // split the curve into dense index ranges and convert them to ids
+        let (char_bits, _) = self.get_id_parameters()?;
+        let bits = precision * char_bits;
+        let (x_bits, y_bits) = (bits - bits / 2, bits / 2);
+        let mut ranges = Vec::new();
+        let node = Node { prefix: 0, depth: 0,
+            x: (0, (1 << x_bits) - 1), y: (0, (1 << y_bits) - 1) };
+        node.decompose(x_range, y_range, bits, &mut ranges);
+
+        let shift = 64 - bits;
+        Ok(ranges.into_iter().map(|(start, end)| ((start << shift)
+            | precision as u64, (end << shift) | precision as u64)).collect())
+    }
+}
+
+// a block of the z-order curve sharing the leading depth bits of prefix
+struct Node {
+    prefix: u64,
+    depth: usize,
+    x: (u64, u64),
+    y: (u64, u64),
+}
+
+impl Node {
+    fn decompose(&self, x_range: (u64, u64), y_range: (u64, u64),
+            bits: usize, ranges: &mut Vec<(u64, u64)>) {
+        if self.x.1 < x_range.0 || self.x.0 > x_range.1
+                || self.y.1 < y_range.0 || self.y.0 > y_range.1 {
+            return;
+        }
+
+        // blocks within the query are contiguous on the curve, otherwise
+        // split on the next bit where even bits split x and odd bits y
+        if self.x.0 >= x_range.0 && self.x.1 <= x_range.1
+                && self.y.0 >= y_range.0 && self.y.1 <= y_range.1 {
+            let shift = bits - self.depth;
+            let start = self.prefix << shift;
+            let end = start + ((1 << shift) - 1);
+            match ranges.last_mut() {
+                Some(last) if last.1 + 1 == start => last.1 = end,
+                _ => ranges.push((start, end)),
+            }
+
+            return;
+        }
+
+        let (x, y) = (self.x, self.y);
+        let children = match self.depth % 2 {
+            0 => {
+                let mid = x.0 + ((x.1 - x.0 + 1) >> 1);
+                [((x.0, mid - 1), y), ((mid, x.1), y)]
+            },
+            _ => {
+                let mid = y.0 + ((y.1 - y.0 + 1) >> 1);
+                [(x, (y.0, mid - 1)), (x, (mid, y.1))]
+            },
+        };
+
+        for (i, (x, y)) in children.iter().enumerate() {
+            let child = Node { prefix: (self.prefix << 1) | i as u64,
+                depth: self.depth + 1, x: *x, y: *y };
+            child.decompose(x_range, y_range, bits, ranges);
+        }
+    }
+}
+
+pub(crate) fn merge_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) =>
+                last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoundingBox, Geocode, GeocodeError};
+
+    fn check_ranges(geocode: Geocode, bbox: &BoundingBox, precision: usize)
+            -> Vec<(u64, u64)> {
+        let ranges = geocode.cover_bbox_ranges(bbox, precision).unwrap();
+        let cells = geocode.cover_bbox(bbox, precision).unwrap();
+
+        // ranges are ordered, disjoint, and contain exactly the covering
+        assert!(ranges.windows(2).all(|x| x[0].1 < x[1].0));
+        for cell in cells.iter() {
+            let id = geocode.code_to_u64(cell).unwrap();
+            assert!(ranges.iter().any(|x| id >= x.0 && id <= x.1));
+        }
+
+        let (char_bits, _) = geocode.get_id_parameters().unwrap();
+        let shift = 64 - precision * char_bits;
+        let count: u64 = ranges.iter()
+            .map(|x| ((x.1 >> shift) - (x.0 >> shift)) + 1).sum();
+        assert_eq!(count as usize, cells.len());
+        ranges
+    }
+
+    #[test]
+    fn cover_bbox_ranges() {
+        // a query within a cell collapses into a single range
+        let geocode = Geocode::Geohash;
+        let cell = geocode.decode("dpc5").unwrap();
+        let bbox = BoundingBox::new(cell.min_x + 1e-6, cell.max_x - 1e-6,
+            cell.min_y + 1e-6, cell.max_y - 1e-6);
+        let ranges = check_ranges(geocode, &bbox, 6);
+        assert_eq!(ranges, vec![(geocode.code_to_u64("dpc500").unwrap(),
+            geocode.code_to_u64("dpc5zz").unwrap())]);
+
+        let bbox = BoundingBox::new(-88.45, -88.35, 44.24, 44.28);
+        for precision in 1..=7 {
+            check_ranges(geocode, &bbox, precision);
+        }
+
+        assert!(check_ranges(geocode, &bbox, 7).len()
+            < geocode.cover_bbox(&bbox, 7).unwrap().len());
+
+        let geocode = Geocode::QuadTile;
+        let bbox = BoundingBox::new(-9840642.99, -9740642.99,
+            5406802.68, 5506802.68);
+        for precision in 1..=12 {
+            check_ranges(geocode, &bbox, precision);
+        }
+
+        // antimeridian crossings merge both sides
+        let geocode = Geocode::Geohash16;
+        let bbox = BoundingBox::new(170.0, -170.0, -10.0, 10.0);
+        check_ranges(geocode, &bbox, 3);
+        assert!(geocode.cover_bbox_ranges(&BoundingBox::new(0.0, 1.0,
+            100.0, 110.0), 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let geocode = Geocode::Geohash;
+        let bbox = BoundingBox::new(-1.0, 1.0, -1.0, 1.0);
+        assert_eq!(geocode.cover_bbox_ranges(&bbox, 0),
+            Err(GeocodeError::InvalidPrecision { precision: 0 }));
+        assert_eq!(geocode.cover_bbox_ranges(&bbox, 13),
+            Err(GeocodeError::InvalidPrecision { precision: 13 }));
+        assert_eq!(geocode.cover_bbox_ranges(&BoundingBox::new(
+            -1.0, 1.0, 1.0, -1.0), 4), Err(GeocodeError::InvalidBoundingBox {
+            bounds: BoundingBox::new(-1.0, 1.0, 1.0, -1.0) }));
+        assert!(matches!(geocode.cover_bbox_ranges(&BoundingBox::new(
+            -89.0, -88.0, 44.0, f64::NAN), 3),
+            Err(GeocodeError::InvalidBoundingBox { .. })));
+        assert_eq!(Geocode::PlusCode.cover_bbox_ranges(&bbox, 4),
+            Err(GeocodeError::UnsupportedGeocode {
+                geocode: Geocode::PlusCode }));
+    }
+}